@@ -0,0 +1,696 @@
+use std::collections::HashSet;
+use std::ops::Range;
+
+use prog_lexer::{Token, TokenKind, TokenStream};
+use prog_parser::ast::*;
+use prog_parser::ASTNode;
+
+/// Prints the `program` in the canonical layout, re-inserting the comments of the token stream it was parsed from.
+///
+/// The stream is expected to be produced by [`prog_lexer::lex_with_comments`], otherwise the comments are lost.
+pub fn format<'src>(program: &Program<'src>, ts: &TokenStream<'src>) -> String {
+	let mut formatter = Formatter::new(ts);
+
+	for stmt in program.stmts.iter() {
+		formatter.item(stmt, Formatter::stmt);
+	}
+
+	formatter.comments_before(usize::MAX);
+	formatter.finish()
+}
+
+struct Formatter<'src> {
+	source: &'src str,
+	/// Comments which have not been printed yet, in the reverse order of appearance.
+	comments: Vec<Token<'src>>,
+	/// Start offsets of the block comments which are followed by code on the same line, and so are printed along with it.
+	attached: HashSet<usize>,
+	output: String,
+	indent: usize,
+	/// Byte offset in the source at which the last printed item ends.
+	last_end: usize,
+	/// Whether nothing has been printed in the current block yet.
+	block_start: bool,
+	/// Whether the next printed text has to be separated from the comment before it.
+	pending_space: bool
+}
+
+impl<'src> Formatter<'src> {
+	fn new(ts: &TokenStream<'src>) -> Self {
+		let source = ts.buffer().last().map_or("", |t| t.span().source());
+
+		let comments = ts
+			.buffer()
+			.iter()
+			.rev()
+			.filter(|t| t.kind() == TokenKind::Comment)
+			.cloned()
+			.collect();
+
+		let tokens = ts.buffer();
+
+		let attached = tokens
+			.iter()
+			.enumerate()
+			.filter(|(_, t)| t.kind() == TokenKind::Comment && !t.value().starts_with("//"))
+			.filter(|&(idx, t)| {
+				let next = tokens[idx + 1..]
+					.iter()
+					.find(|t| t.kind() != TokenKind::Comment);
+
+				next.is_some_and(|next| {
+					next.kind() != TokenKind::Eof
+						&& !source[t.position().end()..next.position().start()].contains('\n')
+				})
+			})
+			.map(|(_, t)| t.position().start())
+			.collect();
+
+		Self {
+			source,
+			comments,
+			attached,
+			output: String::new(),
+			indent: 0,
+			last_end: 0,
+			block_start: true,
+			pending_space: false
+		}
+	}
+
+	fn finish(mut self) -> String {
+		if !self.output.is_empty() {
+			self.output.push('\n');
+		}
+
+		self.output
+	}
+
+	fn push(&mut self, text: &str) {
+		if std::mem::take(&mut self.pending_space) && !text.starts_with([')', ']', ',']) {
+			self.output.push(' ');
+		}
+
+		self.output.push_str(text);
+	}
+
+	/// Starts a new line for the item at `start`, keeping a single blank line if the source had any before it.
+	fn line(&mut self, start: usize) {
+		let gap = self.source.get(self.last_end..start).unwrap_or_default();
+
+		if !self.block_start && gap.matches('\n').count() > 1 {
+			self.output.push('\n');
+		}
+
+		if !self.output.is_empty() {
+			self.output.push('\n');
+		}
+
+		for _ in 0..self.indent {
+			self.output.push('\t');
+		}
+
+		self.block_start = false;
+		self.pending_space = false;
+	}
+
+	fn comment(&mut self, comment: Token<'src>) {
+		// Single line comments include the line break which terminates them
+		let text = comment.value().trim_end();
+
+		self.push(text);
+		self.last_end = comment.position().start() + text.len();
+	}
+
+	/// Prints the comments which start before `offset` on their own lines.
+	///
+	/// Stops at the first attached comment, which is left for [`Self::inline_comments`] after the line is started.
+	fn comments_before(&mut self, offset: usize) {
+		while let Some(&comment) = self.comments.last() {
+			let start = comment.position().start();
+
+			if start >= offset || self.attached.contains(&start) {
+				break;
+			}
+
+			self.comments.pop();
+			self.line(start);
+			self.comment(comment);
+		}
+	}
+
+	/// Prints the comments which start before `offset` in the middle of the line, such as the ones inside of expressions.
+	///
+	/// Single line comments are followed by a line break, the line is then continued with an extra level of indentation.
+	fn inline_comments(&mut self, offset: usize) {
+		while let Some(&comment) = self.comments.last() {
+			if comment.position().start() >= offset {
+				break;
+			}
+
+			self.comments.pop();
+
+			if !self.output.is_empty() && !self.output.ends_with([' ', '\t', '\n', '(', '[']) {
+				self.output.push(' ');
+			}
+
+			self.comment(comment);
+
+			if !comment.value().starts_with("//") {
+				self.pending_space = true;
+				continue;
+			}
+
+			self.output.push('\n');
+
+			for _ in 0..=self.indent {
+				self.output.push('\t');
+			}
+		}
+	}
+
+	/// Prints the comments which are on the same line as the last printed item.
+	fn trailing_comments(&mut self) {
+		while let Some(&comment) = self.comments.last() {
+			let start = comment.position().start();
+
+			// Only separators may be between the item and the comment, otherwise it belongs to the code after them
+			let is_trailing = start >= self.last_end
+				&& !self.attached.contains(&start)
+				&& self.source[self.last_end..start]
+					.chars()
+					.all(|c| c == ',' || (c.is_whitespace() && c != '\n'));
+
+			if !is_trailing {
+				break;
+			}
+
+			self.comments.pop();
+			self.push(" ");
+			self.comment(comment);
+		}
+	}
+
+	/// Prints a keyword which may be followed by a comment on the same line, such as `do` or `then`.
+	fn keyword(&mut self, keyword: &dyn ASTNode<'src>) {
+		self.inline_comments(keyword.start());
+		self.push(keyword.value());
+		self.last_end = keyword.end();
+		self.trailing_comments();
+	}
+
+	/// Prints a node on its own line along with the comments surrounding it.
+	fn item<T>(&mut self, node: &T, print: impl FnOnce(&mut Self, &T))
+	where
+		T: ASTNode<'src>
+	{
+		self.comments_before(node.start());
+		self.line(node.start());
+		self.inline_comments(node.start());
+
+		print(self, node);
+
+		self.last_end = node.end();
+		self.trailing_comments();
+	}
+
+	/// Whether there are no comments left before `offset`.
+	fn no_comments_before(&self, offset: usize) -> bool {
+		self.comments
+			.last()
+			.is_none_or(|c| c.position().start() >= offset)
+	}
+
+	/// Whether a line comment starts in the `range` of the source.
+	fn has_line_comment(&self, range: Range<usize>) -> bool {
+		self.comments
+			.iter()
+			.any(|c| range.contains(&c.position().start()) && c.value().starts_with("//"))
+	}
+
+	/// Prints the indented items of a block, leaving the cursor on the line of the token at `end` which closes it.
+	fn block<T>(&mut self, items: &[T], end: usize, print: fn(&mut Self, &T))
+	where
+		T: ASTNode<'src>
+	{
+		self.indent += 1;
+		self.block_start = true;
+
+		for item in items {
+			self.item(item, print);
+		}
+
+		self.comments_before(end);
+		self.indent -= 1;
+
+		self.block_start = true;
+		self.line(end);
+		self.inline_comments(end);
+	}
+
+	/// Prints a delimited list of items, which is split across multiple lines
+	/// if the source had a line break after the `open` delimiter or there are line comments among the items.
+	///
+	/// If `trailing_func` is set, the last item is a function which stays on the line of the delimiters,
+	/// as in `should_panic(func() do ... end)`, so the line comments in its body are not taken into account.
+	fn delimited<T>(
+		&mut self,
+		open: &dyn ASTNode<'src>,
+		close: &dyn ASTNode<'src>,
+		padding: &str,
+		items: Vec<&T>,
+		trailing_func: bool,
+		print: fn(&mut Self, &T)
+	) where
+		T: ASTNode<'src>
+	{
+		self.push(open.value());
+
+		let (Some(first), Some(last)) = (items.first(), items.last()) else {
+			self.inline_comments(close.start());
+			self.push(close.value());
+			return;
+		};
+
+		// Line comments can only be kept in place if the items are on separate lines
+		let commented = if trailing_func {
+			self.has_line_comment(open.end()..last.start())
+				|| self.has_line_comment(last.end()..close.start())
+		} else {
+			self.has_line_comment(open.end()..close.start())
+		};
+
+		let multiline = self.source[open.end()..first.start()].contains('\n') || commented;
+
+		if !multiline {
+			self.push(padding);
+
+			for (idx, item) in items.iter().enumerate() {
+				if idx > 0 {
+					self.push(", ");
+				}
+
+				print(self, item);
+			}
+
+			self.push(padding);
+			self.inline_comments(close.start());
+			self.push(close.value());
+			return;
+		}
+
+		self.indent += 1;
+		self.block_start = true;
+
+		let count = items.len();
+
+		for (idx, item) in items.into_iter().enumerate() {
+			self.item(item, |f, item| {
+				print(f, item);
+
+				if idx + 1 < count {
+					f.push(",");
+				}
+			});
+		}
+
+		self.comments_before(close.start());
+		self.indent -= 1;
+
+		self.block_start = true;
+		self.line(close.start());
+		self.inline_comments(close.start());
+		self.push(close.value());
+	}
+
+	fn stmt(&mut self, stmt: &Stmt<'src>) {
+		match stmt {
+			Stmt::VarDefine(s) => self.var_define(s),
+
+			Stmt::VarAssign(s) => {
+				self.push(s.name.value());
+				self.push(" = ");
+				self.expr(&s.value);
+			}
+
+			Stmt::DoBlock(s) => self.do_block(s),
+
+			Stmt::Return(s) => {
+				self.push("return ");
+				self.expr(&s.value);
+			}
+
+			Stmt::Call(s) => self.call(s),
+
+			Stmt::WhileLoop(s) => {
+				self.push("while ");
+				self.expr(&s.cond);
+				self.push(" ");
+				self.do_block(&s.block);
+			}
+
+			Stmt::ForLoop(s) => {
+				self.push("for ");
+				self.push(s.var.value());
+
+				if let Some((_, value)) = &s.value {
+					self.push(", ");
+					self.push(value.value());
+				}
+
+				self.push(" in ");
+				self.expr(&s.iter);
+				self.push(" ");
+				self.do_block(&s.block);
+			}
+
+			Stmt::Break(_) => self.push("break"),
+			Stmt::Continue(_) => self.push("continue"),
+			Stmt::If(s) => self.if_cond(s),
+
+			Stmt::Throw(s) => {
+				self.push("throw ");
+				self.expr(&s.value);
+			}
+
+			Stmt::Try(s) => self.try_catch(s),
+
+			Stmt::ExprAssign(ExprAssign::IndexAssign(s)) => {
+				self.index_acc(&s.acc);
+				self.push(" = ");
+				self.expr(&s.value);
+			}
+
+			Stmt::ExprAssign(ExprAssign::FieldAssign(s)) => {
+				self.field_acc(&s.acc);
+				self.push(" = ");
+				self.expr(&s.value);
+			}
+
+			Stmt::ClassDef(s) => {
+				self.push("class ");
+				self.keyword(&s.name);
+
+				if let Some((_, parent)) = &s.parent {
+					self.push(" extends ");
+					self.expr(parent);
+				}
+
+				if s.fields.is_empty() && self.no_comments_before(s._end.start()) {
+					self.push(" end");
+					return;
+				}
+
+				self.block(&s.fields, s._end.start(), Self::var_define);
+				self.push("end");
+			}
+
+			// Invalid statements are kept as they were written
+			Stmt::Error(s) => self.push(s.span.value())
+		}
+	}
+
+	fn var_define(&mut self, stmt: &VarDefine<'src>) {
+		self.push("def ");
+		self.push(stmt.name().value());
+
+		if let VarDefine::WithValue { value, .. } = stmt {
+			self.push(" = ");
+			self.expr(value);
+		}
+	}
+
+	fn do_block(&mut self, block: &DoBlock<'src>) {
+		self.keyword(&block._do);
+
+		// Empty blocks are kept on the same line, as in `func() do end`
+		if block.stmts.is_empty() && self.no_comments_before(block._end.start()) {
+			self.push(" end");
+			return;
+		}
+
+		// So are the blocks of a single statement which were written on one line, as in `func(x) do return x end`
+		if let [stmt] = &block.stmts[..] {
+			let inline = !self.source[block._do.end()..block._end.start()].contains('\n')
+				&& !matches!(stmt, Stmt::If(_) | Stmt::Try(_) | Stmt::ClassDef(_))
+				&& self.no_comments_before(block._end.start());
+
+			if inline {
+				self.push(" ");
+				self.stmt(stmt);
+				self.push(" end");
+				return;
+			}
+		}
+
+		self.block(&block.stmts, block._end.start(), Self::stmt);
+		self.push("end");
+	}
+
+	fn if_cond(&mut self, stmt: &If<'src>) {
+		let mut ends = stmt
+			.b_elifs
+			.iter()
+			.map(|b| b._elseif.start())
+			.chain(stmt.b_else.iter().map(|b| b._else.start()))
+			.chain(std::iter::once(stmt._end.start()));
+
+		self.push("if ");
+		self.expr(&stmt.cond);
+		self.push(" ");
+		self.keyword(&stmt._then);
+		self.block(&stmt.stmts, ends.next().unwrap(), Self::stmt);
+
+		for b_elif in stmt.b_elifs.iter() {
+			self.push("elseif ");
+			self.expr(&b_elif.cond);
+			self.push(" ");
+			self.keyword(&b_elif._then);
+			self.block(&b_elif.stmts, ends.next().unwrap(), Self::stmt);
+		}
+
+		if let Some(b_else) = &stmt.b_else {
+			self.keyword(&b_else._else);
+			self.block(&b_else.stmts, ends.next().unwrap(), Self::stmt);
+		}
+
+		self.push("end");
+	}
+
+	fn try_catch(&mut self, stmt: &Try<'src>) {
+		let mut ends = stmt
+			.b_catch
+			.iter()
+			.map(|b| b._catch.start())
+			.chain(stmt.b_finally.iter().map(|b| b._finally.start()))
+			.chain(std::iter::once(stmt._end.start()));
+
+		self.keyword(&stmt._try);
+		self.block(&stmt.stmts, ends.next().unwrap(), Self::stmt);
+
+		if let Some(b_catch) = &stmt.b_catch {
+			self.push("catch ");
+			self.keyword(&b_catch.name);
+			self.block(&b_catch.stmts, ends.next().unwrap(), Self::stmt);
+		}
+
+		if let Some(b_finally) = &stmt.b_finally {
+			self.keyword(&b_finally._finally);
+			self.block(&b_finally.stmts, ends.next().unwrap(), Self::stmt);
+		}
+
+		self.push("end");
+	}
+
+	fn expr(&mut self, expr: &Expr<'src>) {
+		match expr {
+			Expr::Binary(e) => {
+				self.term(&e.lhs);
+				self.inline_comments(e.op.start());
+				self.push(" ");
+				self.push(e.op.value());
+				self.push(" ");
+				self.term(&e.rhs);
+			}
+
+			Expr::Unary(e) => {
+				match e.op.kind {
+					UnaryOpKind::Minus => self.push("-"),
+					UnaryOpKind::Not => self.push("not ")
+				}
+
+				self.term(&e.operand);
+			}
+
+			Expr::Term(t) => self.term(t)
+		}
+	}
+
+	fn term(&mut self, term: &Term<'src>) {
+		self.inline_comments(term.start());
+
+		match term {
+			Term::Expr(e) => self.expr(e),
+
+			Term::ParenExpr(e) => {
+				self.push("(");
+				self.expr(&e.expr);
+				self.inline_comments(e._rp.start());
+				self.push(")");
+			}
+
+			Term::Lit(t) => self.push(t.value()),
+
+			Term::InterpStr(t) => {
+				self.push(t.segments[0].value());
+
+				for (expr, segment) in t.exprs.iter().zip(&t.segments[1..]) {
+					self.expr(expr);
+					self.push(segment.value());
+				}
+			}
+
+			Term::Ident(t) => self.push(t.value()),
+
+			Term::Func(t) => {
+				let args = t.args.items().iter().map(|a| a.value()).collect::<Vec<_>>();
+
+				self.push("func(");
+				self.push(&args.join(", "));
+				self.push(") ");
+				self.do_block(&t.block);
+			}
+
+			Term::List(t) => {
+				let items = t.items.items();
+				let trailing_func = items.last().is_some_and(|item| is_func(item));
+
+				self.delimited(&t._lb, &t._rb, "", items, trailing_func, Self::expr);
+			}
+
+			Term::Obj(t) => {
+				let fields = t.fields.items();
+				self.delimited(&t._lb, &t._rb, " ", fields, false, Self::obj_entry);
+			}
+
+			Term::Extern(t) => {
+				self.push("extern ");
+				self.expr(&t.value);
+			}
+
+			Term::Call(t) => self.call(t),
+			Term::IndexAcc(t) => self.index_acc(t),
+			Term::FieldAcc(t) => self.field_acc(t)
+		}
+	}
+
+	fn obj_entry(&mut self, entry: &ObjEntry<'src>) {
+		self.inline_comments(entry.start());
+		self.push(entry.name.value());
+		self.push(" = ");
+		self.expr(&entry.value);
+	}
+
+	fn call(&mut self, call: &Call<'src>) {
+		self.term(&call.callee);
+
+		let args = call.args.items();
+		let trailing_func = args.last().is_some_and(|arg| is_func(arg));
+
+		self.delimited(&call._lp, &call._rp, "", args, trailing_func, Self::expr);
+	}
+
+	fn index_acc(&mut self, acc: &IndexAcc<'src>) {
+		self.term(&acc.list);
+		self.push("[");
+		self.expr(&acc.index);
+		self.inline_comments(acc._rb.start());
+		self.push("]");
+	}
+
+	fn field_acc(&mut self, acc: &FieldAcc<'src>) {
+		self.term(&acc.object);
+		self.push(".");
+		self.inline_comments(acc.field.start());
+		self.push(acc.field.value());
+	}
+}
+
+fn is_func(expr: &Expr) -> bool { matches!(expr, Expr::Term(Term::Func(_))) }
+
+#[cfg(test)]
+mod tests {
+	use std::path::Path;
+
+	use prog_parser::ParseStream;
+
+	fn fmt(source: &str) -> String {
+		let ts = prog_lexer::lex(source, "<stdin>").unwrap();
+		let program = ParseStream::new(&ts).parse::<super::Program>().unwrap();
+
+		let ts = prog_lexer::lex_with_comments(source, "<stdin>").unwrap();
+		super::format(&program, &ts)
+	}
+
+	#[test]
+	fn test_layout() {
+		let source = "def   f=func(a,b) do\nif a>b then return a-b\nelse return -(b  -a) end\nend\n\n\n\nf(1,2)";
+		let expected = "def f = func(a, b) do\n\tif a > b then\n\t\treturn a - b\n\telse\n\t\treturn -(b - a)\n\tend\nend\n\nf(1, 2)\n";
+
+		assert_eq!(fmt(source), expected);
+	}
+
+	#[test]
+	fn test_comments() {
+		let source =
+			"// header\nclass A // name\n  def a = [1, // one\n2]\n  /* b */ def b\nend\n// footer";
+		let expected = "// header\nclass A // name\n\tdef a = [\n\t\t1, // one\n\t\t2\n\t]\n\t/* b */ def b\nend\n// footer\n";
+
+		assert_eq!(fmt(source), expected);
+	}
+
+	#[test]
+	fn test_comments_before_items() {
+		let source = "def a = [1, /* one */ 2 /* two */]\ndef b = [\n1, /* one */ 2]\ndef c = {  /* c */ c = 3 }";
+		let expected = "def a = [1, /* one */ 2 /* two */]\ndef b = [\n\t1,\n\t/* one */ 2\n]\ndef c = { /* c */ c = 3 }\n";
+
+		assert_eq!(fmt(source), expected);
+		assert_eq!(fmt(expected), expected);
+	}
+
+	#[test]
+	fn test_inline_functions() {
+		let source = "def id = func(x) do  return x end\nshould_panic(func() do\n// fails\nid()\nend)\nwhile true do if id(true) then break end end";
+		let expected = "def id = func(x) do return x end\nshould_panic(func() do\n\t// fails\n\tid()\nend)\nwhile true do\n\tif id(true) then\n\t\tbreak\n\tend\nend\n";
+
+		assert_eq!(fmt(source), expected);
+		assert_eq!(fmt(expected), expected);
+	}
+
+	#[test]
+	fn test_comments_in_expressions() {
+		let source = "def a = 1 +  /* one */ 2\ndef b = 3 * // three\n4\nprint(a[ /* first */ 0], (a // paren\n))\nif a > /* zero */ 0 then end";
+		let expected = "def a = 1 + /* one */ 2\ndef b = 3 * // three\n\t4\nprint(\n\ta[/* first */ 0],\n\t(a // paren\n\t\t)\n)\nif a > /* zero */ 0 then\nend\n";
+
+		assert_eq!(fmt(source), expected);
+		assert_eq!(fmt(expected), expected);
+	}
+
+	#[test]
+	fn test_idempotence() {
+		let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+
+		for dir in ["examples", "tests/cases"] {
+			for entry in root.join(dir).read_dir().unwrap() {
+				let path = entry.unwrap().path();
+
+				if path.extension().is_none_or(|ext| ext != "prog") {
+					continue;
+				}
+
+				let formatted = fmt(&std::fs::read_to_string(&path).unwrap());
+				assert_eq!(fmt(&formatted), formatted, "{}", path.display());
+			}
+		}
+	}
+}
@@ -108,9 +108,12 @@ impl<'i> ArgList {
 				}
 				Arg::Optional(name, kind) => check_args(*kind, found_arg.kind(), name, true)?,
 				Arg::Variadic(name) => {
+					// Padding values must not end up in the variadic argument
+					let values = arguments.get(index..).unwrap_or_default();
+
 					result.insert(
 						String::from(name.to_owned()),
-						ParsedArg::Variadic(found_args[index..].to_vec())
+						ParsedArg::Variadic(values.to_vec())
 					);
 				}
 			};
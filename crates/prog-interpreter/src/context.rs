@@ -1,13 +1,15 @@
 use std::cell::{Ref, RefMut};
 use std::collections::HashMap;
+use std::fmt::{self, Write};
+use std::rc::Rc;
 
+use prog_utils::fmt::{Formatter, Printable};
+
+use crate::resolver::Binding;
 use crate::{Shared, Value};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct ContextFlags {
-	/// Is writing to console stdout allowed?
-	/// If `false`, any calls to `print` will only write to [`Interpreter::stdout`].
-	pub con_stdout_allowed: bool,
 	/// Are `import` calls allowed?
 	pub imports_allowed: bool,
 	/// Are `input` calls allowed?
@@ -19,7 +21,6 @@ pub struct ContextFlags {
 impl Default for ContextFlags {
 	fn default() -> Self {
 		Self {
-			con_stdout_allowed: true,
 			imports_allowed: true,
 			inputs_allowed: true,
 			externs_allowed: true
@@ -50,9 +51,14 @@ impl<'ast> Context<'ast> {
 		Shared::unwrap_or_clone(self.inner)
 	}
 
-	pub fn child(&self) -> Self {
+	pub fn child(&self) -> Self { self.child_with(Rc::default()) }
+
+	/// Child context whose variables are laid out in advance, none of them being defined yet.
+	pub(crate) fn child_with(&self, layout: Rc<Layout>) -> Self {
 		let mut ctx = ContextInner::new();
 		ctx.flags = self.inner().flags;
+		ctx.vars = vec![None; layout.len()];
+		ctx.layout = layout;
 		ctx.parent = Some(self.clone()); // Only the "reference" is cloned
 
 		Self {
@@ -60,67 +66,138 @@ impl<'ast> Context<'ast> {
 		}
 	}
 
+	/// Separate context with the same variables and parent as this one,
+	/// such as a frame for a single call of a function.
+	pub(crate) fn frame(&self) -> Self {
+		Self {
+			inner: Shared::new(self.inner().clone())
+		}
+	}
+
+	/// Context which this one has been created from with [`Context::child`].
+	pub fn parent(&self) -> Option<Self> { self.inner().parent.clone() }
+
 	pub fn swap(&mut self, other: Self) -> Self { std::mem::replace(self, other) }
 
 	pub fn swap_in_place(this: &mut Self, other: &mut Self) { std::mem::swap(this, other) }
 
+	/// Whether the variable is defined in the innermost context which declares it.
 	pub fn exists<N>(&self, name: N) -> bool
 	where
 		N: AsRef<str>
 	{
-		if self.inner().vars.contains_key(name.as_ref()) {
-			return true;
-		}
+		let inner = self.inner();
 
-		match self.inner().parent {
-			Some(ref p) => p.exists(name),
-			None => false
+		match inner.layout.slot(name.as_ref()) {
+			Some(slot) => inner.vars[slot].is_some(),
+			None => match inner.parent {
+				Some(ref p) => p.exists(name),
+				None => false
+			}
 		}
 	}
 
+	/// Value of the variable in the innermost context which declares it,
+	/// unless it has not been defined there yet.
 	pub fn get<N>(&self, name: N) -> Option<Value<'ast>>
 	where
 		Value<'ast>: Clone,
 		N: AsRef<str>
 	{
-		let name = name.as_ref();
+		let inner = self.inner();
 
-		if let Some(value) = self.inner().vars.get(name).cloned() {
-			return Some(value);
-		}
-
-		match self.inner().parent {
-			Some(ref p) => p.get(name),
-			None => None
+		match inner.layout.slot(name.as_ref()) {
+			Some(slot) => inner.vars[slot].clone(),
+			None => match inner.parent {
+				Some(ref p) => p.get(name),
+				None => None
+			}
 		}
 	}
 
+	/// Defines the variable in this context, returning its previous value.
 	pub fn insert<N>(&self, name: N, value: Value<'ast>) -> Option<Value<'ast>>
 	where
 		N: Into<String>
 	{
-		self.inner_mut().vars.insert(name.into(), value)
+		let mut inner = self.inner_mut();
+		let slot = inner.declare(name.into());
+
+		inner.vars[slot].replace(value)
 	}
 
+	/// Assigns to the variable in the innermost context which declares it,
+	/// returning its previous value, unless it has not been defined there yet.
 	pub fn update<N>(&self, name: &N, value: Value<'ast>) -> Option<Value<'ast>>
 	where
 		N: ToOwned<Owned = String>
 	{
-		use std::collections::hash_map::Entry;
+		let name = name.to_owned();
+		let mut inner = self.inner_mut();
+		let inner = &mut *inner;
+
+		match inner.layout.slot(&name) {
+			Some(slot) => inner.vars[slot]
+				.as_mut()
+				.map(|var| std::mem::replace(var, value)),
+			None => match inner.parent {
+				Some(ref p) => p.update(&name, value),
+				None => None
+			}
+		}
+	}
 
+	/// Declares the variables in this context, leaving the ones which are already declared as they are.
+	pub(crate) fn declare(&self, names: &[String]) {
 		let mut inner = self.inner_mut();
 
-		match inner.vars.entry(name.to_owned()) {
-			Entry::Occupied(mut e) => Some(e.insert(value)),
-			Entry::Vacant(_) => {
-				match inner.parent {
-					Some(ref p) => p.update(name, value),
-					None => None
-				}
-			}
+		for name in names {
+			inner.declare(name.to_owned());
 		}
 	}
 
+	/// Context `depth` levels above this one.
+	fn ancestor(&self, depth: usize) -> Self {
+		let mut ctx = self.clone();
+
+		for _ in 0..depth {
+			let parent = ctx
+				.parent()
+				.expect("Attempted to reach past the top-level context");
+			ctx = parent;
+		}
+
+		ctx
+	}
+
+	/// Value of the variable bound to the slot, unless it has not been defined yet.
+	pub(crate) fn get_at(&self, Binding { depth, slot }: Binding) -> Option<Value<'ast>> {
+		match depth {
+			0 => self.inner().vars[slot].clone(),
+			_ => self.ancestor(depth).inner().vars[slot].clone()
+		}
+	}
+
+	/// Assigns to the variable bound to the slot, returning its previous value,
+	/// unless it has not been defined yet.
+	pub(crate) fn set_at(
+		&self,
+		Binding { depth, slot }: Binding,
+		value: Value<'ast>
+	) -> Option<Value<'ast>> {
+		let ctx = self.ancestor(depth);
+		let mut inner = ctx.inner_mut();
+
+		inner.vars[slot]
+			.as_mut()
+			.map(|var| std::mem::replace(var, value))
+	}
+
+	/// Defines the variable of this context which is laid out at the slot.
+	pub(crate) fn define_at(&self, slot: usize, value: Value<'ast>) {
+		self.inner_mut().vars[slot] = Some(value);
+	}
+
 	pub(crate) fn get_extern<N>(&self, name: N) -> Option<Value<'ast>>
 	where
 		N: AsRef<str>
@@ -149,29 +226,136 @@ impl Default for Context<'_> {
 	fn default() -> Self { Self::new() }
 }
 
+impl Printable for Context<'_> {
+	fn fmt_short(&self, f: &mut Formatter) -> fmt::Result {
+		let inner = self.inner();
+
+		let mut names = inner.defined().map(|(name, _)| name).collect::<Vec<_>>();
+		names.sort();
+
+		write!(f, "[")?;
+		for (idx, name) in names.into_iter().enumerate() {
+			if idx > 0 {
+				write!(f, ", ")?;
+			}
+
+			write!(f, "{name}")?;
+		}
+		write!(f, "]")?;
+
+		match inner.parent {
+			Some(ref p) => {
+				write!(f, " -> ")?;
+				p.fmt_short(f)
+			}
+			None => Ok(())
+		}
+	}
+
+	fn fmt_pretty(&self, f: &mut Formatter) -> fmt::Result {
+		let mut depth = 0;
+		let mut current = Some(self.clone());
+
+		while let Some(ctx) = current {
+			let inner = ctx.inner();
+
+			let mut vars = inner.defined().collect::<Vec<_>>();
+			vars.sort_by_key(|(name, _)| *name);
+
+			writeln!(f, "scope #{depth} {{")?;
+			for (name, value) in vars {
+				writeln!(f, "\t{name} = {value}")?;
+			}
+			writeln!(f, "}}")?;
+
+			depth += 1;
+			current = inner.parent.clone();
+		}
+
+		Ok(())
+	}
+
+	fn fmt_full(&self, f: &mut Formatter) -> fmt::Result { write!(f, "{:#?}", self.inner()) }
+}
+
 #[derive(Debug, Clone)]
 pub struct ContextInner<'ast> {
 	pub flags: ContextFlags,
 
-	vars: HashMap<String, Value<'ast>>,
+	layout: Rc<Layout>,
+	/// Values of the variables by their slots, missing for the ones which have not been defined yet.
+	vars: Vec<Option<Value<'ast>>>,
 	externs: HashMap<String, Value<'ast>>,
 
 	parent: Option<Context<'ast>>
 }
 
-impl ContextInner<'_> {
+impl<'ast> ContextInner<'ast> {
 	pub fn new() -> Self {
 		Self {
 			flags: ContextFlags::default(),
 
-			vars: HashMap::new(),
+			layout: Rc::default(),
+			vars: vec![],
 			externs: HashMap::new(),
 
 			parent: None
 		}
 	}
+
+	/// Slot of the variable, declaring it if it is not declared yet.
+	fn declare(&mut self, name: String) -> usize {
+		if let Some(slot) = self.layout.slot(&name) {
+			return slot;
+		}
+
+		self.vars.push(None);
+		Rc::make_mut(&mut self.layout).declare(name)
+	}
+
+	/// Names and values of the variables which have been defined.
+	fn defined(&self) -> impl Iterator<Item = (&String, &Value<'ast>)> {
+		let names = self.layout.names.iter();
+		names
+			.zip(&self.vars)
+			.filter_map(|(name, value)| Some((name, value.as_ref()?)))
+	}
+
+	/// Variables of the context, in the order of their slots.
+	pub(crate) fn layout(&self) -> &Layout { &self.layout }
+
+	/// Whether the variable in the slot has been defined.
+	pub(crate) fn is_defined(&self, slot: usize) -> bool { self.vars[slot].is_some() }
 }
 
 impl Default for ContextInner<'_> {
 	fn default() -> Self { Self::new() }
 }
+
+/// Names of the variables of a scope, in the order of their slots.
+///
+/// Scopes of a program are laid out before it is evaluated, see [`crate::resolver`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Layout {
+	names: Vec<String>,
+	slots: HashMap<String, usize>
+}
+
+impl Layout {
+	pub(crate) fn len(&self) -> usize { self.names.len() }
+
+	pub(crate) fn slot(&self, name: &str) -> Option<usize> { self.slots.get(name).copied() }
+
+	/// Slot of the variable, adding it after the others if it is not declared yet.
+	pub(crate) fn declare(&mut self, name: String) -> usize {
+		if let Some(slot) = self.slot(&name) {
+			return slot;
+		}
+
+		self.slots.insert(name.clone(), self.names.len());
+		self.names.push(name);
+		self.names.len() - 1
+	}
+
+	pub(crate) fn names(&self) -> &[String] { &self.names }
+}
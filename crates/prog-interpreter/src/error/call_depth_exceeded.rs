@@ -0,0 +1,28 @@
+use ariadne::{Fmt, Label};
+use prog_utils::pretty_errors::{color_generator, AriadneCompatible, Span};
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CallDepthExceeded {
+	/// Maximum number of nested calls allowed by the interpreter.
+	pub(crate) max_depth: usize
+}
+
+impl<'s> AriadneCompatible<'s> for CallDepthExceeded {
+	fn message(&self) -> &'static str { "maximum call depth exceeded" }
+
+	fn note(&self) -> Option<&str> {
+		Some("this is usually caused by a recursive function which never reaches its base case")
+	}
+
+	fn labels(&self, span: Span<'s>) -> Vec<Label<Span<'s>>> {
+		let color = color_generator().next();
+
+		vec![Label::new(span)
+			.with_message(format!(
+				"this call exceeds the maximum depth of {} nested calls",
+				self.max_depth.fg(color)
+			))
+			.with_color(color)]
+	}
+}
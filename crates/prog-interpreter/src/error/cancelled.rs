@@ -0,0 +1,18 @@
+use ariadne::Label;
+use prog_utils::pretty_errors::{color_generator, AriadneCompatible, Span};
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Cancelled;
+
+impl<'s> AriadneCompatible<'s> for Cancelled {
+	fn message(&self) -> &'static str { "evaluation cancelled" }
+
+	fn labels(&self, span: Span<'s>) -> Vec<Label<Span<'s>>> {
+		let color = color_generator().next();
+
+		vec![Label::new(span)
+			.with_message("evaluation was cancelled here")
+			.with_color(color)]
+	}
+}
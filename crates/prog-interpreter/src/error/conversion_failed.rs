@@ -0,0 +1,65 @@
+use ariadne::{Fmt, Label};
+use prog_utils::pretty_errors::{color_generator, AriadneCompatible, Span};
+
+use crate::ValueKind;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ConversionFailed {
+	/// Description of the value that the conversion expected.
+	pub(crate) expected: String,
+	pub(crate) found: ValueKind,
+	/// Location of the mismatched value inside of the converted one, e.g. `.points[0].x`.
+	pub(crate) path: String
+}
+
+impl ConversionFailed {
+	pub fn new<E>(expected: E, found: ValueKind) -> Self
+	where
+		E: Into<String>
+	{
+		Self {
+			expected: expected.into(),
+			found,
+			path: String::new()
+		}
+	}
+
+	/// Marks the mismatch as occurring inside of the field `name`.
+	pub fn in_field(mut self, name: &str) -> Self {
+		self.path.insert_str(0, &format!(".{name}"));
+		self
+	}
+
+	/// Marks the mismatch as occurring inside of the item at `index`.
+	pub fn in_index(mut self, index: usize) -> Self {
+		self.path.insert_str(0, &format!("[{index}]"));
+		self
+	}
+}
+
+impl<'s> AriadneCompatible<'s> for ConversionFailed {
+	fn message(&self) -> &'static str { "value conversion failed" }
+
+	fn labels(&self, span: Span<'s>) -> Vec<ariadne::Label<Span<'s>>> {
+		let mut colors = color_generator();
+
+		let color_expected = colors.next();
+		let color_found = colors.next();
+		let color_path = colors.next();
+
+		let location = if self.path.is_empty() {
+			String::new()
+		} else {
+			format!(" at `{}`", (&self.path).fg(color_path))
+		};
+
+		vec![Label::new(span)
+			.with_message(format!(
+				"expected a value of type `{}`, found `{}`{location}",
+				(&self.expected).fg(color_expected),
+				self.found.fg(color_found)
+			))
+			.with_color(colors.next())]
+	}
+}
@@ -0,0 +1,26 @@
+use std::time::Duration;
+
+use ariadne::{Fmt, Label};
+use prog_utils::pretty_errors::{color_generator, AriadneCompatible, Span};
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DeadlineExceeded {
+	/// Time which the evaluation was allowed to run for.
+	pub(crate) timeout: Duration
+}
+
+impl<'s> AriadneCompatible<'s> for DeadlineExceeded {
+	fn message(&self) -> &'static str { "deadline exceeded" }
+
+	fn labels(&self, span: Span<'s>) -> Vec<Label<Span<'s>>> {
+		let color = color_generator().next();
+
+		vec![Label::new(span)
+			.with_message(format!(
+				"evaluation was stopped here, after running for {}",
+				format!("{:?}", self.timeout).fg(color)
+			))
+			.with_color(color)]
+	}
+}
@@ -0,0 +1,32 @@
+use ariadne::{Fmt, Label};
+
+use prog_utils::pretty_errors::{color_generator, AriadneCompatible, Span};
+
+use crate::ValueKind;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ExprNotIterable(pub(crate) ValueKind);
+
+impl<'s> AriadneCompatible<'s> for ExprNotIterable {
+	fn message(&self) -> &'static str { "expression is not iterable" }
+
+	fn note(&self) -> Option<&str> {
+		Some(
+			"lists, strings, objects and class instances with a `next` method can be iterated over"
+		)
+	}
+
+	fn labels(&self, span: Span<'s>) -> Vec<Label<Span<'s>>> {
+		let mut colors = color_generator();
+
+		let color_found = colors.next();
+
+		vec![Label::new(span)
+			.with_message(format!(
+				"expected an iterable expression, found `{}`",
+				self.0.fg(color_found)
+			))
+			.with_color(color_found)]
+	}
+}
@@ -0,0 +1,28 @@
+use ariadne::{Fmt, Label};
+use prog_utils::pretty_errors::{color_generator, AriadneCompatible, Span};
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FuelExhausted {
+	/// Fuel which the evaluation started with.
+	pub(crate) fuel: u64
+}
+
+impl<'s> AriadneCompatible<'s> for FuelExhausted {
+	fn message(&self) -> &'static str { "fuel exhausted" }
+
+	fn note(&self) -> Option<&str> {
+		Some("every evaluated statement and expression consumes a unit of fuel")
+	}
+
+	fn labels(&self, span: Span<'s>) -> Vec<Label<Span<'s>>> {
+		let color = color_generator().next();
+
+		vec![Label::new(span)
+			.with_message(format!(
+				"evaluation ran out of fuel here, after {} steps",
+				self.fuel.fg(color)
+			))
+			.with_color(color)]
+	}
+}
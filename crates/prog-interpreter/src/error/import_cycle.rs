@@ -0,0 +1,36 @@
+use ariadne::{Fmt, Label};
+use prog_utils::pretty_errors::{color_generator, AriadneCompatible, Span};
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ImportCycle {
+	/// Paths of the modules forming the cycle, in import order.
+	/// The first and the last entries refer to the same module.
+	pub(crate) chain: Vec<String>
+}
+
+impl<'s> AriadneCompatible<'s> for ImportCycle {
+	fn message(&self) -> &'static str { "import cycle detected" }
+
+	fn labels(&self, span: Span<'s>) -> Vec<Label<Span<'s>>> {
+		let mut colors = color_generator();
+
+		let color_module = colors.next();
+		let color_chain = colors.next();
+
+		let module = self.chain.last().map(String::as_str).unwrap_or_default();
+		let chain = self
+			.chain
+			.iter()
+			.map(|m| format!("`{}`", m.fg(color_chain)))
+			.collect::<Vec<_>>()
+			.join(" -> ");
+
+		vec![Label::new(span)
+			.with_message(format!(
+				"importing `{}` here creates a cycle: {chain}",
+				module.fg(color_module)
+			))
+			.with_color(color_module)]
+	}
+}
@@ -0,0 +1,23 @@
+use ariadne::{Fmt, Label};
+use prog_utils::pretty_errors::{color_generator, AriadneCompatible, Span};
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct InvalidFile(pub(crate) String);
+
+impl<'s> AriadneCompatible<'s> for InvalidFile {
+	fn message(&self) -> &'static str { "invalid file" }
+
+	fn labels(&self, span: Span<'s>) -> Vec<Label<Span<'s>>> {
+		let mut colors = color_generator();
+
+		let color_path = colors.next();
+
+		vec![Label::new(span)
+			.with_message(format!(
+				"file `{}` does not exist or is invalid",
+				(&self.0).fg(color_path)
+			))
+			.with_color(color_path)]
+	}
+}
@@ -0,0 +1,23 @@
+use ariadne::{Fmt, Label};
+use prog_utils::pretty_errors::{color_generator, AriadneCompatible, Span};
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct IoFailed(pub(crate) String);
+
+impl<'s> AriadneCompatible<'s> for IoFailed {
+	fn message(&self) -> &'static str { "I/O failed" }
+
+	fn labels(&self, span: Span<'s>) -> Vec<Label<Span<'s>>> {
+		let mut colors = color_generator();
+
+		let color_error = colors.next();
+
+		vec![Label::new(span)
+			.with_message(format!(
+				"I/O operation failed: {}",
+				(&self.0).fg(color_error)
+			))
+			.with_color(color_error)]
+	}
+}
@@ -0,0 +1,30 @@
+use ariadne::{Fmt, Label};
+use prog_utils::pretty_errors::{color_generator, AriadneCompatible, Span};
+
+use crate::ValueKind;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct MethodDoesntExist {
+	pub(crate) kind: ValueKind,
+	pub(crate) method_name: String
+}
+
+impl<'s> AriadneCompatible<'s> for MethodDoesntExist {
+	fn message(&self) -> &'static str { "method doesn't exist" }
+
+	fn labels(&self, span: Span<'s>) -> Vec<Label<Span<'s>>> {
+		let mut colors = color_generator();
+
+		let color_method = colors.next();
+		let color_kind = colors.next();
+
+		vec![Label::new(span)
+			.with_message(format!(
+				"values of type `{}` do not have a method `{}`",
+				self.kind.fg(color_kind),
+				(&self.method_name).fg(color_method)
+			))
+			.with_color(color_method)]
+	}
+}
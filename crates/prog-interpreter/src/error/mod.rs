@@ -2,40 +2,76 @@ mod arg_count_mismatch;
 mod arg_type_mismatch;
 mod assertion_eq_failed;
 mod assertion_failed;
+mod call_depth_exceeded;
+mod cancelled;
 mod cannot_index_expr;
 mod class_field_redef;
 mod class_fn_reassign;
+mod conversion_failed;
 mod ctx_disallowed;
+mod deadline_exceeded;
 mod expr_not_assignable;
 mod expr_not_callable;
+mod expr_not_iterable;
 mod field_doesnt_exist;
+mod fuel_exhausted;
+mod import_cycle;
 mod invalid_class_construction;
 mod invalid_extern;
+mod invalid_file;
 mod invalid_index;
+mod invalid_parent_class;
+mod io_failed;
+mod method_doesnt_exist;
+mod native_fn_failed;
 mod obj_entry_redef;
+mod size_limit_exceeded;
+mod special_method_failed;
+mod thrown;
 mod unimplemented;
 mod var_doesnt_exist;
+mod var_redef;
+mod var_used_before_def;
 
 pub use arg_count_mismatch::ArgCountMismatch;
 pub use arg_type_mismatch::ArgTypeMismatch;
 pub use assertion_eq_failed::AssertionEqFailed;
 pub use assertion_failed::AssertionFailed;
+pub use call_depth_exceeded::CallDepthExceeded;
+pub use cancelled::Cancelled;
 pub use cannot_index_expr::CannotIndexExpr;
 pub use class_field_redef::ClassFieldRedef;
 pub use class_fn_reassign::ClassFnReassign;
+pub use conversion_failed::ConversionFailed;
 pub use ctx_disallowed::CtxDisallowed;
+pub use deadline_exceeded::DeadlineExceeded;
 pub use expr_not_assignable::ExprNotAssignable;
 pub use expr_not_callable::ExprNotCallable;
+pub use expr_not_iterable::ExprNotIterable;
 pub use field_doesnt_exist::FieldDoesntExist;
+pub use fuel_exhausted::FuelExhausted;
+pub use import_cycle::ImportCycle;
 pub use invalid_class_construction::InvalidClassConstruction;
 pub use invalid_extern::InvalidExtern;
+pub use invalid_file::InvalidFile;
 pub use invalid_index::InvalidIndex;
+pub use invalid_parent_class::InvalidParentClass;
+pub use io_failed::IoFailed;
+pub use method_doesnt_exist::MethodDoesntExist;
+pub use native_fn_failed::NativeFnFailed;
 pub use obj_entry_redef::ObjEntryRedef;
+pub use size_limit_exceeded::SizeLimitExceeded;
+pub use special_method_failed::SpecialMethodFailed;
+pub use thrown::Thrown;
 pub use unimplemented::Unimplemented;
 pub use var_doesnt_exist::VarDoesntExist;
+pub use var_redef::VarRedef;
+pub use var_used_before_def::VarUsedBeforeDef;
 
 use prog_utils::pretty_errors::{PrettyError, PrettyErrorKind};
 
+use crate::Value;
+
 pub type InterpretError<'s> = PrettyError<'s, InterpretErrorKind<'s>>;
 
 #[derive(Debug, Clone, prog_macros::AriadneCompatible)]
@@ -45,19 +81,108 @@ pub enum InterpretErrorKind<'s> {
 	ArgTypeMismatch(ArgTypeMismatch),
 	AssertionEqFailed(AssertionEqFailed<'s>),
 	AssertionFailed(AssertionFailed),
+	CallDepthExceeded(CallDepthExceeded),
+	Cancelled(Cancelled),
 	CannotIndexExpr(CannotIndexExpr),
 	ClassFieldRedef(ClassFieldRedef<'s>),
 	ClassFnReassign(ClassFnReassign),
+	ConversionFailed(ConversionFailed),
 	CtxDisallowed(CtxDisallowed),
+	DeadlineExceeded(DeadlineExceeded),
 	ExprNotAssignable(ExprNotAssignable),
 	ExprNotCallable(ExprNotCallable),
+	ExprNotIterable(ExprNotIterable),
 	FieldDoesntExist(FieldDoesntExist),
+	FuelExhausted(FuelExhausted),
+	ImportCycle(ImportCycle),
 	InvalidClassConstruction(InvalidClassConstruction),
 	InvalidExtern(InvalidExtern),
+	InvalidFile(InvalidFile),
 	InvalidIndex(InvalidIndex<'s>),
+	InvalidParentClass(InvalidParentClass),
+	IoFailed(IoFailed),
+	LexFailed(prog_lexer::LexErrorKind),
+	MethodDoesntExist(MethodDoesntExist),
+	NativeFnFailed(NativeFnFailed),
 	ObjEntryRedef(ObjEntryRedef<'s>),
+	ParseFailed(prog_parser::ParseErrorKind),
+	SizeLimitExceeded(SizeLimitExceeded),
+	SpecialMethodFailed(SpecialMethodFailed<'s>),
+	Thrown(Thrown<'s>),
 	Unimplemented(Unimplemented),
-	VarDoesntExist(VarDoesntExist)
+	VarDoesntExist(VarDoesntExist),
+	VarRedef(VarRedef<'s>),
+	VarUsedBeforeDef(VarUsedBeforeDef<'s>)
 }
 
 impl<'s> PrettyErrorKind<'s> for InterpretErrorKind<'s> {}
+
+impl<'s> InterpretErrorKind<'s> {
+	/// Name of the error kind, as exposed to the programs which catch it.
+	pub fn name(&self) -> &'static str {
+		match self {
+			Self::ArgCountMismatch(_) => "ArgCountMismatch",
+			Self::ArgTypeMismatch(_) => "ArgTypeMismatch",
+			Self::AssertionEqFailed(_) => "AssertionEqFailed",
+			Self::AssertionFailed(_) => "AssertionFailed",
+			Self::CallDepthExceeded(_) => "CallDepthExceeded",
+			Self::Cancelled(_) => "Cancelled",
+			Self::CannotIndexExpr(_) => "CannotIndexExpr",
+			Self::ClassFieldRedef(_) => "ClassFieldRedef",
+			Self::ClassFnReassign(_) => "ClassFnReassign",
+			Self::ConversionFailed(_) => "ConversionFailed",
+			Self::CtxDisallowed(_) => "CtxDisallowed",
+			Self::DeadlineExceeded(_) => "DeadlineExceeded",
+			Self::ExprNotAssignable(_) => "ExprNotAssignable",
+			Self::ExprNotCallable(_) => "ExprNotCallable",
+			Self::ExprNotIterable(_) => "ExprNotIterable",
+			Self::FieldDoesntExist(_) => "FieldDoesntExist",
+			Self::FuelExhausted(_) => "FuelExhausted",
+			Self::ImportCycle(_) => "ImportCycle",
+			Self::InvalidClassConstruction(_) => "InvalidClassConstruction",
+			Self::InvalidExtern(_) => "InvalidExtern",
+			Self::InvalidFile(_) => "InvalidFile",
+			Self::InvalidIndex(_) => "InvalidIndex",
+			Self::InvalidParentClass(_) => "InvalidParentClass",
+			Self::IoFailed(_) => "IoFailed",
+			Self::LexFailed(_) => "LexFailed",
+			Self::MethodDoesntExist(_) => "MethodDoesntExist",
+			Self::NativeFnFailed(_) => "NativeFnFailed",
+			Self::ObjEntryRedef(_) => "ObjEntryRedef",
+			Self::ParseFailed(_) => "ParseFailed",
+			Self::SizeLimitExceeded(_) => "SizeLimitExceeded",
+			Self::SpecialMethodFailed(_) => "SpecialMethodFailed",
+			Self::Thrown(_) => "Thrown",
+			Self::Unimplemented(_) => "Unimplemented",
+			Self::VarDoesntExist(_) => "VarDoesntExist",
+			Self::VarRedef(_) => "VarRedef",
+			Self::VarUsedBeforeDef(_) => "VarUsedBeforeDef"
+		}
+	}
+
+	/// Whether the error can be caught by `try ... catch ... end`.
+	///
+	/// Errors in the source code itself are not recoverable, even when raised by a special method.
+	/// Exceeding the resource limits is only reported to the host, so that scripts cannot evade them.
+	pub fn is_catchable(&self) -> bool {
+		match self {
+			Self::LexFailed(_) | Self::ParseFailed(_) => false,
+			Self::VarRedef(_) | Self::VarUsedBeforeDef(_) => false,
+			Self::Cancelled(_)
+			| Self::DeadlineExceeded(_)
+			| Self::FuelExhausted(_)
+			| Self::SizeLimitExceeded(_) => false,
+			Self::SpecialMethodFailed(e) => e.error.kind.is_catchable(),
+			_ => true
+		}
+	}
+
+	/// Value thrown by `throw`, looking through the special methods which propagated it.
+	pub fn thrown(&self) -> Option<&Value<'s>> {
+		match self {
+			Self::Thrown(e) => Some(&e.0),
+			Self::SpecialMethodFailed(e) => e.error.kind.thrown(),
+			_ => None
+		}
+	}
+}
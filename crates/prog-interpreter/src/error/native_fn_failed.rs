@@ -0,0 +1,21 @@
+use ariadne::{Fmt, Label};
+use prog_utils::pretty_errors::{color_generator, AriadneCompatible, Span};
+
+/// Error reported by a function registered through [`Interpreter::register_fn`](crate::Interpreter::register_fn).
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct NativeFnFailed(pub(crate) String);
+
+impl<'s> AriadneCompatible<'s> for NativeFnFailed {
+	fn message(&self) -> &'static str { "native function failed" }
+
+	fn labels(&self, span: Span<'s>) -> Vec<Label<Span<'s>>> {
+		let mut colors = color_generator();
+
+		let color_message = colors.next();
+
+		vec![Label::new(span)
+			.with_message((&self.0).fg(color_message))
+			.with_color(color_message)]
+	}
+}
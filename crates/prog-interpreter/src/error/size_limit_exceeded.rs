@@ -0,0 +1,38 @@
+use ariadne::{Fmt, Label};
+use prog_utils::pretty_errors::{color_generator, AriadneCompatible, Span};
+
+use crate::ValueKind;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SizeLimitExceeded {
+	pub(crate) kind: ValueKind,
+	/// Number of items of the list or the object, or bytes of the string.
+	pub(crate) size: usize,
+	pub(crate) max_size: usize
+}
+
+impl<'s> AriadneCompatible<'s> for SizeLimitExceeded {
+	fn message(&self) -> &'static str { "size limit exceeded" }
+
+	fn labels(&self, span: Span<'s>) -> Vec<Label<Span<'s>>> {
+		let mut colors = color_generator();
+
+		let color_size = colors.next();
+		let color_max = colors.next();
+
+		let unit = match self.kind {
+			ValueKind::Str => "bytes",
+			_ => "items"
+		};
+
+		vec![Label::new(span)
+			.with_message(format!(
+				"{} of {} {unit} exceeds the limit of {}",
+				self.kind,
+				self.size.fg(color_size),
+				self.max_size.fg(color_max)
+			))
+			.with_color(color_size)]
+	}
+}
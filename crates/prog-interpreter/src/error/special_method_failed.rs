@@ -0,0 +1,53 @@
+use ariadne::{Fmt, Label};
+
+use prog_utils::pretty_errors::{color_generator, AriadneCompatible, Span};
+
+use crate::InterpretError;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SpecialMethodFailed<'s> {
+	pub(crate) method: String,
+	/// Head of the method definition, `func(...)`.
+	pub(crate) def: Span<'s>,
+	/// Error raised by the method.
+	pub(crate) error: Box<InterpretError<'s>>
+}
+
+impl<'s> AriadneCompatible<'s> for SpecialMethodFailed<'s> {
+	fn message(&self) -> &'static str { self.error.kind.message() }
+
+	fn note(&self) -> Option<&str> { self.error.kind.note() }
+
+	fn labels(&self, span: Span<'s>) -> Vec<Label<Span<'s>>> {
+		let mut colors = color_generator();
+
+		let color_site = colors.next();
+		let color_def = colors.next();
+
+		let mut labels = vec![Label::new(span)
+			.with_message(format!(
+				"in `{}`, called by this operation",
+				(&self.method).fg(color_site)
+			))
+			.with_color(color_site)];
+
+		// Reports are only able to show the file the error occurred in
+		if self.def.file() == span.file() {
+			labels.push(
+				Label::new(self.def)
+					.with_message(format!(
+						"`{}` is defined here",
+						(&self.method).fg(color_def)
+					))
+					.with_color(color_def)
+			);
+		}
+
+		if self.error.span.file() == span.file() {
+			labels.extend(self.error.kind.labels(self.error.span));
+		}
+
+		labels
+	}
+}
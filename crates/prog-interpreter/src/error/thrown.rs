@@ -0,0 +1,25 @@
+use ariadne::{Fmt, Label};
+use prog_utils::pretty_errors::{color_generator, AriadneCompatible, Span};
+
+use crate::Value;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Thrown<'s>(pub(crate) Value<'s>);
+
+impl<'s> AriadneCompatible<'s> for Thrown<'s> {
+	fn message(&self) -> &'static str { "uncaught exception" }
+
+	fn note(&self) -> Option<&str> {
+		Some("thrown values can be caught with `try ... catch <name> ... end`")
+	}
+
+	fn labels(&self, span: Span<'s>) -> Vec<Label<Span<'s>>> {
+		let mut colors = color_generator();
+		let color = colors.next();
+
+		vec![Label::new(span)
+			.with_message(format!("`{:#}` was thrown here", (&self.0).fg(color)))
+			.with_color(color)]
+	}
+}
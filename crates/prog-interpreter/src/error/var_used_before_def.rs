@@ -0,0 +1,34 @@
+use ariadne::{Fmt, Label};
+use prog_utils::pretty_errors::{color_generator, AriadneCompatible, Span};
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct VarUsedBeforeDef<'s> {
+	pub(crate) name: String,
+	/// Span of the name in the definition which comes after the usage.
+	pub(crate) def_name: Span<'s>
+}
+
+impl<'s> AriadneCompatible<'s> for VarUsedBeforeDef<'s> {
+	fn message(&self) -> &'static str { "variable is used before its definition" }
+
+	fn labels(&self, span: Span<'s>) -> Vec<Label<Span<'s>>> {
+		let mut colors = color_generator();
+
+		let color_usage = colors.next();
+		let color_def = colors.next();
+
+		vec![
+			Label::new(span)
+				.with_message(format!(
+					"`{}` is used {}...",
+					(&self.name).fg(color_usage),
+					"here".fg(color_usage)
+				))
+				.with_color(color_usage),
+			Label::new(self.def_name)
+				.with_message(format!("...but only defined {}", "here".fg(color_def)))
+				.with_color(color_def),
+		]
+	}
+}
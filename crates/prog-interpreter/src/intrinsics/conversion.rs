@@ -0,0 +1,175 @@
+use prog_macros::get_argument;
+
+use super::Intrinsic;
+use crate::arg_parser::{Arg, ArgList};
+use crate::value::{CallableData, IntrinsicFn};
+use crate::{
+	error, overload, AsRaw, InterpretError, InterpretErrorKind, InterpretResult, Value, ValueKind
+};
+
+pub(super) fn fetch<'i>() -> Vec<Intrinsic<'i>> {
+	vec![
+		Intrinsic {
+			name: "type_of",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_type_of,
+				ArgList::new(vec![Arg::RequiredUntyped("value".into())])
+			)),
+			receivers: &[],
+			auto_import: true
+		},
+		Intrinsic {
+			name: "to_num",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_to_num,
+				ArgList::new(vec![Arg::RequiredUntyped("value".into())])
+			)),
+			receivers: &[],
+			auto_import: true
+		},
+		Intrinsic {
+			name: "to_str",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_to_str,
+				ArgList::new(vec![Arg::RequiredUntyped("value".into())])
+			)),
+			receivers: &[],
+			auto_import: true
+		},
+		Intrinsic {
+			name: "to_bool",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_to_bool,
+				ArgList::new(vec![Arg::RequiredUntyped("value".into())])
+			)),
+			receivers: &[],
+			auto_import: true
+		},
+		Intrinsic {
+			name: "is_instance",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_is_instance,
+				ArgList::new(vec![
+					Arg::RequiredUntyped("value".into()),
+					Arg::Required("class".into(), ValueKind::Class),
+				])
+			)),
+			receivers: &[],
+			auto_import: true
+		},
+		Intrinsic {
+			name: "fields",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_fields,
+				ArgList::new(vec![Arg::RequiredUntyped("value".into())])
+			)),
+			receivers: &[],
+			auto_import: true
+		},
+	]
+}
+
+/// Name of the kind of the value, such as `Num` or `ClassInstance`.
+fn i_type_of<'i>(
+	CallableData { mut args, .. }: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let value = get_argument!(args => value: _);
+	Ok(Value::Str(value.kind().to_string().into()))
+}
+
+/// Converts booleans and numeric strings into numbers, resulting in `none` if a string is not a number.
+fn i_to_num<'i>(
+	CallableData {
+		mut args,
+		call_site,
+		..
+	}: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let num = match get_argument!(args => value: _) {
+		Value::Num(num) => Some(*num.as_raw()),
+		Value::Bool(bool) => Some(if bool.into() { 1.0 } else { 0.0 }),
+		Value::Str(str) => str.as_raw().trim().parse::<f64>().ok(),
+
+		v => {
+			return Err(InterpretError::new(
+				*call_site.args.nth_item(0).unwrap(),
+				InterpretErrorKind::ArgTypeMismatch(error::ArgTypeMismatch {
+					expected: ValueKind::Str,
+					found: v.kind()
+				})
+			));
+		}
+	};
+
+	Ok(match num {
+		Some(num) => Value::Num(num.into()),
+		None => Value::None
+	})
+}
+
+/// Converts the value into a string the same way as `print` does.
+fn i_to_str<'i>(
+	CallableData {
+		i,
+		mut args,
+		call_site
+	}: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let value = get_argument!(args => value: _);
+	let span_value = *call_site.args.nth_item(0).unwrap();
+
+	overload::stringify(i, &value, span_value).map(|str| Value::Str(str.into()))
+}
+
+fn i_to_bool<'i>(
+	CallableData { mut args, .. }: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let value = get_argument!(args => value: _);
+	Ok(Value::Bool(value.is_truthy().into()))
+}
+
+/// Whether the value is an instance of the class or of one of its descendants.
+fn i_is_instance<'i>(
+	CallableData { mut args, .. }: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let value = get_argument!(args => value: _);
+	let class = get_argument!(args => class: Class);
+
+	let is_instance = match value {
+		Value::ClassInstance(instance) => instance.is_instance_of(&class),
+		_ => false
+	};
+
+	Ok(Value::Bool(is_instance.into()))
+}
+
+/// Names of the fields of an object or of a class instance, sorted alphabetically.
+fn i_fields<'i>(
+	CallableData {
+		mut args,
+		call_site,
+		..
+	}: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let names = match get_argument!(args => value: _) {
+		Value::Obj(obj) => obj.entries().into_iter().map(|(name, _)| name).collect(),
+		Value::ClassInstance(instance) => instance.field_names(),
+
+		v => {
+			return Err(InterpretError::new(
+				*call_site.args.nth_item(0).unwrap(),
+				InterpretErrorKind::ArgTypeMismatch(error::ArgTypeMismatch {
+					expected: ValueKind::Obj,
+					found: v.kind()
+				})
+			));
+		}
+	};
+
+	let names = names
+		.into_iter()
+		.map(|name: String| Value::Str(name.into()))
+		.collect::<Vec<_>>();
+
+	Ok(Value::List(names.into()))
+}
@@ -0,0 +1,319 @@
+use std::cmp::Ordering;
+
+use prog_macros::get_argument;
+
+use super::{call_arg, index_arg, limit_exceeded, Intrinsic};
+use crate::arg_parser::{Arg, ArgList};
+use crate::value::{CallableData, IntrinsicFn};
+use crate::{error, AsRaw, InterpretError, InterpretErrorKind, InterpretResult, Value, ValueKind};
+
+pub(super) fn fetch<'i>() -> Vec<Intrinsic<'i>> {
+	vec![
+		Intrinsic {
+			name: "push",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_push,
+				ArgList::new(vec![
+					Arg::Required("list".into(), ValueKind::List),
+					Arg::RequiredUntyped("value".into()),
+				])
+			)),
+			receivers: &[ValueKind::List],
+			auto_import: false
+		},
+		Intrinsic {
+			name: "pop",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_pop,
+				ArgList::new(vec![Arg::Required("list".into(), ValueKind::List)])
+			)),
+			receivers: &[ValueKind::List],
+			auto_import: false
+		},
+		Intrinsic {
+			name: "insert",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_insert,
+				ArgList::new(vec![
+					Arg::Required("list".into(), ValueKind::List),
+					Arg::Required("index".into(), ValueKind::Num),
+					Arg::RequiredUntyped("value".into()),
+				])
+			)),
+			receivers: &[ValueKind::List],
+			auto_import: false
+		},
+		Intrinsic {
+			name: "remove",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_remove,
+				ArgList::new(vec![
+					Arg::Required("list".into(), ValueKind::List),
+					Arg::Required("index".into(), ValueKind::Num),
+				])
+			)),
+			receivers: &[ValueKind::List],
+			auto_import: false
+		},
+		Intrinsic {
+			name: "slice",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_slice,
+				ArgList::new(vec![
+					Arg::Required("list".into(), ValueKind::List),
+					Arg::Required("start".into(), ValueKind::Num),
+					Arg::Optional("end".into(), ValueKind::Num),
+				])
+			)),
+			receivers: &[ValueKind::List],
+			auto_import: false
+		},
+		Intrinsic {
+			name: "sort",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_sort,
+				ArgList::new(vec![Arg::Required("list".into(), ValueKind::List)])
+			)),
+			receivers: &[ValueKind::List],
+			auto_import: false
+		},
+		Intrinsic {
+			name: "map",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_map,
+				ArgList::new(vec![
+					Arg::Required("list".into(), ValueKind::List),
+					Arg::RequiredUntyped("func".into()),
+				])
+			)),
+			receivers: &[ValueKind::List],
+			auto_import: false
+		},
+		Intrinsic {
+			name: "filter",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_filter,
+				ArgList::new(vec![
+					Arg::Required("list".into(), ValueKind::List),
+					Arg::RequiredUntyped("func".into()),
+				])
+			)),
+			receivers: &[ValueKind::List],
+			auto_import: false
+		},
+		Intrinsic {
+			name: "reduce",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_reduce,
+				ArgList::new(vec![
+					Arg::Required("list".into(), ValueKind::List),
+					Arg::RequiredUntyped("func".into()),
+					Arg::Optional("initial".into(), ValueKind::None),
+				])
+			)),
+			receivers: &[ValueKind::List],
+			auto_import: false
+		},
+	]
+}
+
+fn i_push<'i>(
+	CallableData { mut args, .. }: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let list = get_argument!(args => list: List);
+	let value = get_argument!(args => value: _);
+
+	list.push(value);
+	Ok(Value::None)
+}
+
+fn i_pop<'i>(
+	CallableData { mut args, .. }: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let list = get_argument!(args => list: List);
+	Ok(list.pop().unwrap_or_default())
+}
+
+fn i_insert<'i>(
+	CallableData {
+		mut args,
+		call_site,
+		..
+	}: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let list = get_argument!(args => list: List);
+	let index = index_arg(get_argument!(args => index: Num).into(), &call_site, 1)?;
+	let value = get_argument!(args => value: _);
+
+	// Inserting right past the last item appends to the list
+	let mut items = list.items_mut();
+	if index > items.len() {
+		return Err(out_of_bounds(index, items.len(), &call_site));
+	}
+
+	items.insert(index, value);
+	Ok(Value::None)
+}
+
+fn i_remove<'i>(
+	CallableData {
+		mut args,
+		call_site,
+		..
+	}: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let list = get_argument!(args => list: List);
+	let index = index_arg(get_argument!(args => index: Num).into(), &call_site, 1)?;
+
+	list.remove(index)
+		.ok_or_else(|| out_of_bounds(index, list.len(), &call_site))
+}
+
+fn i_slice<'i>(
+	CallableData {
+		mut args,
+		call_site,
+		..
+	}: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let list = get_argument!(args => list: List);
+	let items = list.items();
+
+	let start = index_arg(get_argument!(args => start: Num).into(), &call_site, 1)?;
+	let end = match get_argument!(args => end: Num?) {
+		Some(end) => index_arg(end.into(), &call_site, 2)?,
+		None => items.len()
+	};
+
+	// Bounds are clamped to the list, so that slicing never fails on a valid index
+	let end = end.min(items.len());
+	let start = start.min(end);
+
+	Ok(Value::List(items[start..end].to_vec().into()))
+}
+
+fn i_sort<'i>(
+	CallableData {
+		i,
+		mut args,
+		call_site
+	}: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let list = get_argument!(args => list: List);
+	let mut items = list.items_mut();
+
+	// The comparisons cannot fail, so the fuel for the items is spent up front
+	for _ in 0..items.len() {
+		i.limits.step().map_err(limit_exceeded(&call_site))?;
+	}
+
+	// Only numbers and strings are ordered, and they cannot be mixed
+	let mut incomparable = None;
+	items.sort_by(|a, b| match (a, b) {
+		(Value::Num(a), Value::Num(b)) => a.as_raw().total_cmp(b.as_raw()),
+		(Value::Str(a), Value::Str(b)) => a.as_raw().cmp(b.as_raw()),
+
+		_ => {
+			incomparable.get_or_insert((a.kind(), b.kind()));
+			Ordering::Equal
+		}
+	});
+
+	if let Some((a, b)) = incomparable {
+		return Err(InterpretError::new(
+			*call_site.args.nth_item(0).unwrap(),
+			InterpretErrorKind::NativeFnFailed(error::NativeFnFailed(format!(
+				"cannot order `{a}` and `{b}`, only lists of numbers or strings can be sorted"
+			)))
+		));
+	}
+
+	Ok(Value::None)
+}
+
+fn i_map<'i>(
+	CallableData {
+		i,
+		mut args,
+		call_site
+	}: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	// Items are copied so that the callback is free to modify the list
+	let items = get_argument!(args => list: List).items().clone();
+	let func = get_argument!(args => func: _);
+
+	let mut mapped = Vec::with_capacity(items.len());
+
+	for item in items {
+		i.limits.step().map_err(limit_exceeded(&call_site))?;
+		mapped.push(call_arg(i, &func, vec![item], &call_site, 1)?);
+	}
+
+	Ok(Value::List(mapped.into()))
+}
+
+fn i_filter<'i>(
+	CallableData {
+		i,
+		mut args,
+		call_site
+	}: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let items = get_argument!(args => list: List).items().clone();
+	let func = get_argument!(args => func: _);
+
+	let mut filtered = vec![];
+
+	for item in items {
+		i.limits.step().map_err(limit_exceeded(&call_site))?;
+
+		if call_arg(i, &func, vec![item.clone()], &call_site, 1)?.is_truthy() {
+			filtered.push(item);
+		}
+	}
+
+	Ok(Value::List(filtered.into()))
+}
+
+fn i_reduce<'i>(
+	CallableData {
+		i,
+		mut args,
+		call_site
+	}: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let items = get_argument!(args => list: List).items().clone();
+	let func = get_argument!(args => func: _);
+	let initial = get_argument!(args => initial: _?).filter(|v| !matches!(v, Value::None));
+
+	// Without an initial value (or with `none`), the first item is used in its place
+	let mut items = items.into_iter();
+	let Some(mut acc) = initial.or_else(|| items.next()) else {
+		return Err(InterpretError::new(
+			*call_site.args.nth_item(0).unwrap(),
+			InterpretErrorKind::NativeFnFailed(error::NativeFnFailed(String::from(
+				"cannot reduce an empty list without an initial value"
+			)))
+		));
+	};
+
+	for item in items {
+		i.limits.step().map_err(limit_exceeded(&call_site))?;
+		acc = call_arg(i, &func, vec![acc, item], &call_site, 1)?;
+	}
+
+	Ok(acc)
+}
+
+fn out_of_bounds<'i>(
+	index: usize,
+	len: usize,
+	call_site: &crate::value::CallSite<'i>
+) -> InterpretError<'i> {
+	InterpretError::new(
+		*call_site.args.nth_item(1).unwrap(),
+		InterpretErrorKind::NativeFnFailed(error::NativeFnFailed(format!(
+			"index {index} is out of bounds for a list of length {len}"
+		)))
+	)
+}
@@ -0,0 +1,628 @@
+mod conversion;
+mod list;
+mod number;
+mod object;
+mod string;
+
+use prog_macros::get_argument;
+use prog_parser::{ASTNode, Span};
+
+use crate::arg_parser::{Arg, ArgList, ArgumentParseError};
+use crate::value::{CallSite, CallableData, IntrinsicFn};
+use crate::{
+	error, AsRaw, Callable, InterpretError, InterpretErrorKind, InterpretResult, Interpreter,
+	Primitive, Value, ValueKind
+};
+
+#[derive(Debug)]
+pub(crate) struct Intrinsic<'i> {
+	pub(crate) name: &'static str,
+	pub(crate) value: Value<'i>,
+	/// Kinds of the values which have the intrinsic as a method,
+	/// passing themselves as its first argument.
+	pub(crate) receivers: &'static [ValueKind],
+	pub(crate) auto_import: bool
+}
+
+#[derive(Debug)]
+pub(crate) struct IntrinsicTable<'i> {
+	entries: Vec<Intrinsic<'i>>
+}
+
+impl<'i> IntrinsicTable<'i> {
+	pub fn new() -> Self {
+		let mut this = Self::new_empty();
+		this.entries.extend(Self::fetch());
+		this.entries.extend(list::fetch());
+		this.entries.extend(string::fetch());
+		this.entries.extend(object::fetch());
+		this.entries.extend(number::fetch());
+		this.entries.extend(conversion::fetch());
+
+		this
+	}
+
+	pub fn new_empty() -> Self { Self { entries: vec![] } }
+
+	fn fetch() -> Box<[Intrinsic<'i>]> {
+		Box::new([
+			Intrinsic {
+				name: "should_panic",
+				value: Value::IntrinsicFn(IntrinsicFn::new(
+					i_should_panic,
+					ArgList::new(vec![Arg::Required("func".into(), ValueKind::Func)])
+				)),
+				receivers: &[],
+				auto_import: false
+			},
+			Intrinsic {
+				name: "import",
+				value: Value::IntrinsicFn(IntrinsicFn::new(
+					i_import,
+					ArgList::new(vec![Arg::Required("path".into(), ValueKind::Str)])
+				)),
+				receivers: &[],
+				auto_import: true
+			},
+			Intrinsic {
+				name: "input",
+				value: Value::IntrinsicFn(IntrinsicFn::new(
+					i_input,
+					ArgList::new(vec![Arg::Optional("message".into(), ValueKind::Str)])
+				)),
+				receivers: &[],
+				auto_import: false
+			},
+			Intrinsic {
+				name: "print",
+				value: Value::IntrinsicFn(IntrinsicFn::new(
+					i_print,
+					ArgList::new(vec![Arg::Variadic("args".into())])
+				)),
+				receivers: &[],
+				auto_import: true
+			},
+			Intrinsic {
+				name: "raw_print",
+				value: Value::IntrinsicFn(IntrinsicFn::new(
+					i_raw_print,
+					ArgList::new(vec![Arg::Required("str".into(), ValueKind::Str)])
+				)),
+				receivers: &[],
+				auto_import: false
+			},
+			Intrinsic {
+				name: "debug",
+				value: Value::IntrinsicFn(IntrinsicFn::new(
+					i_debug,
+					ArgList::new(vec![Arg::RequiredUntyped("value".into())])
+				)),
+				receivers: &[],
+				auto_import: true
+			},
+			Intrinsic {
+				name: "dump_ctx",
+				value: Value::IntrinsicFn(IntrinsicFn::new(i_dump_ctx, ArgList::new_empty())),
+				receivers: &[],
+				auto_import: false
+			},
+			Intrinsic {
+				name: "range",
+				value: Value::IntrinsicFn(IntrinsicFn::new(
+					i_range,
+					ArgList::new(vec![
+						Arg::Required("start".into(), ValueKind::Num),
+						Arg::Optional("end".into(), ValueKind::Num),
+						Arg::Optional("step".into(), ValueKind::Num),
+					])
+				)),
+				receivers: &[],
+				auto_import: true
+			},
+			Intrinsic {
+				name: "len",
+				value: Value::IntrinsicFn(IntrinsicFn::new(
+					i_len,
+					ArgList::new(vec![Arg::RequiredUntyped("value".into())])
+				)),
+				receivers: &[ValueKind::List, ValueKind::Str, ValueKind::Obj],
+				auto_import: false
+			},
+			Intrinsic {
+				name: "assert",
+				value: Value::IntrinsicFn(IntrinsicFn::new(
+					i_assert,
+					ArgList::new(vec![
+						Arg::Required("expr".into(), ValueKind::Bool),
+						Arg::Optional("msg".into(), ValueKind::Str),
+					])
+				)),
+				receivers: &[],
+				auto_import: true
+			},
+			Intrinsic {
+				name: "assert_eq",
+				value: Value::IntrinsicFn(IntrinsicFn::new(
+					i_assert_eq,
+					ArgList::new(vec![
+						Arg::RequiredUntyped("left".into()),
+						Arg::RequiredUntyped("right".into()),
+					])
+				)),
+				receivers: &[],
+				auto_import: true
+			},
+			Intrinsic {
+				name: "assert_neq",
+				value: Value::IntrinsicFn(IntrinsicFn::new(
+					i_assert_neq,
+					ArgList::new(vec![
+						Arg::RequiredUntyped("left".into()),
+						Arg::RequiredUntyped("right".into()),
+					])
+				)),
+				receivers: &[],
+				auto_import: true
+			}
+		])
+	}
+}
+
+impl<'i> IntoIterator for IntrinsicTable<'i> {
+	type IntoIter = std::vec::IntoIter<Self::Item>;
+	type Item = Intrinsic<'i>;
+
+	fn into_iter(self) -> Self::IntoIter { self.entries.into_iter() }
+}
+
+impl Default for IntrinsicTable<'_> {
+	fn default() -> Self { Self::new_empty() }
+}
+
+fn i_should_panic<'i>(
+	CallableData {
+		i,
+		mut args,
+		call_site
+	}: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	use crate::Callable;
+
+	let span_callee = call_site.callee;
+
+	let mut func = get_argument!(args => func: Func);
+	let result = func.call(CallableData {
+		i,
+		args: std::collections::HashMap::new(),
+		call_site
+	});
+
+	match result {
+		Ok(_) => Err(InterpretError::new(
+			span_callee,
+			crate::InterpretErrorKind::AssertionFailed(error::AssertionFailed(Some(String::from(
+				"function did not panic"
+			))))
+		)),
+
+		// The error is described the same way as in `catch`, trace included
+		Err(error) if error.kind.is_catchable() => Ok(crate::error_value(&error)),
+
+		// Limits of the interpreter are left for the host, same as in `try`
+		Err(error) => Err(error)
+	}
+}
+
+fn i_import<'i>(
+	CallableData {
+		i,
+		mut args,
+		call_site
+	}: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	if !i.context.inner().flags.imports_allowed {
+		return Err(InterpretError::new(
+			call_site.callee,
+			crate::InterpretErrorKind::CtxDisallowed(error::CtxDisallowed {
+				thing: String::from("imports"),
+				plural: true
+			})
+		));
+	}
+
+	let path = String::from(get_argument!(args => path: Str));
+	crate::module::import(i, &path, &call_site)
+}
+
+fn i_input<'i>(
+	CallableData {
+		i,
+		mut args,
+		call_site
+	}: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let span_callee = call_site.callee;
+
+	if !i.context.inner().flags.inputs_allowed {
+		return Err(InterpretError::new(
+			span_callee,
+			crate::InterpretErrorKind::CtxDisallowed(error::CtxDisallowed {
+				thing: String::from("user input"),
+				plural: false
+			})
+		));
+	}
+
+	let message = get_argument!(args => message: Str?).map(String::from);
+
+	if let Some(message) = message {
+		i.io.write_stdout(message.as_bytes())
+			.and_then(|_| i.io.flush())
+			.map_err(io_failed(span_callee))?;
+	}
+
+	let mut line = String::new();
+	let read = i.io.read_line(&mut line).map_err(io_failed(span_callee))?;
+
+	if read == 0 {
+		return Ok(Value::None);
+	}
+
+	let line = line.trim_end_matches(['\r', '\n']);
+	Ok(Value::Str(line.into()))
+}
+
+fn i_print<'i>(
+	CallableData {
+		i,
+		mut args,
+		call_site
+	}: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let values = get_argument!(args => args: ...);
+	let mut formatted = Vec::with_capacity(values.len());
+
+	for (index, value) in values.iter().enumerate() {
+		let span = *call_site.args.nth_item(index).unwrap();
+		formatted.push(crate::overload::stringify(i, value, span)?);
+	}
+
+	let mut formatted = formatted.join(" ");
+	formatted.push('\n');
+
+	i.io.write_stdout(formatted.as_bytes())
+		.map_err(io_failed(call_site.callee))?;
+
+	Ok(Value::None)
+}
+
+fn i_raw_print<'i>(
+	CallableData {
+		i,
+		mut args,
+		call_site
+	}: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let str = String::from(get_argument!(args => str: Str));
+
+	i.io.write_stdout(str.as_bytes())
+		.map_err(io_failed(call_site.callee))?;
+
+	Ok(Value::None)
+}
+
+fn i_dump_ctx<'i>(
+	CallableData { i, call_site, .. }: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	use prog_utils::fmt::Printable;
+
+	let dump = i.context.str_pretty();
+
+	i.io.write_stdout(dump.as_bytes())
+		.map_err(io_failed(call_site.callee))?;
+
+	Ok(Value::None)
+}
+
+fn i_debug<'i>(
+	CallableData {
+		i,
+		mut args,
+		call_site
+	}: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let expr = call_site.args.nth_item(0).copied().unwrap().value();
+
+	let value = get_argument!(args => value: _);
+
+	let file = call_site.file();
+	let position = call_site.args.start();
+
+	let mut column = 1;
+	let mut row = 1;
+
+	for (idx, char) in call_site.source().char_indices() {
+		if idx >= position {
+			break;
+		}
+
+		if char == '\n' {
+			column += 1;
+			row = 1;
+		}
+
+		row += 1;
+	}
+
+	let formatted = format!("[{file}:{column}:{row}] {expr} = {value}\n");
+
+	i.io.write_stdout(formatted.as_bytes())
+		.map_err(io_failed(call_site.callee))?;
+
+	Ok(Value::None)
+}
+
+/// Maximum number of items of a list produced by `range`, regardless of the size limit of the interpreter.
+const MAX_RANGE_LEN: usize = 1 << 24;
+/// Largest number up to which all of the integers are represented exactly.
+const MAX_SAFE_INTEGER: f64 = 9007199254740991.0;
+
+fn i_range<'i>(
+	CallableData {
+		i,
+		mut args,
+		call_site
+	}: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let start = f64::from(get_argument!(args => start: Num));
+	let end = get_argument!(args => end: Num?).map(f64::from);
+	let step = get_argument!(args => step: Num?).map_or(1.0, f64::from);
+
+	// `range(end)` counts from zero
+	let (start, end) = match end {
+		Some(end) => (start, end),
+		None => (0.0, start)
+	};
+
+	let span_args = call_site.args.span();
+	let range_failed = |span, message: String| {
+		InterpretError::new(
+			span,
+			crate::InterpretErrorKind::NativeFnFailed(error::NativeFnFailed(message))
+		)
+	};
+
+	if step == 0.0 || !step.is_finite() {
+		let span_step = *call_site.args.nth_item(2).unwrap();
+
+		return Err(range_failed(
+			span_step,
+			String::from("range step must be a non-zero finite number")
+		));
+	}
+
+	if [start, end]
+		.iter()
+		.any(|bound| !bound.is_finite() || bound.abs() > MAX_SAFE_INTEGER)
+	{
+		return Err(range_failed(
+			span_args,
+			format!("range bounds must be finite numbers between -{MAX_SAFE_INTEGER} and {MAX_SAFE_INTEGER}")
+		));
+	}
+
+	// Adding a step which is too small to change the bounds would produce the same item over and over
+	if start + step == start || end - step == end {
+		return Err(range_failed(
+			span_args,
+			format!("range step {step} is too small for the bounds {start} and {end}")
+		));
+	}
+
+	let len = ((end - start) / step).ceil().max(0.0);
+
+	if len > MAX_RANGE_LEN as f64 {
+		return Err(range_failed(
+			span_args,
+			format!("range cannot have more than {MAX_RANGE_LEN} items")
+		));
+	}
+
+	let len = len as usize;
+	let limit_exceeded = limit_exceeded(&call_site);
+
+	i.limits
+		.check_len(ValueKind::List, len)
+		.map_err(limit_exceeded)?;
+
+	let mut items = Vec::with_capacity(len);
+
+	// Every item is computed from the start, so that the rounding errors of the steps do not add up
+	for idx in 0..len {
+		i.limits.step().map_err(limit_exceeded)?;
+		items.push(Value::Num((start + idx as f64 * step).into()));
+	}
+
+	Ok(Value::List(items.into()))
+}
+
+fn i_len<'i>(
+	CallableData {
+		mut args,
+		call_site,
+		..
+	}: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let len = match get_argument!(args => value: _) {
+		Value::List(list) => list.len(),
+		Value::Str(str) => str.as_raw().chars().count(),
+		Value::Obj(obj) => obj.entries().len(),
+
+		v => {
+			return Err(InterpretError::new(
+				*call_site.args.nth_item(0).unwrap(),
+				InterpretErrorKind::ArgTypeMismatch(error::ArgTypeMismatch {
+					expected: ValueKind::List,
+					found: v.kind()
+				})
+			));
+		}
+	};
+
+	Ok(Value::Num((len as f64).into()))
+}
+
+fn i_assert<'i>(
+	CallableData {
+		mut args,
+		call_site,
+		..
+	}: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let expr = get_argument!(args => expr: Bool);
+	let msg = get_argument!(args => msg: Str?);
+
+	if !expr.is_truthy() {
+		let expr_span = *call_site.args.nth_item(0).unwrap();
+
+		return Err(InterpretError::new(
+			expr_span,
+			crate::InterpretErrorKind::AssertionFailed(error::AssertionFailed(msg.map(Into::into)))
+		));
+	}
+
+	Ok(Value::None)
+}
+
+fn i_assert_eq<'i>(data: CallableData<'_, 'i>) -> InterpretResult<'i, Value<'i>> {
+	generic_lr_assert(|l, r| l == r, data)
+}
+
+fn i_assert_neq<'i>(data: CallableData<'_, 'i>) -> InterpretResult<'i, Value<'i>> {
+	generic_lr_assert(|l, r| l != r, data)
+}
+
+fn generic_lr_assert<'int, F>(
+	pred: F,
+	CallableData {
+		mut args,
+		call_site,
+		..
+	}: CallableData<'_, 'int>
+) -> InterpretResult<'int, Value<'int>>
+where
+	F: FnOnce(&Value<'int>, &Value<'int>) -> bool
+{
+	use prog_parser::{Position, Span};
+
+	let left = get_argument!(args => left: _);
+	let right = get_argument!(args => right: _);
+
+	if !pred(&left, &right) {
+		let source = call_site.args.source();
+		let file = call_site.args.file();
+
+		let span_left = *call_site.args.items().first().copied().unwrap();
+		let span_right = *call_site.args.items().get(1).copied().unwrap();
+
+		let start = span_left.position().start();
+		let end = span_right.position().end();
+
+		let position = Position::new(start, end);
+		let expr_span = Span::new(source, file, position);
+
+		return Err(InterpretError::new(
+			expr_span,
+			crate::InterpretErrorKind::AssertionEqFailed(error::AssertionEqFailed {
+				left: (left, span_left),
+				right: (right, span_right)
+			})
+		));
+	}
+
+	Ok(Value::None)
+}
+
+fn io_failed<'i>(span: Span<'i>) -> impl FnOnce(std::io::Error) -> InterpretError<'i> {
+	move |e| {
+		InterpretError::new(
+			span,
+			crate::InterpretErrorKind::IoFailed(error::IoFailed(e.to_string()))
+		)
+	}
+}
+
+/// Converts the `n`-th argument into an index, which must be a positive whole number.
+fn index_arg<'i>(value: f64, call_site: &CallSite<'i>, n: usize) -> InterpretResult<'i, usize> {
+	crate::f64_to_usize(value).ok_or_else(|| {
+		InterpretError::new(
+			*call_site.args.nth_item(n).unwrap(),
+			InterpretErrorKind::InvalidIndex(error::InvalidIndex(Value::Num(value.into())))
+		)
+	})
+}
+
+/// Reports a limit of the interpreter which was exceeded while an intrinsic was running at its call site.
+fn limit_exceeded<'i>(
+	call_site: &CallSite<'i>
+) -> impl Fn(InterpretErrorKind<'i>) -> InterpretError<'i> + Copy {
+	let span = call_site.span();
+	move |kind| InterpretError::new(span, kind)
+}
+
+/// Calls a function which was passed as the `n`-th argument, such as the callback of `map`.
+fn call_arg<'i>(
+	i: &mut Interpreter<'i>,
+	func: &Value<'i>,
+	args: Vec<Value<'i>>,
+	call_site: &CallSite<'i>,
+	n: usize
+) -> InterpretResult<'i, Value<'i>> {
+	let span = *call_site.args.nth_item(n).unwrap();
+
+	let mut func = match func.clone() {
+		Value::Func(f) => Box::new(f) as Box<dyn Callable>,
+		Value::IntrinsicFn(f) => Box::new(f) as Box<dyn Callable>,
+
+		v => {
+			return Err(InterpretError::new(
+				span,
+				InterpretErrorKind::ArgTypeMismatch(error::ArgTypeMismatch {
+					expected: ValueKind::Func,
+					found: v.kind()
+				})
+			));
+		}
+	};
+
+	let parsed_args = func.arg_list().verify(&args).map_err(|e| {
+		let kind = match e {
+			ArgumentParseError::CountMismatch {
+				expected,
+				end_boundary,
+				found
+			} => InterpretErrorKind::ArgCountMismatch(error::ArgCountMismatch {
+				expected,
+				end_boundary,
+				found
+			}),
+
+			ArgumentParseError::IncorrectType {
+				expected, found, ..
+			} => InterpretErrorKind::ArgTypeMismatch(error::ArgTypeMismatch { expected, found })
+		};
+
+		InterpretError::new(span, kind)
+	})?;
+
+	func.call(CallableData {
+		i,
+		args: parsed_args,
+		call_site: CallSite {
+			callee: span,
+			_lp: span,
+			args: prog_parser::ast::Punctuated::new(),
+			_rp: span
+		}
+	})
+}
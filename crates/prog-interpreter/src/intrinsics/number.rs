@@ -0,0 +1,163 @@
+use std::hash::{BuildHasher, Hasher};
+
+use prog_macros::get_argument;
+
+use super::Intrinsic;
+use crate::arg_parser::{Arg, ArgList};
+use crate::value::{CallableData, IntrinsicFn};
+use crate::{error, InterpretError, InterpretErrorKind, InterpretResult, Value, ValueKind};
+
+pub(super) fn fetch<'i>() -> Vec<Intrinsic<'i>> {
+	vec![
+		Intrinsic {
+			name: "floor",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_floor,
+				ArgList::new(vec![Arg::Required("num".into(), ValueKind::Num)])
+			)),
+			receivers: &[ValueKind::Num],
+			auto_import: false
+		},
+		Intrinsic {
+			name: "sqrt",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_sqrt,
+				ArgList::new(vec![Arg::Required("num".into(), ValueKind::Num)])
+			)),
+			receivers: &[ValueKind::Num],
+			auto_import: false
+		},
+		Intrinsic {
+			name: "sin",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_sin,
+				ArgList::new(vec![Arg::Required("num".into(), ValueKind::Num)])
+			)),
+			receivers: &[ValueKind::Num],
+			auto_import: false
+		},
+		Intrinsic {
+			name: "pow",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_pow,
+				ArgList::new(vec![
+					Arg::Required("base".into(), ValueKind::Num),
+					Arg::Required("exp".into(), ValueKind::Num),
+				])
+			)),
+			receivers: &[ValueKind::Num],
+			auto_import: false
+		},
+		Intrinsic {
+			name: "random",
+			value: Value::IntrinsicFn(IntrinsicFn::new(i_random, ArgList::new_empty())),
+			receivers: &[],
+			auto_import: false
+		},
+		Intrinsic {
+			name: "min",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_min,
+				ArgList::new(vec![
+					Arg::Required("num".into(), ValueKind::Num),
+					Arg::Variadic("nums".into()),
+				])
+			)),
+			receivers: &[ValueKind::Num],
+			auto_import: false
+		},
+		Intrinsic {
+			name: "max",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_max,
+				ArgList::new(vec![
+					Arg::Required("num".into(), ValueKind::Num),
+					Arg::Variadic("nums".into()),
+				])
+			)),
+			receivers: &[ValueKind::Num],
+			auto_import: false
+		},
+	]
+}
+
+fn i_floor<'i>(
+	CallableData { mut args, .. }: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let num = f64::from(get_argument!(args => num: Num));
+	Ok(Value::Num(num.floor().into()))
+}
+
+fn i_sqrt<'i>(
+	CallableData { mut args, .. }: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let num = f64::from(get_argument!(args => num: Num));
+	Ok(Value::Num(num.sqrt().into()))
+}
+
+fn i_sin<'i>(
+	CallableData { mut args, .. }: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let num = f64::from(get_argument!(args => num: Num));
+	Ok(Value::Num(num.sin().into()))
+}
+
+fn i_pow<'i>(
+	CallableData { mut args, .. }: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let base = f64::from(get_argument!(args => base: Num));
+	let exp = f64::from(get_argument!(args => exp: Num));
+
+	Ok(Value::Num(base.powf(exp).into()))
+}
+
+/// Random number in the range of `[0, 1)`.
+fn i_random<'i>(_: CallableData<'_, 'i>) -> InterpretResult<'i, Value<'i>> {
+	// Every `RandomState` is seeded with different keys, which makes its hashes random
+	let bits = std::collections::hash_map::RandomState::new()
+		.build_hasher()
+		.finish();
+
+	// The upper 53 bits fill the mantissa of an `f64` uniformly
+	let num = (bits >> 11) as f64 / (1u64 << 53) as f64;
+	Ok(Value::Num(num.into()))
+}
+
+fn i_min<'i>(data: CallableData<'_, 'i>) -> InterpretResult<'i, Value<'i>> {
+	generic_extremum(f64::min, data)
+}
+
+fn i_max<'i>(data: CallableData<'_, 'i>) -> InterpretResult<'i, Value<'i>> {
+	generic_extremum(f64::max, data)
+}
+
+fn generic_extremum<'i, F>(
+	pick: F,
+	CallableData {
+		mut args,
+		call_site,
+		..
+	}: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>>
+where
+	F: Fn(f64, f64) -> f64
+{
+	let mut result = f64::from(get_argument!(args => num: Num));
+	let nums = get_argument!(args => nums: ...);
+
+	for (index, num) in nums.into_iter().enumerate() {
+		let Value::Num(num) = num else {
+			return Err(InterpretError::new(
+				*call_site.args.nth_item(index + 1).unwrap(),
+				InterpretErrorKind::ArgTypeMismatch(error::ArgTypeMismatch {
+					expected: ValueKind::Num,
+					found: num.kind()
+				})
+			));
+		};
+
+		result = pick(result, num.into());
+	}
+
+	Ok(Value::Num(result.into()))
+}
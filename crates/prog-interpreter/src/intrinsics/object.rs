@@ -0,0 +1,76 @@
+use prog_macros::get_argument;
+
+use super::Intrinsic;
+use crate::arg_parser::{Arg, ArgList};
+use crate::value::{CallableData, IntrinsicFn};
+use crate::{InterpretResult, Value, ValueKind};
+
+pub(super) fn fetch<'i>() -> Vec<Intrinsic<'i>> {
+	vec![
+		Intrinsic {
+			name: "keys",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_keys,
+				ArgList::new(vec![Arg::Required("obj".into(), ValueKind::Obj)])
+			)),
+			receivers: &[ValueKind::Obj],
+			auto_import: false
+		},
+		Intrinsic {
+			name: "values",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_values,
+				ArgList::new(vec![Arg::Required("obj".into(), ValueKind::Obj)])
+			)),
+			receivers: &[ValueKind::Obj],
+			auto_import: false
+		},
+		Intrinsic {
+			name: "has",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_has,
+				ArgList::new(vec![
+					Arg::Required("obj".into(), ValueKind::Obj),
+					Arg::Required("name".into(), ValueKind::Str),
+				])
+			)),
+			receivers: &[ValueKind::Obj],
+			auto_import: false
+		},
+	]
+}
+
+/// Keys of the object, sorted the same way as when iterating over it.
+fn i_keys<'i>(
+	CallableData { mut args, .. }: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let keys = get_argument!(args => obj: Obj)
+		.entries()
+		.into_iter()
+		.map(|(name, _)| Value::Str(name.into()))
+		.collect::<Vec<_>>();
+
+	Ok(Value::List(keys.into()))
+}
+
+/// Values of the object, in the order of their keys.
+fn i_values<'i>(
+	CallableData { mut args, .. }: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let values = get_argument!(args => obj: Obj)
+		.entries()
+		.into_iter()
+		.map(|(_, value)| value)
+		.collect::<Vec<_>>();
+
+	Ok(Value::List(values.into()))
+}
+
+fn i_has<'i>(
+	CallableData { mut args, .. }: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let obj = get_argument!(args => obj: Obj);
+	let name = String::from(get_argument!(args => name: Str));
+
+	Ok(Value::Bool(obj.contains(name).into()))
+}
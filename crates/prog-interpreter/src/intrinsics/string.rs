@@ -0,0 +1,262 @@
+use prog_macros::get_argument;
+
+use super::{index_arg, limit_exceeded, Intrinsic};
+use crate::arg_parser::{Arg, ArgList};
+use crate::value::{CallableData, IntrinsicFn};
+use crate::{
+	error, overload, AsRaw, InterpretError, InterpretErrorKind, InterpretResult, Value, ValueKind
+};
+
+pub(super) fn fetch<'i>() -> Vec<Intrinsic<'i>> {
+	vec![
+		Intrinsic {
+			name: "split",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_split,
+				ArgList::new(vec![
+					Arg::Required("str".into(), ValueKind::Str),
+					Arg::Required("sep".into(), ValueKind::Str),
+				])
+			)),
+			receivers: &[ValueKind::Str],
+			auto_import: false
+		},
+		Intrinsic {
+			name: "join",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_join,
+				ArgList::new(vec![
+					Arg::Required("sep".into(), ValueKind::Str),
+					Arg::Required("list".into(), ValueKind::List),
+				])
+			)),
+			receivers: &[ValueKind::Str],
+			auto_import: false
+		},
+		Intrinsic {
+			name: "trim",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_trim,
+				ArgList::new(vec![Arg::Required("str".into(), ValueKind::Str)])
+			)),
+			receivers: &[ValueKind::Str],
+			auto_import: false
+		},
+		Intrinsic {
+			name: "find",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_find,
+				ArgList::new(vec![
+					Arg::Required("str".into(), ValueKind::Str),
+					Arg::Required("pattern".into(), ValueKind::Str),
+				])
+			)),
+			receivers: &[ValueKind::Str],
+			auto_import: false
+		},
+		Intrinsic {
+			name: "replace",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_replace,
+				ArgList::new(vec![
+					Arg::Required("str".into(), ValueKind::Str),
+					Arg::Required("from".into(), ValueKind::Str),
+					Arg::Required("to".into(), ValueKind::Str),
+				])
+			)),
+			receivers: &[ValueKind::Str],
+			auto_import: false
+		},
+		Intrinsic {
+			name: "upper",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_upper,
+				ArgList::new(vec![Arg::Required("str".into(), ValueKind::Str)])
+			)),
+			receivers: &[ValueKind::Str],
+			auto_import: false
+		},
+		Intrinsic {
+			name: "lower",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_lower,
+				ArgList::new(vec![Arg::Required("str".into(), ValueKind::Str)])
+			)),
+			receivers: &[ValueKind::Str],
+			auto_import: false
+		},
+		Intrinsic {
+			name: "substr",
+			value: Value::IntrinsicFn(IntrinsicFn::new(
+				i_substr,
+				ArgList::new(vec![
+					Arg::Required("str".into(), ValueKind::Str),
+					Arg::Required("start".into(), ValueKind::Num),
+					Arg::Optional("end".into(), ValueKind::Num),
+				])
+			)),
+			receivers: &[ValueKind::Str],
+			auto_import: false
+		},
+	]
+}
+
+fn i_split<'i>(
+	CallableData {
+		i,
+		mut args,
+		call_site
+	}: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let str = String::from(get_argument!(args => str: Str));
+	let sep = String::from(get_argument!(args => sep: Str));
+
+	if sep.is_empty() {
+		return Err(InterpretError::new(
+			*call_site.args.nth_item(1).unwrap(),
+			InterpretErrorKind::NativeFnFailed(error::NativeFnFailed(String::from(
+				"separator must not be empty"
+			)))
+		));
+	}
+
+	let limit_exceeded = limit_exceeded(&call_site);
+
+	i.limits
+		.check_len(ValueKind::List, str.matches(&sep).count() + 1)
+		.map_err(limit_exceeded)?;
+
+	let parts = str
+		.split(&sep)
+		.map(|part| {
+			i.limits.step().map_err(limit_exceeded)?;
+			Ok(Value::Str(part.into()))
+		})
+		.collect::<InterpretResult<Vec<_>>>()?;
+
+	Ok(Value::List(parts.into()))
+}
+
+fn i_join<'i>(
+	CallableData {
+		i,
+		mut args,
+		call_site
+	}: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let sep = String::from(get_argument!(args => sep: Str));
+	let items = get_argument!(args => list: List).items().clone();
+
+	let limit_exceeded = limit_exceeded(&call_site);
+	let span_list = *call_site.args.nth_item(1).unwrap();
+
+	let mut parts = Vec::with_capacity(items.len());
+	let mut size = sep.len() * items.len().saturating_sub(1);
+
+	// Items are converted the same way as when they are printed
+	for item in &items {
+		i.limits.step().map_err(limit_exceeded)?;
+
+		let part = overload::stringify(i, item, span_list)?;
+		size += part.len();
+
+		// The size is checked as it grows, before the joined string is allocated
+		i.limits
+			.check_len(ValueKind::Str, size)
+			.map_err(limit_exceeded)?;
+
+		parts.push(part);
+	}
+
+	Ok(Value::Str(parts.join(&sep).into()))
+}
+
+fn i_trim<'i>(
+	CallableData { mut args, .. }: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let str = get_argument!(args => str: Str);
+	Ok(Value::Str(str.as_raw().trim().into()))
+}
+
+fn i_find<'i>(
+	CallableData { mut args, .. }: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let str = String::from(get_argument!(args => str: Str));
+	let pattern = String::from(get_argument!(args => pattern: Str));
+
+	// Positions are counted in characters, same as in `substr`
+	let index = str
+		.find(&pattern)
+		.map(|byte_idx| str[..byte_idx].chars().count());
+
+	Ok(match index {
+		Some(index) => Value::Num((index as f64).into()),
+		None => Value::None
+	})
+}
+
+fn i_replace<'i>(
+	CallableData {
+		i,
+		mut args,
+		call_site
+	}: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let str = String::from(get_argument!(args => str: Str));
+	let from = String::from(get_argument!(args => from: Str));
+	let to = String::from(get_argument!(args => to: Str));
+
+	if from.is_empty() {
+		return Ok(Value::Str(str.into()));
+	}
+
+	let count = str.matches(&from).count();
+	let size = str.len() - count * from.len() + count * to.len();
+
+	i.limits
+		.check_len(ValueKind::Str, size)
+		.map_err(limit_exceeded(&call_site))?;
+
+	Ok(Value::Str(str.replace(&from, &to).into()))
+}
+
+fn i_upper<'i>(
+	CallableData { mut args, .. }: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let str = get_argument!(args => str: Str);
+	Ok(Value::Str(str.as_raw().to_uppercase().into()))
+}
+
+fn i_lower<'i>(
+	CallableData { mut args, .. }: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let str = get_argument!(args => str: Str);
+	Ok(Value::Str(str.as_raw().to_lowercase().into()))
+}
+
+fn i_substr<'i>(
+	CallableData {
+		mut args,
+		call_site,
+		..
+	}: CallableData<'_, 'i>
+) -> InterpretResult<'i, Value<'i>> {
+	let chars = get_argument!(args => str: Str)
+		.as_raw()
+		.chars()
+		.collect::<Vec<_>>();
+
+	let start = index_arg(get_argument!(args => start: Num).into(), &call_site, 1)?;
+	let end = match get_argument!(args => end: Num?) {
+		Some(end) => index_arg(end.into(), &call_site, 2)?,
+		None => chars.len()
+	};
+
+	// Bounds are clamped to the string, same as in `slice`
+	let end = end.min(chars.len());
+	let start = start.min(end);
+
+	Ok(Value::Str(
+		chars[start..end].iter().collect::<String>().into()
+	))
+}
@@ -0,0 +1,260 @@
+use std::cell::{Ref, RefMut};
+use std::fmt::{self, Debug};
+use std::io::{self, BufRead, Write};
+
+use crate::Shared;
+
+/// Streams which the interpreter reads from and writes to.
+///
+/// All of the I/O performed by the intrinsics (`print`, `raw_print`, `input`, `debug`, etc.)
+/// goes through the implementation of this trait that the [`Interpreter`](crate::Interpreter) was built with.
+pub trait InterpreterIo {
+	/// Writes `bytes` to the output stream.
+	fn write_stdout(&mut self, bytes: &[u8]) -> io::Result<()>;
+
+	/// Writes `bytes` to the error stream.
+	fn write_stderr(&mut self, bytes: &[u8]) -> io::Result<()>;
+
+	/// Reads a single line from the input stream, including the trailing newline, and appends it to `buf`.
+	///
+	/// Returns the number of bytes read, `0` meaning that the input stream has been exhausted.
+	fn read_line(&mut self, buf: &mut String) -> io::Result<usize>;
+
+	/// Flushes any buffered output.
+	fn flush(&mut self) -> io::Result<()> { Ok(()) }
+}
+
+impl Debug for dyn InterpreterIo + '_ {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "<interpreter io>") }
+}
+
+/// Reads from and writes to the console of the process.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConsoleIo;
+
+impl InterpreterIo for ConsoleIo {
+	fn write_stdout(&mut self, bytes: &[u8]) -> io::Result<()> {
+		let mut stdout = io::stdout().lock();
+
+		stdout.write_all(bytes)?;
+		stdout.flush()
+	}
+
+	fn write_stderr(&mut self, bytes: &[u8]) -> io::Result<()> { io::stderr().write_all(bytes) }
+
+	fn read_line(&mut self, buf: &mut String) -> io::Result<usize> { io::stdin().read_line(buf) }
+
+	fn flush(&mut self) -> io::Result<()> { io::stdout().flush() }
+}
+
+/// Captures all of the output into in-memory buffers and reads the input from one.
+///
+/// Clones share the same buffers, so a handle can be kept around
+/// to script the input and inspect the output after the interpreter has been built.
+#[derive(Debug, Clone)]
+pub struct CaptureIo {
+	stdin: Shared<Vec<u8>>,
+	stdout: Shared<Vec<u8>>,
+	stderr: Shared<Vec<u8>>
+}
+
+impl CaptureIo {
+	pub fn new() -> Self {
+		Self {
+			stdin: Shared::new(vec![]),
+			stdout: Shared::new(vec![]),
+			stderr: Shared::new(vec![])
+		}
+	}
+
+	/// Input which has not been read yet.
+	pub fn stdin(&self) -> Ref<'_, Vec<u8>> { self.stdin.borrow() }
+
+	pub fn stdin_mut(&self) -> RefMut<'_, Vec<u8>> { self.stdin.borrow_mut() }
+
+	pub fn stdout(&self) -> Ref<'_, Vec<u8>> { self.stdout.borrow() }
+
+	pub fn stdout_mut(&self) -> RefMut<'_, Vec<u8>> { self.stdout.borrow_mut() }
+
+	pub fn stderr(&self) -> Ref<'_, Vec<u8>> { self.stderr.borrow() }
+
+	pub fn stderr_mut(&self) -> RefMut<'_, Vec<u8>> { self.stderr.borrow_mut() }
+}
+
+impl Default for CaptureIo {
+	fn default() -> Self { Self::new() }
+}
+
+impl InterpreterIo for CaptureIo {
+	fn write_stdout(&mut self, bytes: &[u8]) -> io::Result<()> {
+		self.stdout.borrow_mut().extend(bytes);
+		Ok(())
+	}
+
+	fn write_stderr(&mut self, bytes: &[u8]) -> io::Result<()> {
+		self.stderr.borrow_mut().extend(bytes);
+		Ok(())
+	}
+
+	fn read_line(&mut self, buf: &mut String) -> io::Result<usize> {
+		let mut stdin = self.stdin.borrow_mut();
+
+		let end = stdin
+			.iter()
+			.position(|&b| b == b'\n')
+			.map_or(stdin.len(), |idx| idx + 1);
+		let bytes = stdin.drain(..end).collect::<Vec<_>>();
+
+		buf.push_str(&String::from_utf8_lossy(&bytes));
+		Ok(bytes.len())
+	}
+}
+
+/// Reads from and writes to arbitrary [`BufRead`] and [`Write`] implementors.
+#[derive(Debug)]
+pub struct StreamIo<I, O, E> {
+	stdin: I,
+	stdout: O,
+	stderr: E
+}
+
+impl<I, O, E> StreamIo<I, O, E>
+where
+	I: BufRead,
+	O: Write,
+	E: Write
+{
+	pub fn new(stdin: I, stdout: O, stderr: E) -> Self {
+		Self {
+			stdin,
+			stdout,
+			stderr
+		}
+	}
+
+	pub fn into_inner(self) -> (I, O, E) { (self.stdin, self.stdout, self.stderr) }
+}
+
+impl<I, O, E> InterpreterIo for StreamIo<I, O, E>
+where
+	I: BufRead,
+	O: Write,
+	E: Write
+{
+	fn write_stdout(&mut self, bytes: &[u8]) -> io::Result<()> { self.stdout.write_all(bytes) }
+
+	fn write_stderr(&mut self, bytes: &[u8]) -> io::Result<()> { self.stderr.write_all(bytes) }
+
+	fn read_line(&mut self, buf: &mut String) -> io::Result<usize> { self.stdin.read_line(buf) }
+
+	fn flush(&mut self) -> io::Result<()> {
+		self.stdout.flush()?;
+		self.stderr.flush()
+	}
+}
+
+/// Buffers the output and passes it to the callbacks one line at a time, without the trailing newline.
+///
+/// Incomplete lines are passed on when the output is flushed.
+pub struct CallbackIo<I, O, E>
+where
+	I: BufRead,
+	O: FnMut(&str),
+	E: FnMut(&str)
+{
+	stdin: I,
+
+	on_stdout: O,
+	stdout_buf: Vec<u8>,
+
+	on_stderr: E,
+	stderr_buf: Vec<u8>
+}
+
+impl<I, O, E> CallbackIo<I, O, E>
+where
+	I: BufRead,
+	O: FnMut(&str),
+	E: FnMut(&str)
+{
+	pub fn new(stdin: I, on_stdout: O, on_stderr: E) -> Self {
+		Self {
+			stdin,
+
+			on_stdout,
+			stdout_buf: vec![],
+
+			on_stderr,
+			stderr_buf: vec![]
+		}
+	}
+
+	fn emit_lines<F>(buf: &mut Vec<u8>, callback: &mut F, flush: bool)
+	where
+		F: FnMut(&str)
+	{
+		while let Some(idx) = buf.iter().position(|&b| b == b'\n') {
+			let line = buf.drain(..=idx).collect::<Vec<_>>();
+			callback(&String::from_utf8_lossy(&line[..idx]));
+		}
+
+		if flush && !buf.is_empty() {
+			callback(&String::from_utf8_lossy(buf));
+			buf.clear();
+		}
+	}
+}
+
+impl<I, O, E> InterpreterIo for CallbackIo<I, O, E>
+where
+	I: BufRead,
+	O: FnMut(&str),
+	E: FnMut(&str)
+{
+	fn write_stdout(&mut self, bytes: &[u8]) -> io::Result<()> {
+		self.stdout_buf.extend(bytes);
+		Self::emit_lines(&mut self.stdout_buf, &mut self.on_stdout, false);
+
+		Ok(())
+	}
+
+	fn write_stderr(&mut self, bytes: &[u8]) -> io::Result<()> {
+		self.stderr_buf.extend(bytes);
+		Self::emit_lines(&mut self.stderr_buf, &mut self.on_stderr, false);
+
+		Ok(())
+	}
+
+	fn read_line(&mut self, buf: &mut String) -> io::Result<usize> { self.stdin.read_line(buf) }
+
+	fn flush(&mut self) -> io::Result<()> {
+		Self::emit_lines(&mut self.stdout_buf, &mut self.on_stdout, true);
+		Self::emit_lines(&mut self.stderr_buf, &mut self.on_stderr, true);
+
+		Ok(())
+	}
+}
+
+impl<I, O, E> Drop for CallbackIo<I, O, E>
+where
+	I: BufRead,
+	O: FnMut(&str),
+	E: FnMut(&str)
+{
+	fn drop(&mut self) { let _ = InterpreterIo::flush(self); }
+}
+
+impl<I, O, E> Debug for CallbackIo<I, O, E>
+where
+	I: BufRead + Debug,
+	O: FnMut(&str),
+	E: FnMut(&str)
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("CallbackIo")
+			.field("stdin", &self.stdin)
+			.field("stdout_buf", &self.stdout_buf)
+			.field("stderr_buf", &self.stderr_buf)
+			.finish_non_exhaustive()
+	}
+}
@@ -1,44 +1,43 @@
 // TODO: replace `Display` implementations with `Printable` when it's mature enough
 
+// `InterpretError` carries spans and values, boxing it everywhere is not worth the noise
+#![allow(clippy::result_large_err)]
+
 mod arg_parser;
 mod context;
 pub mod error;
 mod intrinsics;
+pub mod io;
+mod limits;
+mod module;
+mod ops;
+mod overload;
+mod resolver;
 mod shared;
 pub mod value;
+mod vm;
 
 pub use context::{Context, ContextFlags};
 pub use error::{InterpretError, InterpretErrorKind};
+pub use io::{CallbackIo, CaptureIo, ConsoleIo, InterpreterIo, StreamIo};
+pub use limits::CancelHandle;
 pub use shared::Shared;
-pub use value::{AsRaw, Primitive, Value, ValueKind};
-pub(crate) use value::{Callable, CallableData};
-
-use prog_parser::{ast, ASTNode};
+pub use prog_macros::{FromValue, IntoValue};
+pub use value::{AsRaw, FromValue, IntoValue, Primitive, Value, ValueKind};
+pub use arg_parser::{Arg, ArgList};
+pub use value::CallableData;
+pub(crate) use value::Callable;
 
-mod extension {
-	#[derive(Debug)]
-	pub(crate) struct ClassAcc<'a, 'i: 'a> {
-		pub(crate) eval_cache: crate::value::Class<'i>,
-		pub(crate) field_acc: &'a prog_parser::ast::FieldAcc<'i>
-	}
+use std::collections::HashMap;
+use std::rc::Rc;
 
-	#[derive(Debug)]
-	pub(crate) struct ClassInstanceAcc<'a, 'i: 'a> {
-		pub(crate) eval_cache: crate::value::ClassInstance<'i>,
-		pub(crate) field_acc: &'a prog_parser::ast::FieldAcc<'i>
-	}
-
-	#[derive(Debug)]
-	pub(crate) struct ClassInstanceAssign<'a, 'i: 'a> {
-		pub(crate) eval_cache: crate::value::ClassInstance<'i>,
-		pub(crate) field_assign: &'a prog_parser::ast::FieldAssign<'i>
-	}
-}
+use prog_parser::{ast, ASTNode, Span};
+use prog_utils::pretty_errors::TraceFrame;
 
 fn f64_to_usize(num: f64) -> Option<usize> {
 	let is_normal = num.is_normal() || num == 0.0;
 	let is_whole = num.fract() == 0.0;
-	let is_in_range = num <= (usize::MAX as f64);
+	let is_in_range = (0.0..=(usize::MAX as f64)).contains(&num);
 
 	if !is_normal || !is_whole || !is_in_range {
 		return None;
@@ -47,12 +46,57 @@ fn f64_to_usize(num: f64) -> Option<usize> {
 	Some(num as usize)
 }
 
+/// If the value is a function and has a `self` argument, binds it to `instance`
+/// and removes it from the argument list to prevent explicit `self` argument requirement:
+/// `some_instance.foo(some_instance)`
+fn bind_self<'ast>(value: &mut Value<'ast>, instance: value::ClassInstance<'ast>) {
+	use prog_parser::ast::{SelfKw, SuperKw};
+
+	if let Value::Func(func) = value {
+		if matches!(func.ast.args, ast::FuncArgs::WithSelf { .. }) {
+			// The scope is shared by every copy of the method, so the bound one gets its own
+			func.ctx = func.ctx.frame();
+
+			// The body of a derived class defines `super` as its parent class.
+			// Methods see the instance through it, so that `self` stays the same in the parent's methods
+			let parent = func.ctx.parent().and_then(|ctx| ctx.get(SuperKw::KEYWORD));
+
+			if let Some(Value::Class(parent)) = parent {
+				let viewed = instance.viewed_as(parent);
+				func.ctx
+					.insert(SuperKw::KEYWORD, Value::ClassInstance(viewed));
+			}
+
+			func.ctx
+				.insert(SelfKw::KEYWORD, Value::ClassInstance(instance.receiver()));
+
+			assert_eq!(
+				func.args.remove(0),
+				Some(arg_parser::Arg::RequiredUntyped(SelfKw::KEYWORD.into()))
+			);
+		}
+	}
+}
+
 pub type InterpretResult<'s, T> = Result<T, InterpretError<'s>>;
 
+/// Produces the index or key along with the item of every iteration of a `for` loop.
+type ForIter<'ast> = Box<
+	dyn FnMut(&mut Interpreter<'ast>) -> InterpretResult<'ast, Option<(Value<'ast>, Value<'ast>)>>
+		+ 'ast
+>;
+
 pub trait Evaluatable<'ast> {
 	type Output: Into<Value<'ast>>;
 
 	fn evaluate(&self, i: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output>;
+
+	/// Evaluates the node with the backend which the interpreter has been built with.
+	///
+	/// Only programs and expressions are compiled for the virtual machine, other nodes are always walked.
+	fn execute(&self, i: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output> {
+		self.evaluate(i)
+	}
 }
 
 pub trait EvaluatableOnce<'ast> {
@@ -63,42 +107,71 @@ pub trait EvaluatableOnce<'ast> {
 
 #[derive(Debug)]
 pub struct Interpreter<'ast> {
-	stdin: Vec<u8>,
-	stdout: Vec<u8>,
-
+	io: Box<dyn InterpreterIo>,
+
+	modules: module::ModuleLoader<'ast>,
+	/// Top-level context shared between all modules, holds the intrinsics.
+	globals: Context<'ast>,
+	/// Methods of the built-in values, by the kind of their receiver.
+	methods: HashMap<ValueKind, HashMap<&'static str, value::IntrinsicFn<'ast>>>,
+	/// Calls which are being evaluated, the outermost one first.
+	call_stack: Vec<TraceFrame<'ast>>,
+	max_call_depth: usize,
+	limits: limits::Limits,
+	backend: Backend,
+	/// Layouts of the scopes of the programs evaluated by the tree walker, by the nodes which open them.
+	layouts: HashMap<resolver::NodeKey, Rc<context::Layout>>,
 	pub context: Context<'ast>
 }
 
 impl<'ast> Interpreter<'ast> {
-	pub fn new() -> Self {
-		let table = intrinsics::IntrinsicTable::new();
-		Self::new_empty().populate(table)
-	}
+	pub fn new() -> Self { InterpreterBuilder::new().build() }
 
-	pub fn new_empty() -> Self {
-		Self {
-			stdin: vec![],
-			stdout: vec![],
+	pub fn new_empty() -> Self { InterpreterBuilder::new().intrinsics(false).build() }
 
-			context: Context::new()
-		}
-	}
+	pub fn builder() -> InterpreterBuilder { InterpreterBuilder::new() }
 
 	pub fn evaluate<N>(&mut self, node: N) -> InterpretResult<'ast, N::Output>
 	where
 		N: Evaluatable<'ast>
 	{
-		node.evaluate(self)
+		self.limits.start();
+		let result = node.execute(self);
+		self.limits.finish();
+
+		// Output which is still buffered by the I/O implementation has nowhere else to go,
+		// and failing to flush it should not hide the result of the evaluation
+		let _ = self.io.flush();
+
+		result
 	}
 
-	fn populate(self, table: intrinsics::IntrinsicTable<'ast>) -> Self {
+	/// Resolves the variables of the program as if it was evaluated in the current context,
+	/// without evaluating it.
+	///
+	/// Returns the errors which would prevent the evaluation from starting:
+	/// variables which are not defined, defined twice in the same scope, or used before their definition.
+	pub fn check(&self, program: &ast::Program<'ast>) -> Vec<InterpretError<'ast>> {
+		resolver::resolve_program(&self.context, program)
+			.err()
+			.unwrap_or_default()
+	}
+
+	fn populate(mut self, table: intrinsics::IntrinsicTable<'ast>) -> Self {
 		for intrinsic in table {
+			if let Value::IntrinsicFn(ref func) = intrinsic.value {
+				for kind in intrinsic.receivers {
+					let methods = self.methods.entry(*kind).or_default();
+					methods.insert(intrinsic.name, func.clone());
+				}
+			}
+
 			if intrinsic.auto_import {
 				let name = intrinsic.name.to_owned();
 				let value = intrinsic.value.clone();
 
 				assert!(
-					self.context.insert(name, value).is_none(),
+					self.globals.insert(name, value).is_none(),
 					"Attempted to override item `{}` with an intrinsic",
 					intrinsic.name
 				);
@@ -108,7 +181,7 @@ impl<'ast> Interpreter<'ast> {
 			let value = intrinsic.value.clone();
 
 			assert!(
-				self.context.insert_extern(name, value).is_none(),
+				self.globals.insert_extern(name, value).is_none(),
 				"Attempted to override extern item `{}`",
 				intrinsic.name
 			)
@@ -117,19 +190,259 @@ impl<'ast> Interpreter<'ast> {
 		self
 	}
 
-	pub fn stdin(&self) -> &[u8] { &self.stdin }
+	/// Registers a function implemented in Rust under `name`,
+	/// making it available both as a global and through `extern "name"`.
+	///
+	/// Arguments are verified against `args` before `func` is called.
+	/// Returns the global value which was previously registered under the same name, if any.
+	pub fn register_fn<N, F, R>(&mut self, name: N, args: ArgList, func: F) -> Option<Value<'ast>>
+	where
+		N: Into<String>,
+		F: for<'i> Fn(CallableData<'i, 'ast>) -> InterpretResult<'ast, R> + 'ast,
+		R: IntoValue<'ast>
+	{
+		let name = name.into();
+		let func = move |data: CallableData<'_, 'ast>| func(data).map(R::into_value);
+		let value = Value::IntrinsicFn(value::IntrinsicFn::from_fn(func, args));
+
+		self.globals.insert_extern(name.clone(), value.clone());
+		self.globals.insert(name, value)
+	}
+
+	/// Layout of the scope opened by the node at the span,
+	/// which is empty if the node has not been resolved before being walked.
+	fn layout(&self, span: Span<'ast>) -> Rc<context::Layout> {
+		self.layouts
+			.get(&resolver::key(span))
+			.cloned()
+			.unwrap_or_default()
+	}
+
+	/// Child of the current context, for the scope opened by the node at the span.
+	fn scope(&self, span: Span<'ast>) -> Context<'ast> {
+		self.context.child_with(self.layout(span))
+	}
+
+	/// Prepares the current context for the program which has been resolved,
+	/// keeping the layouts of its scopes for the tree walker.
+	fn enter_resolved(&mut self, resolution: &mut resolver::Resolution) {
+		self.context.declare(&resolution.declared);
 
-	pub fn stdin_mut(&mut self) -> &mut [u8] { &mut self.stdin }
+		if self.backend == Backend::TreeWalker {
+			self.layouts.extend(resolution.scopes.drain());
+		}
+	}
+
+	/// Method `name` of a built-in value, bound to the value written at `span`.
+	fn method(&self, receiver: Value<'ast>, name: &str, span: Span<'ast>) -> Option<Value<'ast>> {
+		let method = self.methods.get(&receiver.kind())?.get(name)?;
+		Some(Value::IntrinsicFn(method.bind_receiver(receiver, span)))
+	}
+
+	/// Calls which are being evaluated, the outermost one first.
+	pub fn call_stack(&self) -> &[TraceFrame<'ast>] { &self.call_stack }
+
+	/// Maximum number of nested calls, exceeding which fails with [`error::CallDepthExceeded`].
+	pub fn max_call_depth(&self) -> usize { self.max_call_depth }
+
+	/// Backend which evaluates the programs and expressions.
+	pub fn backend(&self) -> Backend { self.backend }
+
+	/// Number of statements and expressions which can still be evaluated, if limited.
+	pub fn fuel(&self) -> Option<u64> { self.limits.fuel }
+
+	/// Sets the number of statements and expressions which can be evaluated,
+	/// running out of which fails with [`error::FuelExhausted`].
+	pub fn set_fuel(&mut self, fuel: Option<u64>) { self.limits.fuel = fuel }
+
+	/// Handle which cancels the evaluation in progress, failing it with [`error::Cancelled`].
+	pub fn cancel_handle(&self) -> CancelHandle {
+		CancelHandle::new(std::sync::Arc::clone(&self.limits.cancelled))
+	}
+
+	/// Evaluates `f` as the call made at `call_site`.
+	///
+	/// Errors leaving the call are given the trace of the calls in progress, unless they already have one.
+	pub(crate) fn traced<F, T>(
+		&mut self,
+		call_site: &value::CallSite<'ast>,
+		f: F
+	) -> InterpretResult<'ast, T>
+	where
+		F: FnOnce(&mut Self) -> InterpretResult<'ast, T>
+	{
+		if self.call_stack.len() >= self.max_call_depth {
+			let error = InterpretError::new(
+				call_site.span(),
+				InterpretErrorKind::CallDepthExceeded(error::CallDepthExceeded {
+					max_depth: self.max_call_depth
+				})
+			);
+
+			return Err(self.with_trace(error));
+		}
+
+		let frame = TraceFrame::new(call_site.callee.value().to_owned(), call_site.span());
+		self.call_stack.push(frame);
+
+		let result = f(self).map_err(|error| self.with_trace(error));
+		self.call_stack.pop();
+
+		result
+	}
 
-	pub fn stdout(&self) -> &[u8] { &self.stdout }
+	/// Gives the error the trace of the calls in progress, unless it already has one.
+	pub(crate) fn with_trace(&self, error: InterpretError<'ast>) -> InterpretError<'ast> {
+		if !error.trace.is_empty() {
+			return error;
+		}
+
+		error.with_trace(self.call_stack.clone())
+	}
 
-	pub fn stdout_mut(&mut self) -> &mut [u8] { &mut self.stdout }
+	pub fn io(&self) -> &dyn InterpreterIo { self.io.as_ref() }
+
+	pub fn io_mut(&mut self) -> &mut dyn InterpreterIo { self.io.as_mut() }
 }
 
 impl Default for Interpreter<'_> {
 	fn default() -> Self { Self::new() }
 }
 
+/// Way in which the interpreter evaluates programs.
+///
+/// Both backends share the semantics, including the errors and the limits,
+/// and the values created by one of them can be used by the other.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Backend {
+	/// Evaluates the syntax tree directly.
+	#[default]
+	TreeWalker,
+	/// Compiles the syntax tree into bytecode and runs it on a stack-based virtual machine.
+	Vm
+}
+
+#[derive(Debug)]
+pub struct InterpreterBuilder {
+	io: Option<Box<dyn InterpreterIo>>,
+	flags: ContextFlags,
+	intrinsics: bool,
+	max_call_depth: usize,
+	limits: limits::Limits,
+	backend: Backend
+}
+
+impl InterpreterBuilder {
+	/// Maximum number of nested calls used unless configured otherwise.
+	pub const DEFAULT_MAX_CALL_DEPTH: usize = 1000;
+
+	pub fn new() -> Self {
+		Self {
+			io: None,
+			flags: ContextFlags::default(),
+			intrinsics: true,
+			max_call_depth: Self::DEFAULT_MAX_CALL_DEPTH,
+			limits: limits::Limits::default(),
+			backend: Backend::default()
+		}
+	}
+
+	/// Sets the streams which the interpreter reads from and writes to.
+	///
+	/// Defaults to [`ConsoleIo`].
+	pub fn io<I>(mut self, io: I) -> Self
+	where
+		I: InterpreterIo + 'static
+	{
+		self.io = Some(Box::new(io));
+		self
+	}
+
+	/// Sets the flags of the top-level context.
+	pub fn flags(mut self, flags: ContextFlags) -> Self {
+		self.flags = flags;
+		self
+	}
+
+	/// Sets whether the intrinsics should be registered.
+	pub fn intrinsics(mut self, intrinsics: bool) -> Self {
+		self.intrinsics = intrinsics;
+		self
+	}
+
+	/// Sets the maximum number of nested calls, exceeding which fails with [`error::CallDepthExceeded`].
+	///
+	/// Every call is evaluated on the native stack of the thread running the interpreter,
+	/// which must be large enough for the depth.
+	/// Defaults to [`Self::DEFAULT_MAX_CALL_DEPTH`].
+	pub fn max_call_depth(mut self, depth: usize) -> Self {
+		self.max_call_depth = depth;
+		self
+	}
+
+	/// Sets the number of statements and expressions which can be evaluated,
+	/// running out of which fails with [`error::FuelExhausted`].
+	///
+	/// The fuel is shared by all evaluations and can be refilled with [`Interpreter::set_fuel`].
+	pub fn fuel(mut self, fuel: u64) -> Self {
+		self.limits.fuel = Some(fuel);
+		self
+	}
+
+	/// Sets the time which each evaluation is allowed to run for,
+	/// exceeding which fails with [`error::DeadlineExceeded`].
+	pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
+		self.limits.timeout = Some(timeout);
+		self
+	}
+
+	/// Sets the maximum number of items of lists and objects, and bytes of strings,
+	/// which are produced by the expressions. Exceeding it fails with [`error::SizeLimitExceeded`].
+	pub fn max_size(mut self, max_size: usize) -> Self {
+		self.limits.max_size = Some(max_size);
+		self
+	}
+
+	/// Sets the backend which evaluates the programs and expressions.
+	///
+	/// Defaults to [`Backend::TreeWalker`].
+	pub fn backend(mut self, backend: Backend) -> Self {
+		self.backend = backend;
+		self
+	}
+
+	pub fn build<'ast>(self) -> Interpreter<'ast> {
+		let globals = Context::new();
+		globals.inner_mut().flags = self.flags;
+
+		let context = globals.child();
+
+		let interpreter = Interpreter {
+			io: self.io.unwrap_or_else(|| Box::new(ConsoleIo)),
+
+			modules: module::ModuleLoader::new(),
+			globals,
+			methods: HashMap::new(),
+			call_stack: vec![],
+			max_call_depth: self.max_call_depth,
+			limits: self.limits,
+			backend: self.backend,
+			layouts: HashMap::new(),
+			context
+		};
+
+		if !self.intrinsics {
+			return interpreter;
+		}
+
+		interpreter.populate(intrinsics::IntrinsicTable::new())
+	}
+}
+
+impl Default for InterpreterBuilder {
+	fn default() -> Self { Self::new() }
+}
+
 //* `Program` and `Stmt` *//
 
 // The `Evaluatable` implementations for `ast::Program` and `&[ast::Stmt]` differ in how they handle return values.
@@ -144,28 +457,39 @@ impl<'ast> Evaluatable<'ast> for ast::Program<'ast> {
 	type Output = Value<'ast>;
 
 	fn evaluate(&self, i: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output> {
-		let value = self.stmts.as_ref().evaluate(i)?;
-
-		if let Some(ctrl) = value {
-			return match ctrl {
-				value::CtrlFlow::Return(_, ret) => Ok(*ret),
-
-				_ => {
-					Err(InterpretError::new(
-						ctrl.span(),
-						InterpretErrorKind::CtxDisallowed(error::CtxDisallowed {
-							thing: ctrl.to_string(),
-							plural: false
-						})
-					))
-				}
-			};
+		let ctrl = self.stmts.as_ref().evaluate(i)?;
+		ops::program_result(ctrl)
+	}
+
+	fn execute(&self, i: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output> {
+		let mut resolution = resolver::resolve_program(&i.context, self).map_err(first_error)?;
+
+		let chunk = match i.backend {
+			Backend::TreeWalker => None,
+			Backend::Vm => Some(vm::compile_program(i, self, &resolution))
+		};
+
+		i.enter_resolved(&mut resolution);
+		let entry = i.modules.enter_entry(self);
+
+		let result = match chunk {
+			Some(chunk) => vm::run_program(i, &chunk),
+			None => self.evaluate(i)
+		};
+
+		if entry {
+			i.modules.exit_entry();
 		}
 
-		Ok(Value::None)
+		result
 	}
 }
 
+/// Error which is reported first out of the ones found by resolving a program.
+fn first_error<'ast>(mut errors: Vec<InterpretError<'ast>>) -> InterpretError<'ast> {
+	errors.remove(0)
+}
+
 impl<'ast> Evaluatable<'ast> for &[ast::Stmt<'ast>] {
 	type Output = Option<value::CtrlFlow<'ast>>;
 
@@ -184,6 +508,10 @@ impl<'ast> Evaluatable<'ast> for ast::Stmt<'ast> {
 	type Output = Value<'ast>;
 
 	fn evaluate(&self, i: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output> {
+		i.limits
+			.step()
+			.map_err(|kind| InterpretError::new(self.span(), kind))?;
+
 		match self {
 			Self::VarDefine(stmt) => stmt.evaluate(i).map(Value::from),
 			Self::VarAssign(stmt) => stmt.evaluate(i).map(Value::from),
@@ -191,11 +519,19 @@ impl<'ast> Evaluatable<'ast> for ast::Stmt<'ast> {
 			Self::Return(stmt) => stmt.evaluate(i).map(Value::from),
 			Self::Call(stmt) => stmt.evaluate(i),
 			Self::WhileLoop(stmt) => stmt.evaluate(i).map(Value::from),
+			Self::ForLoop(stmt) => stmt.evaluate(i).map(Value::from),
 			Self::Break(stmt) => stmt.evaluate(i).map(Value::from),
 			Self::Continue(stmt) => stmt.evaluate(i).map(Value::from),
 			Self::If(stmt) => stmt.evaluate(i).map(Value::from),
+			Self::Throw(stmt) => stmt.evaluate(i).map(Value::from),
+			Self::Try(stmt) => stmt.evaluate(i).map(Value::from),
 			Self::ExprAssign(stmt) => stmt.evaluate(i).map(Value::from),
-			Self::ClassDef(stmt) => stmt.evaluate(i).map(Value::from)
+			Self::ClassDef(stmt) => stmt.evaluate(i).map(Value::from),
+
+			Self::Error(stmt) => {
+				let kind = InterpretErrorKind::ParseFailed(stmt.error.kind.clone());
+				Err(InterpretError::new(stmt.error.span, kind))
+			}
 		}
 	}
 }
@@ -206,10 +542,33 @@ impl<'ast> Evaluatable<'ast> for ast::Expr<'ast> {
 	type Output = Value<'ast>;
 
 	fn evaluate(&self, i: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output> {
-		match self {
+		let error = |kind| InterpretError::new(self.span(), kind);
+
+		i.limits.step().map_err(error)?;
+
+		let value = match self {
 			Self::Binary(expr) => expr.evaluate(i),
 			Self::Unary(expr) => expr.evaluate(i),
 			Self::Term(expr) => expr.evaluate(i)
+		}?;
+
+		i.limits.check_size(&value).map_err(error)?;
+		Ok(value)
+	}
+
+	fn execute(&self, i: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output> {
+		let mut resolution = resolver::resolve_expr(&i.context, self).map_err(first_error)?;
+
+		let chunk = match i.backend {
+			Backend::TreeWalker => None,
+			Backend::Vm => Some(vm::compile_expr(i, self, &resolution))
+		};
+
+		i.enter_resolved(&mut resolution);
+
+		match chunk {
+			Some(chunk) => vm::run_program(i, &chunk),
+			None => self.evaluate(i)
 		}
 	}
 }
@@ -219,37 +578,19 @@ impl<'ast> Evaluatable<'ast> for ast::BinaryExpr<'ast> {
 
 	fn evaluate(&self, i: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output> {
 		use ast::BinaryOpKind as Op;
-		use Value as V;
-
-		let span_expr = self.span();
 
 		let lhs = self.lhs.evaluate(i)?;
-		let rhs = self.rhs.evaluate(i)?;
 
-		Ok(match (self.op.kind, lhs, rhs) {
-			(Op::Plus, V::Num(lhs), V::Num(rhs)) => V::Num(lhs + rhs),
-			(Op::Minus, V::Num(lhs), V::Num(rhs)) => V::Num(lhs - rhs),
-			(Op::Asterisk, V::Num(lhs), V::Num(rhs)) => V::Num(lhs * rhs),
-			(Op::Slash, V::Num(lhs), V::Num(rhs)) => V::Num(lhs / rhs),
-			(Op::Sign, V::Num(lhs), V::Num(rhs)) => V::Num(lhs % rhs),
-			(Op::Gt, V::Num(lhs), V::Num(rhs)) => V::Bool(value::Bool::from(lhs > rhs)),
-			(Op::Lt, V::Num(lhs), V::Num(rhs)) => V::Bool(value::Bool::from(lhs < rhs)),
-			(Op::Gte, V::Num(lhs), V::Num(rhs)) => V::Bool(value::Bool::from(lhs >= rhs)),
-			(Op::Lte, V::Num(lhs), V::Num(rhs)) => V::Bool(value::Bool::from(lhs <= rhs)),
-
-			(Op::Plus, V::Str(lhs), rhs) => V::Str(value::Str::from(format!("{lhs}{rhs}"))),
-
-			(Op::EqEq, lhs, rhs) => V::Bool(value::Bool::from(lhs == rhs)),
-			(Op::Neq, lhs, rhs) => V::Bool(value::Bool::from(lhs != rhs)),
-
-			// TODO
-			_ => {
-				return Err(InterpretError::new(
-					span_expr,
-					InterpretErrorKind::Unimplemented(error::Unimplemented)
-				))
-			}
-		})
+		// Logical operators short-circuit, resulting in the operand which decides the outcome
+		match self.op.kind {
+			Op::And if !lhs.is_truthy() => return Ok(lhs),
+			Op::Or if lhs.is_truthy() => return Ok(lhs),
+			Op::And | Op::Or => return self.rhs.evaluate(i),
+			_ => ()
+		}
+
+		let rhs = self.rhs.evaluate(i)?;
+		ops::binary(i, self.op.kind, lhs, rhs, self.span(), self.rhs.span())
 	}
 }
 
@@ -257,26 +598,8 @@ impl<'ast> Evaluatable<'ast> for ast::UnaryExpr<'ast> {
 	type Output = Value<'ast>;
 
 	fn evaluate(&self, i: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output> {
-		use ast::UnaryOpKind as Op;
-		use Value as V;
-
-		let span_expr = self.span();
-
 		let operand = self.operand.evaluate(i)?;
-
-		Ok(match (self.op.kind, operand) {
-			(Op::Minus, V::Num(operand)) => V::Num(-operand),
-
-			(Op::Not, V::Bool(operand)) => V::Bool(!operand),
-			(Op::Not, operand) => V::Bool(value::Bool::from(operand.is_truthy())),
-
-			_ => {
-				return Err(InterpretError::new(
-					span_expr,
-					InterpretErrorKind::Unimplemented(error::Unimplemented)
-				))
-			}
-		})
+		ops::unary(i, self.op.kind, operand, self.span())
 	}
 }
 
@@ -284,11 +607,12 @@ impl<'ast> Evaluatable<'ast> for ast::Term<'ast> {
 	type Output = Value<'ast>;
 
 	fn evaluate(&self, i: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output> {
-		match self {
+		let value = match self {
 			Self::Expr(expr) => expr.evaluate(i),
 			Self::ParenExpr(expr) => expr.expr.evaluate(i),
 
 			Self::Lit(lit) => lit.evaluate(i),
+			Self::InterpStr(str) => str.evaluate(i),
 			Self::Ident(ident) => ident.evaluate(i),
 			Self::Func(func) => func.evaluate(i).map(Value::Func),
 			Self::List(list) => list.evaluate(i).map(Value::List),
@@ -298,7 +622,14 @@ impl<'ast> Evaluatable<'ast> for ast::Term<'ast> {
 			Self::Call(call) => call.evaluate(i),
 			Self::IndexAcc(acc) => acc.evaluate(i),
 			Self::FieldAcc(acc) => acc.evaluate(i)
-		}
+		}?;
+
+		// Terms are checked as well, since receivers of methods and callees are not expressions
+		i.limits
+			.check_size(&value)
+			.map_err(|kind| InterpretError::new(self.span(), kind))?;
+
+		Ok(value)
 	}
 }
 
@@ -317,45 +648,40 @@ impl<'ast> Evaluatable<'ast> for ast::Lit<'ast> {
 	}
 }
 
-impl<'ast> Evaluatable<'ast> for ast::Ident<'ast> {
+impl<'ast> Evaluatable<'ast> for ast::InterpStr<'ast> {
 	type Output = Value<'ast>;
 
 	fn evaluate(&self, i: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output> {
-		let name = self.value();
-		let value = i.context.get(name);
+		let mut str = self.segments[0].value.clone();
 
-		value.ok_or(InterpretError::new(
-			self.span(),
-			InterpretErrorKind::VarDoesntExist(error::VarDoesntExist(self.value_owned()))
-		))
+		for (expr, segment) in self.exprs.iter().zip(&self.segments[1..]) {
+			let value = expr.evaluate(i)?;
+
+			str.push_str(&overload::stringify(i, &value, expr.span())?);
+			str.push_str(&segment.value);
+		}
+
+		Ok(Value::Str(value::Str::from(str)))
 	}
 }
 
-impl<'ast> Evaluatable<'ast> for ast::Func<'ast> {
-	type Output = value::Func<'ast>;
+impl<'ast> Evaluatable<'ast> for ast::Ident<'ast> {
+	type Output = Value<'ast>;
 
 	fn evaluate(&self, i: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output> {
-		use arg_parser::{Arg, ArgList};
-
-		let ctx = i.context.child();
-
-		let args = if !self.args.is_empty() {
-			let args = self
-				.args
-				.items()
-				.into_iter()
-				.map(|a| Arg::RequiredUntyped(Box::from(a.value())))
-				.collect::<Vec<_>>();
+		ops::var(i, self.value(), self.span())
+	}
+}
 
-			ArgList::new(args)
-		} else {
-			ArgList::new_empty()
-		};
+impl<'ast> Evaluatable<'ast> for ast::Func<'ast> {
+	type Output = value::Func<'ast>;
 
+	fn evaluate(&self, i: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output> {
 		Ok(value::Func {
 			ast: std::rc::Rc::new(self.clone()),
-			args,
-			ctx
+			args: ops::func_args(self),
+			ctx: i.scope(self._func.span()),
+			code: None
 		})
 	}
 }
@@ -417,25 +743,8 @@ impl<'ast> Evaluatable<'ast> for ast::Extern<'ast> {
 	type Output = Value<'ast>;
 
 	fn evaluate(&self, i: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output> {
-		let span_value = self.value.span();
-		let value = match self.value.evaluate(i)? {
-			Value::Str(s) => <value::Str as Into<String>>::into(s),
-
-			v => {
-				return Err(InterpretError::new(
-					span_value,
-					InterpretErrorKind::ArgTypeMismatch(error::ArgTypeMismatch {
-						expected: ValueKind::Str,
-						found: v.kind()
-					})
-				))
-			}
-		};
-
-		i.context.get_extern(&value).ok_or(InterpretError::new(
-			span_value,
-			InterpretErrorKind::InvalidExtern(error::InvalidExtern(value))
-		))
+		let value = self.value.evaluate(i)?;
+		ops::external(i, value, self.value.span())
 	}
 }
 
@@ -444,92 +753,18 @@ impl<'ast> Evaluatable<'ast> for ast::Call<'ast> {
 	type Output = Value<'ast>;
 
 	fn evaluate(&self, i: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output> {
-		use arg_parser::ArgumentParseError;
-		use prog_parser::{Position, Span};
-
-		let span_args = {
-			// Parentheses are included in the span in case the argument list is empty
-			let source = self.source();
-			let file = self.file();
-			let position = Position::new(self._lp.start(), self._rp.end());
-
-			Span::new(source, file, position)
-		};
-
-		let call_site = value::CallSite {
-			callee: self.callee.span(),
-			_lp: self._lp.span(),
-			args: self.args.map_ref(ASTNode::span, ASTNode::span),
-			_rp: self._rp.span()
-		};
-
-		let mut func = match self.callee.evaluate(i)? {
-			Value::Func(f) => Box::new(f) as Box<dyn Callable>,
-			Value::IntrinsicFn(f) => Box::new(f) as Box<dyn Callable>,
-			Value::Class(c) => Box::new(c) as Box<dyn Callable>,
-
-			v => {
-				return Err(InterpretError::new(
-					call_site.callee,
-					InterpretErrorKind::ExprNotCallable(error::ExprNotCallable {
-						expected: vec![ValueKind::Func, ValueKind::Class],
-						found: v.kind()
-					})
-				));
-			}
-		};
-
-		// TODO: is there really no way to write this better?
-		let (arg_spans, arg_values) = self
-			.args
-			.items()
-			.into_iter()
-			.map(|e| (e.span(), e.evaluate(i)))
-			.unzip::<_, _, Vec<_>, Vec<_>>();
-		let arg_values = arg_values
-			.into_iter()
-			.collect::<InterpretResult<Vec<_>>>()?;
+		let callee = self.callee.evaluate(i)?;
+		ops::check_callable(&callee, self.callee.span())?;
 
-		let parsed_args = func.arg_list().verify(&arg_values).map_err(|e| {
-			match e {
-				ArgumentParseError::CountMismatch {
-					expected,
-					end_boundary,
-					found
-				} => {
-					InterpretError::new(
-						span_args,
-						InterpretErrorKind::ArgCountMismatch(error::ArgCountMismatch {
-							expected,
-							end_boundary,
-							found
-						})
-					)
-				}
+		let mut arg_spans = vec![];
+		let mut args = vec![];
 
-				ArgumentParseError::IncorrectType {
-					index,
-					expected,
-					found
-				} => {
-					let arg_span = arg_spans.get(index).copied().unwrap();
-
-					InterpretError::new(
-						arg_span,
-						InterpretErrorKind::ArgTypeMismatch(error::ArgTypeMismatch {
-							expected,
-							found
-						})
-					)
-				}
-			}
-		})?;
+		for arg in self.args.items() {
+			arg_spans.push(arg.span());
+			args.push(arg.evaluate(i)?);
+		}
 
-		func.call(CallableData {
-			i,
-			args: parsed_args,
-			call_site
-		})
+		ops::call(i, callee, args, &arg_spans, ops::call_site(self))
 	}
 }
 
@@ -537,36 +772,18 @@ impl<'ast> Evaluatable<'ast> for ast::IndexAcc<'ast> {
 	type Output = Value<'ast>;
 
 	fn evaluate(&self, i: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output> {
-		let list = match self.list.evaluate(i)? {
-			Value::List(l) => l,
-			v => {
-				return Err(InterpretError::new(
-					self.list.span(),
-					InterpretErrorKind::CannotIndexExpr(error::CannotIndexExpr {
-						expected: vec![ValueKind::List],
-						found: v.kind()
-					})
-				))
-			}
-		};
+		let list = self.list.evaluate(i)?;
+		ops::check_indexable(&list, self.list.span())?;
 
-		let index = match self.index.evaluate(i)? {
-			Value::Num(n) => {
-				f64_to_usize(Into::<f64>::into(n)).ok_or(InterpretError::new(
-					self.index.span(),
-					InterpretErrorKind::InvalidIndex(error::InvalidIndex(Value::Num(n)))
-				))?
-			}
-
-			v => {
-				return Err(InterpretError::new(
-					self.index.span(),
-					InterpretErrorKind::InvalidIndex(error::InvalidIndex(v))
-				));
-			}
-		};
-
-		Ok(list.get(index).unwrap_or(Value::None))
+		let index = self.index.evaluate(i)?;
+		ops::index(
+			i,
+			list,
+			index,
+			self.span(),
+			self.list.span(),
+			self.index.span()
+		)
 	}
 }
 
@@ -574,96 +791,10 @@ impl<'ast> Evaluatable<'ast> for ast::FieldAcc<'ast> {
 	type Output = Value<'ast>;
 
 	fn evaluate(&self, i: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output> {
-		let obj = match self.object.evaluate(i)? {
-			Value::Obj(o) => o,
-
-			Value::Class(class) => {
-				let eval_cache = class;
-				let field_acc = self;
-				return extension::ClassAcc {
-					eval_cache,
-					field_acc
-				}
-				.evaluate_once(i);
-			}
-
-			Value::ClassInstance(class_inst) => {
-				let eval_cache = class_inst;
-				let field_acc = self;
-				return extension::ClassInstanceAcc {
-					eval_cache,
-					field_acc
-				}
-				.evaluate_once(i);
-			}
-
-			v => {
-				return Err(InterpretError::new(
-					self.object.span(),
-					InterpretErrorKind::CannotIndexExpr(error::CannotIndexExpr {
-						expected: vec![ValueKind::Obj, ValueKind::Class, ValueKind::ClassInstance],
-						found: v.kind()
-					})
-				));
-			}
-		};
-
-		Ok(obj.get(self.field.value()).unwrap_or(Value::None))
-	}
-}
-
-impl<'ast> EvaluatableOnce<'ast> for extension::ClassAcc<'_, 'ast> {
-	type Output = Value<'ast>;
-
-	fn evaluate_once(self, _: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output> {
-		let field = self.field_acc.field;
-		let Some(value) = self.eval_cache.get(field.value()) else {
-			return Err(InterpretError::new(
-				field.span(),
-				InterpretErrorKind::FieldDoesntExist(error::FieldDoesntExist {
-					class_name: self.eval_cache.name().to_owned(),
-					field_name: field.value_owned()
-				})
-			));
-		};
-
-		Ok(value)
-	}
-}
-
-impl<'ast> EvaluatableOnce<'ast> for extension::ClassInstanceAcc<'_, 'ast> {
-	type Output = Value<'ast>;
-
-	fn evaluate_once(self, _: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output> {
-		use prog_parser::ast::SelfKw;
-
-		let field = self.field_acc.field;
-		let Some(mut value) = self.eval_cache.get(field.value()) else {
-			return Err(InterpretError::new(
-				field.span(),
-				InterpretErrorKind::FieldDoesntExist(error::FieldDoesntExist {
-					class_name: self.eval_cache.name().to_owned(),
-					field_name: field.value_owned()
-				})
-			));
-		};
+		let object = self.object.evaluate(i)?;
+		let name = self.field.value();
 
-		// If the value is a function and has a `self` argument,
-		// remove it from the argument list to prevent explicit `self` argument requirement:
-		// `some_instance.foo(some_instance)`
-		if let Value::Func(func) = &mut value {
-			if matches!(func.ast.args, ast::FuncArgs::WithSelf { .. }) {
-				func.ctx
-					.insert(SelfKw::KEYWORD, Value::ClassInstance(self.eval_cache));
-
-				assert_eq!(
-					func.args.remove(0),
-					Some(arg_parser::Arg::RequiredUntyped(SelfKw::KEYWORD.into()))
-				);
-			}
-		}
-
-		Ok(value)
+		ops::field(i, object, name, self.object.span(), self.field.span())
 	}
 }
 
@@ -688,17 +819,8 @@ impl<'ast> Evaluatable<'ast> for ast::VarAssign<'ast> {
 	type Output = ();
 
 	fn evaluate(&self, i: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output> {
-		let name = self.name.value_owned();
 		let value = self.value.evaluate(i)?;
-
-		if i.context.update(&name, value).is_none() {
-			return Err(InterpretError::new(
-				self.name.span(),
-				InterpretErrorKind::VarDoesntExist(error::VarDoesntExist(name))
-			));
-		}
-
-		Ok(())
+		ops::assign_var(i, self.name.value(), value, self.name.span())
 	}
 }
 
@@ -706,7 +828,7 @@ impl<'ast> Evaluatable<'ast> for ast::DoBlock<'ast> {
 	type Output = Option<value::CtrlFlow<'ast>>;
 
 	fn evaluate(&self, i: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output> {
-		let original_ctx = i.context.swap(i.context.child());
+		let original_ctx = i.context.swap(i.scope(self._do.span()));
 		let result = self.stmts.as_ref().evaluate(i);
 		i.context.swap(original_ctx);
 
@@ -729,9 +851,7 @@ impl<'ast> Evaluatable<'ast> for ast::WhileLoop<'ast> {
 	type Output = Option<value::CtrlFlow<'ast>>;
 
 	fn evaluate(&self, i: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output> {
-		let mut cond_result = self.cond.evaluate(i)?;
-
-		while cond_result.is_truthy() {
+		while self.cond.evaluate(i)?.is_truthy() {
 			if let Some(ctrl) = self.block.evaluate(i)? {
 				match ctrl {
 					value::CtrlFlow::Return(..) => return Ok(Some(ctrl)),
@@ -739,8 +859,38 @@ impl<'ast> Evaluatable<'ast> for ast::WhileLoop<'ast> {
 					value::CtrlFlow::Continue(..) => continue
 				}
 			}
+		}
+
+		Ok(None)
+	}
+}
+
+impl<'ast> Evaluatable<'ast> for ast::ForLoop<'ast> {
+	type Output = Option<value::CtrlFlow<'ast>>;
+
+	fn evaluate(&self, i: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output> {
+		let iter = self.iter.evaluate(i)?;
+		let (mut next_pair, keyed) = ops::iterate(iter, self.iter.span())?;
+
+		let vars = (
+			self.var.value(),
+			self.value.as_ref().map(|(_, v)| v.value())
+		);
+
+		while let Some(pair) = next_pair(i)? {
+			let original_ctx = i.context.swap(i.scope(self._for.span()));
+			ops::bind_iteration(i, vars, keyed, pair);
+
+			let result = self.block.evaluate(i);
+			i.context.swap(original_ctx);
 
-			cond_result = self.cond.evaluate(i)?;
+			if let Some(ctrl) = result? {
+				match ctrl {
+					value::CtrlFlow::Return(..) => return Ok(Some(ctrl)),
+					value::CtrlFlow::Break(..) => break,
+					value::CtrlFlow::Continue(..) => continue
+				}
+			}
 		}
 
 		Ok(None)
@@ -807,126 +957,165 @@ impl<'ast> Evaluatable<'ast> for ast::Else<'ast> {
 	}
 }
 
-impl<'ast> Evaluatable<'ast> for ast::ExprAssign<'ast> {
+impl<'ast> Evaluatable<'ast> for ast::Throw<'ast> {
 	type Output = ();
 
 	fn evaluate(&self, i: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output> {
-		match self {
-			Self::IndexAssign(stmt) => stmt.evaluate(i),
-			Self::FieldAssign(stmt) => stmt.evaluate(i)
-		}
+		let value = self.value.evaluate(i)?;
+
+		Err(InterpretError::new(
+			self.span(),
+			InterpretErrorKind::Thrown(error::Thrown(value))
+		))
 	}
 }
 
-impl<'ast> Evaluatable<'ast> for ast::IndexAssign<'ast> {
-	type Output = ();
+impl<'ast> Evaluatable<'ast> for ast::Try<'ast> {
+	type Output = Option<value::CtrlFlow<'ast>>;
 
 	fn evaluate(&self, i: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output> {
-		let span_list = self.acc.list.span();
-		let span_index = self.acc.index.span();
-
-		let list = match self.acc.list.evaluate(i)? {
-			Value::List(l) => l,
-			v => {
-				return Err(InterpretError::new(
-					span_list,
-					InterpretErrorKind::ExprNotAssignable(error::ExprNotAssignable {
-						expected: vec![ValueKind::List, ValueKind::Obj],
-						found: v.kind()
-					})
-				));
+		let mut result = evaluate_scoped(i, self._try.span(), &self.stmts, None)
+			.map_err(|error| i.with_trace(error));
+
+		if let (Err(error), Some(branch)) = (&result, &self.b_catch) {
+			if error.kind.is_catchable() {
+				let value = error_value(error);
+				let var = Some((branch.name.value(), value));
+				result = evaluate_scoped(i, branch._catch.span(), &branch.stmts, var);
 			}
-		};
+		}
 
-		let index = match self.acc.index.evaluate(i)? {
-			Value::Num(n) => {
-				f64_to_usize(Into::<f64>::into(n)).ok_or(InterpretError::new(
-					span_index,
-					InterpretErrorKind::InvalidIndex(error::InvalidIndex(Value::Num(n)))
-				))?
+		// Control flow and errors of the `finally` branch take precedence over the pending result
+		if let Some(ref branch) = self.b_finally {
+			if let Some(ctrl) = evaluate_scoped(i, branch._finally.span(), &branch.stmts, None)? {
+				return Ok(Some(ctrl));
 			}
+		}
 
-			v => {
-				return Err(InterpretError::new(
-					span_index,
-					InterpretErrorKind::InvalidIndex(error::InvalidIndex(v))
-				));
-			}
-		};
+		result
+	}
+}
 
-		list.insert(index, self.value.evaluate(i)?);
-		Ok(())
+/// Evaluates the statements in the scope opened by the node at the span, optionally binding a variable in it.
+///
+/// The original scope is restored even if the statements fail midway.
+fn evaluate_scoped<'ast>(
+	i: &mut Interpreter<'ast>,
+	span: Span<'ast>,
+	stmts: &[ast::Stmt<'ast>],
+	var: Option<(&str, Value<'ast>)>
+) -> InterpretResult<'ast, Option<value::CtrlFlow<'ast>>> {
+	let original_ctx = i.context.swap(i.scope(span));
+
+	if let Some((name, value)) = var {
+		i.context.insert(name, value);
+	}
+
+	let result = stmts.evaluate(i);
+	i.context.swap(original_ctx);
+
+	result
+}
+
+/// Value which a caught error is bound to.
+///
+/// Thrown values are bound as they are, while runtime errors become objects of the form
+/// `{ message, kind, span = { file, start, end, line, column }, trace = [{ name, span }, ...] }`.
+fn error_value<'ast>(error: &InterpretError<'ast>) -> Value<'ast> {
+	use prog_utils::pretty_errors::AriadneCompatible;
+
+	if let Some(value) = error.kind.thrown() {
+		return value.clone();
 	}
+
+	let str = |s: &str| Value::Str(s.to_owned().into());
+
+	let trace = error
+		.trace
+		.iter()
+		.map(|frame| {
+			Value::Obj(
+				HashMap::from([
+					(String::from("name"), str(&frame.name)),
+					(String::from("span"), span_value(frame.span))
+				])
+				.into()
+			)
+		})
+		.collect::<Vec<_>>();
+
+	Value::Obj(
+		HashMap::from([
+			(String::from("message"), str(error.kind.message())),
+			(String::from("kind"), str(error.kind.name())),
+			(String::from("span"), span_value(error.span)),
+			(String::from("trace"), Value::List(trace.into()))
+		])
+		.into()
+	)
 }
 
-impl<'ast> Evaluatable<'ast> for ast::FieldAssign<'ast> {
+/// Object of the form `{ file, start, end, line, column }` describing the span.
+fn span_value<'ast>(span: Span<'ast>) -> Value<'ast> {
+	let position = span.position();
+	let preceding = &span.source()[..position.start()];
+
+	let line = preceding.matches('\n').count() + 1;
+	let column = match preceding.rfind('\n') {
+		Some(idx) => preceding[idx + 1..].chars().count() + 1,
+		None => preceding.chars().count() + 1
+	};
+
+	let num = |n: usize| Value::Num((n as f64).into());
+
+	Value::Obj(
+		HashMap::from([
+			(String::from("file"), Value::Str(span.file().to_owned().into())),
+			(String::from("start"), num(position.start())),
+			(String::from("end"), num(position.end())),
+			(String::from("line"), num(line)),
+			(String::from("column"), num(column))
+		])
+		.into()
+	)
+}
+
+impl<'ast> Evaluatable<'ast> for ast::ExprAssign<'ast> {
 	type Output = ();
 
 	fn evaluate(&self, i: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output> {
-		let span_obj = self.acc.object.span();
-
-		let obj = match self.acc.object.evaluate(i)? {
-			Value::Obj(o) => o,
-			Value::ClassInstance(c) => {
-				let eval_cache = c;
-				let field_assign = self;
-				return extension::ClassInstanceAssign {
-					eval_cache,
-					field_assign
-				}
-				.evaluate_once(i);
-			}
+		match self {
+			Self::IndexAssign(stmt) => stmt.evaluate(i),
+			Self::FieldAssign(stmt) => stmt.evaluate(i)
+		}
+	}
+}
 
-			v => {
-				return Err(InterpretError::new(
-					span_obj,
-					InterpretErrorKind::ExprNotAssignable(error::ExprNotAssignable {
-						expected: vec![ValueKind::List, ValueKind::Obj],
-						found: v.kind()
-					})
-				));
-			}
-		};
+impl<'ast> Evaluatable<'ast> for ast::IndexAssign<'ast> {
+	type Output = ();
 
-		obj.insert(self.acc.field.value(), self.value.evaluate(i)?);
-		Ok(())
+	fn evaluate(&self, i: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output> {
+		let list = self.acc.list.evaluate(i)?;
+		ops::check_index_assignable(&list, self.acc.list.span())?;
+
+		let index = self.acc.index.evaluate(i)?;
+		ops::check_assign_index(&list, &index, self.acc.index.span())?;
+
+		let value = self.value.evaluate(i)?;
+		ops::assign_index(i, list, index, value, self.span(), self.acc.index.span())
 	}
 }
 
-impl<'ast> EvaluatableOnce<'ast> for extension::ClassInstanceAssign<'_, 'ast> {
+impl<'ast> Evaluatable<'ast> for ast::FieldAssign<'ast> {
 	type Output = ();
 
-	fn evaluate_once(self, i: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output> {
-		let class_name = self.eval_cache.name().to_owned();
-		let field = self.field_assign.acc.field;
-		let field_name = field.value_owned();
-
-		let Some(field_value) = self.eval_cache.get(&field_name) else {
-			return Err(InterpretError::new(
-				field.span(),
-				InterpretErrorKind::FieldDoesntExist(error::FieldDoesntExist {
-					class_name,
-					field_name
-				})
-			));
-		};
-
-		// NOTE: Reassigning a function should only be invalid if the *actual* class
-		// is the one that has that function defined, *not its instance*
-		if matches!(field_value.kind(), ValueKind::Func | ValueKind::IntrinsicFn)
-			&& !self.eval_cache.contains(&field_name)
-		{
-			return Err(InterpretError::new(
-				field.span(),
-				InterpretErrorKind::ClassFnReassign(error::ClassFnReassign {
-					class_name,
-					field_name
-				})
-			));
-		}
+	fn evaluate(&self, i: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output> {
+		let object = self.acc.object.evaluate(i)?;
+		let name = self.acc.field.value();
+		ops::check_field_assignable(&object, name, self.acc.object.span(), self.acc.field.span())?;
 
-		let new_field_value = self.field_assign.value.evaluate(i)?;
-		self.eval_cache.insert(field_name, new_field_value);
+		let value = self.value.evaluate(i)?;
+		ops::assign_field(object, name, value);
 
 		Ok(())
 	}
@@ -938,54 +1127,58 @@ impl<'ast> Evaluatable<'ast> for ast::ClassDef<'ast> {
 	fn evaluate(&self, i: &mut Interpreter<'ast>) -> InterpretResult<'ast, Self::Output> {
 		use std::collections::hash_map::{Entry, HashMap};
 
-		let name = self.name.value_owned();
-		let fields = Shared::new(HashMap::new());
-
-		i.context.insert(
-			name.clone(),
-			Value::Class(value::Class::new(name.clone(), Shared::clone(&fields)))
-		);
-
-		let parent_ctx = i.context.swap(i.context.child());
-		i.context.insert(
-			prog_parser::ast::SelfKw::KEYWORD,
-			Value::Class(value::Class::new(name, Shared::clone(&fields)))
-		);
-
-		let mut field_positions = HashMap::new();
-		for field in self.fields.iter() {
-			let field_name = field.name();
-
-			match fields.borrow_mut().entry(field_name.value_owned()) {
-				Entry::Vacant(e) => {
-					let value = field
-						.value()
-						.map(|v| v.evaluate(i))
-						.unwrap_or(Ok(Value::None))?;
-
-					e.insert(value);
-					field_positions
-						.entry(field_name.value_owned())
-						.insert_entry(field_name.span());
-				}
-
-				Entry::Occupied(_) => {
-					let Some(def_name) = field_positions.get(ASTNode::value(&field_name)).copied()
-					else {
-						panic!(
-							"Mismatch between field maps: position of `{field_name}` is missing"
-						);
-					};
+		let parent = match &self.parent {
+			Some((_, expr)) => Some(ops::parent_class(expr.evaluate(i)?, expr.span())?),
+			None => None
+		};
 
-					return Err(InterpretError::new(
-						field_name.span(),
-						InterpretErrorKind::ClassFieldRedef(error::ClassFieldRedef { def_name })
-					));
+		let layout = i.layout(self._class.span());
+		let (parent_ctx, fields) = ops::enter_class(i, self.name.value_owned(), parent, layout);
+
+		// The scope of the class is left even if one of the fields fails
+		let mut evaluate_fields = || {
+			let mut field_positions = HashMap::new();
+			for field in self.fields.iter() {
+				let field_name = field.name();
+
+				match fields.borrow_mut().entry(field_name.value_owned()) {
+					Entry::Vacant(e) => {
+						let value = field
+							.value()
+							.map(|v| v.evaluate(i))
+							.unwrap_or(Ok(Value::None))?;
+
+						e.insert(value);
+						field_positions
+							.entry(field_name.value_owned())
+							.insert_entry(field_name.span());
+					}
+
+					Entry::Occupied(_) => {
+						let Some(def_name) =
+							field_positions.get(ASTNode::value(&field_name)).copied()
+						else {
+							panic!(
+								"Mismatch between field maps: position of `{field_name}` is missing"
+							);
+						};
+
+						return Err(InterpretError::new(
+							field_name.span(),
+							InterpretErrorKind::ClassFieldRedef(error::ClassFieldRedef {
+								def_name
+							})
+						));
+					}
 				}
 			}
-		}
 
+			Ok(())
+		};
+
+		let result = evaluate_fields();
 		i.context.swap(parent_ctx);
-		Ok(())
+
+		result
 	}
 }
@@ -0,0 +1,106 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::{error, AsRaw, InterpretErrorKind, Value, ValueKind};
+
+/// Resource limits of an interpreter, checked whenever a statement or an expression is evaluated.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Limits {
+	/// Number of statements and expressions which can still be evaluated.
+	pub(crate) fuel: Option<u64>,
+	/// Fuel at the start of the evaluation, reported once it runs out.
+	pub(crate) initial_fuel: u64,
+	/// Time which a single evaluation is allowed to run for.
+	pub(crate) timeout: Option<Duration>,
+	/// Point in time at which the evaluation in progress stops.
+	pub(crate) deadline: Option<Instant>,
+	/// Maximum number of items of lists and objects, or bytes of strings.
+	pub(crate) max_size: Option<usize>,
+	pub(crate) cancelled: Arc<AtomicBool>
+}
+
+impl Limits {
+	/// Prepares the limits for an evaluation started by the host.
+	pub(crate) fn start(&mut self) {
+		self.initial_fuel = self.fuel.unwrap_or_default();
+		self.deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+	}
+
+	/// Clears the state of the evaluation which has just finished.
+	pub(crate) fn finish(&mut self) {
+		self.deadline = None;
+		self.cancelled.store(false, Ordering::Relaxed);
+	}
+
+	/// Spends a unit of fuel on evaluating a statement or an expression.
+	pub(crate) fn step<'s>(&mut self) -> Result<(), InterpretErrorKind<'s>> {
+		if let Some(ref mut fuel) = self.fuel {
+			if *fuel == 0 {
+				return Err(InterpretErrorKind::FuelExhausted(error::FuelExhausted {
+					fuel: self.initial_fuel
+				}));
+			}
+
+			*fuel -= 1;
+		}
+
+		if self.cancelled.load(Ordering::Relaxed) {
+			return Err(InterpretErrorKind::Cancelled(error::Cancelled));
+		}
+
+		match (self.deadline, self.timeout) {
+			(Some(deadline), Some(timeout)) if Instant::now() >= deadline => Err(
+				InterpretErrorKind::DeadlineExceeded(error::DeadlineExceeded { timeout })
+			),
+
+			_ => Ok(())
+		}
+	}
+
+	/// Verifies that the value produced by an expression fits into the size limit.
+	pub(crate) fn check_size<'s>(&self, value: &Value<'s>) -> Result<(), InterpretErrorKind<'s>> {
+		let size = match value {
+			Value::List(list) => list.len(),
+			Value::Obj(obj) => obj.len(),
+			Value::Str(str) => str.as_raw().len(),
+			_ => return Ok(())
+		};
+
+		self.check_len(value.kind(), size)
+	}
+
+	/// Verifies that a value of the kind and the size fits into the size limit before it is produced.
+	pub(crate) fn check_len<'s>(
+		&self,
+		kind: ValueKind,
+		size: usize
+	) -> Result<(), InterpretErrorKind<'s>> {
+		match self.max_size {
+			Some(max_size) if size > max_size => Err(InterpretErrorKind::SizeLimitExceeded(
+				error::SizeLimitExceeded {
+					kind,
+					size,
+					max_size
+				}
+			)),
+
+			_ => Ok(())
+		}
+	}
+}
+
+/// Handle which cancels the evaluation of an interpreter, possibly from another thread.
+///
+/// The evaluation fails with [`error::Cancelled`] at the next statement or expression.
+/// Cancelling an interpreter which is not evaluating anything affects its next evaluation.
+#[derive(Debug, Clone)]
+pub struct CancelHandle(Arc<AtomicBool>);
+
+impl CancelHandle {
+	pub(crate) fn new(cancelled: Arc<AtomicBool>) -> Self { Self(cancelled) }
+
+	pub fn cancel(&self) { self.0.store(true, Ordering::Relaxed) }
+
+	pub fn is_cancelled(&self) -> bool { self.0.load(Ordering::Relaxed) }
+}
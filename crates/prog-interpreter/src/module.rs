@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use prog_parser::{ast, ASTNode};
+
+use crate::value::CallSite;
+use crate::{
+	error, Evaluatable, InterpretError, InterpretErrorKind, InterpretResult, Interpreter, Value
+};
+
+/// Name of the file which is loaded when a directory is imported.
+pub const MOD_FILE: &str = "mod.prog";
+/// Extension which is appended to import paths that do not specify one.
+pub const EXTENSION: &str = "prog";
+
+#[derive(Debug, Default)]
+pub(crate) struct ModuleLoader<'ast> {
+	/// Resulting values of the evaluated modules, keyed by their canonical paths.
+	cache: HashMap<PathBuf, Value<'ast>>,
+	/// Canonical and displayed paths of the modules which are currently being evaluated,
+	/// in import order.
+	stack: Vec<(PathBuf, String)>
+}
+
+impl ModuleLoader<'_> {
+	pub fn new() -> Self {
+		Self {
+			cache: HashMap::new(),
+			stack: vec![]
+		}
+	}
+
+	/// Puts the file of the program on the stack if it is the entry point of the evaluation,
+	/// so that importing it back is reported as a cycle instead of loading it a second time.
+	///
+	/// Returns whether the file has been put on the stack and has to be popped once the program is evaluated.
+	pub fn enter_entry(&mut self, program: &ast::Program) -> bool {
+		if !self.stack.is_empty() {
+			return false;
+		}
+
+		let Some(file) = program.stmts.first().map(|stmt| stmt.span().file()) else {
+			return false;
+		};
+
+		match Path::new(file).canonicalize() {
+			Ok(canonical) => {
+				self.stack.push((canonical, file.to_owned()));
+				true
+			}
+			Err(_) => false
+		}
+	}
+
+	pub fn exit_entry(&mut self) { self.stack.pop(); }
+
+	/// Returns the chain of modules which would be formed by importing `module`,
+	/// if that import closes a cycle.
+	fn find_cycle(&self, module: &(PathBuf, String)) -> Option<Vec<String>> {
+		let start = self.stack.iter().position(|(path, _)| *path == module.0)?;
+		let chain = self.stack[start..]
+			.iter()
+			.chain([module])
+			.map(|(_, name)| name.to_owned())
+			.collect();
+
+		Some(chain)
+	}
+}
+
+/// Resolves an import `path` relative to the directory of the `importer` file.
+///
+/// Directories resolve to their [`MOD_FILE`], and paths without an extension get [`EXTENSION`] appended.
+pub fn resolve<I, P>(importer: I, path: P) -> PathBuf
+where
+	I: AsRef<Path>,
+	P: AsRef<Path>
+{
+	let base = importer.as_ref().parent().unwrap_or(Path::new(""));
+	let mut resolved = base.join(path);
+
+	if resolved.is_dir() {
+		resolved.push(MOD_FILE);
+	}
+
+	if resolved.extension().is_none() {
+		resolved.set_extension(EXTENSION);
+	}
+
+	resolved
+}
+
+/// Loads, evaluates and caches the module at `path`, returning its resulting value.
+///
+/// Every module is evaluated at most once in its own top-level context,
+/// subsequent imports of the same file return the cached value.
+pub(crate) fn import<'ast>(
+	i: &mut Interpreter<'ast>,
+	path: &str,
+	call_site: &CallSite<'ast>
+) -> InterpretResult<'ast, Value<'ast>> {
+	let span_path = call_site.args.span();
+
+	let resolved = resolve(call_site.file(), path);
+	let invalid_file = || {
+		InterpretError::new(
+			span_path,
+			InterpretErrorKind::InvalidFile(error::InvalidFile(resolved.display().to_string()))
+		)
+	};
+
+	let canonical = resolved
+		.canonicalize()
+		.ok()
+		.filter(|p| p.is_file())
+		.ok_or_else(invalid_file)?;
+
+	if let Some(value) = i.modules.cache.get(&canonical) {
+		return Ok(value.clone());
+	}
+
+	let file = resolved.display().to_string();
+	let module = (canonical.clone(), file.clone());
+
+	if let Some(chain) = i.modules.find_cycle(&module) {
+		return Err(InterpretError::new(
+			span_path,
+			InterpretErrorKind::ImportCycle(error::ImportCycle { chain })
+		));
+	}
+
+	let source = std::fs::read_to_string(&canonical).map_err(|_| invalid_file())?;
+
+	// Values produced by the module keep references to its source for the rest of the program,
+	// and since every module is only loaded once, the source is intentionally leaked
+	let source: &'static str = Box::leak(source.replace("\r\n", "\n").into_boxed_str());
+	let file: &'static str = Box::leak(file.into_boxed_str());
+
+	let ts = prog_lexer::lex(source, file)
+		.map_err(|e| InterpretError::new(e.span, InterpretErrorKind::LexFailed(e.kind)))?;
+	let ast: ast::Program<'ast> = prog_parser::ParseStream::new(&ts)
+		.parse::<ast::Program>()
+		.map_err(|e| InterpretError::new(e.span, InterpretErrorKind::ParseFailed(e.kind)))?;
+
+	let module_ctx = i.globals.child();
+	module_ctx.inner_mut().flags = i.context.inner().flags;
+
+	i.modules.stack.push(module);
+	let parent_ctx = i.context.swap(module_ctx);
+	let result = ast.execute(i);
+	i.context.swap(parent_ctx);
+	i.modules.stack.pop();
+
+	let value = result?;
+	i.modules.cache.insert(canonical, value.clone());
+
+	Ok(value)
+}
@@ -0,0 +1,736 @@
+//! Operations shared by the tree walker and the virtual machine.
+//!
+//! Both backends evaluate the operands in the same order and hand them over to these functions,
+//! so that the results and the errors of the operations do not depend on the backend.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use prog_parser::{ast, ASTNode, Position, Span};
+
+use crate::arg_parser::{self, Arg, ArgList, ArgumentParseError};
+use crate::context::Layout;
+use crate::resolver::Binding;
+use crate::value::{CallSite, Class, CtrlFlow};
+use crate::{
+	bind_self, error, f64_to_usize, overload, value, AsRaw, Callable, CallableData, Context,
+	ForIter, InterpretError, InterpretErrorKind, InterpretResult, Interpreter, Shared, Value,
+	ValueKind
+};
+
+/// Value produced by a program or a function body, given the control flow which left it.
+pub(crate) fn program_result<'ast>(
+	ctrl: Option<CtrlFlow<'ast>>
+) -> InterpretResult<'ast, Value<'ast>> {
+	match ctrl {
+		None => Ok(Value::None),
+		Some(CtrlFlow::Return(_, value)) => Ok(*value),
+
+		Some(ctrl) => Err(InterpretError::new(
+			ctrl.span(),
+			InterpretErrorKind::CtxDisallowed(error::CtxDisallowed {
+				thing: ctrl.to_string(),
+				plural: false
+			})
+		))
+	}
+}
+
+//* Operators *//
+
+/// Applies a binary operator other than the short-circuiting `and` and `or`.
+pub(crate) fn binary<'ast>(
+	i: &mut Interpreter<'ast>,
+	op: ast::BinaryOpKind,
+	lhs: Value<'ast>,
+	rhs: Value<'ast>,
+	span_expr: Span<'ast>,
+	span_rhs: Span<'ast>
+) -> InterpretResult<'ast, Value<'ast>> {
+	use ast::BinaryOpKind as Op;
+	use Value as V;
+
+	// Class instances overload the operators with special methods
+	let method = overload::binary_method(op);
+
+	if let (V::ClassInstance(instance), Some(method)) = (&lhs, method) {
+		let args = vec![rhs.clone()];
+
+		if let Some(result) = overload::call(i, instance, method, args, span_expr)? {
+			return Ok(match op {
+				Op::Neq => V::Bool(value::Bool::from(!result.is_truthy())),
+				_ => result
+			});
+		}
+	}
+
+	Ok(match (op, lhs, rhs) {
+		(Op::Plus, V::Num(lhs), V::Num(rhs)) => V::Num(lhs + rhs),
+		(Op::Minus, V::Num(lhs), V::Num(rhs)) => V::Num(lhs - rhs),
+		(Op::Asterisk, V::Num(lhs), V::Num(rhs)) => V::Num(lhs * rhs),
+		(Op::Slash, V::Num(lhs), V::Num(rhs)) => V::Num(lhs / rhs),
+		(Op::Sign, V::Num(lhs), V::Num(rhs)) => V::Num(lhs % rhs),
+		(Op::Gt, V::Num(lhs), V::Num(rhs)) => V::Bool(value::Bool::from(lhs > rhs)),
+		(Op::Lt, V::Num(lhs), V::Num(rhs)) => V::Bool(value::Bool::from(lhs < rhs)),
+		(Op::Gte, V::Num(lhs), V::Num(rhs)) => V::Bool(value::Bool::from(lhs >= rhs)),
+		(Op::Lte, V::Num(lhs), V::Num(rhs)) => V::Bool(value::Bool::from(lhs <= rhs)),
+
+		(Op::Plus, V::Str(lhs), rhs) => {
+			let rhs = overload::stringify(i, &rhs, span_rhs)?;
+
+			// Checked before the concatenated string is allocated
+			i.limits
+				.check_len(ValueKind::Str, lhs.as_raw().len() + rhs.len())
+				.map_err(|kind| InterpretError::new(span_expr, kind))?;
+
+			V::Str(value::Str::from(format!("{lhs}{rhs}")))
+		}
+
+		(Op::EqEq, lhs, rhs) => V::Bool(value::Bool::from(lhs == rhs)),
+		(Op::Neq, lhs, rhs) => V::Bool(value::Bool::from(lhs != rhs)),
+
+		// TODO
+		_ => {
+			return Err(InterpretError::new(
+				span_expr,
+				InterpretErrorKind::Unimplemented(error::Unimplemented)
+			))
+		}
+	})
+}
+
+pub(crate) fn unary<'ast>(
+	i: &mut Interpreter<'ast>,
+	op: ast::UnaryOpKind,
+	operand: Value<'ast>,
+	span_expr: Span<'ast>
+) -> InterpretResult<'ast, Value<'ast>> {
+	use ast::UnaryOpKind as Op;
+	use Value as V;
+
+	let method = overload::unary_method(op);
+
+	if let (V::ClassInstance(instance), Some(method)) = (&operand, method) {
+		if let Some(result) = overload::call(i, instance, method, vec![], span_expr)? {
+			return Ok(result);
+		}
+	}
+
+	Ok(match (op, operand) {
+		(Op::Minus, V::Num(operand)) => V::Num(-operand),
+
+		(Op::Not, V::Bool(operand)) => V::Bool(!operand),
+		(Op::Not, operand) => V::Bool(value::Bool::from(operand.is_truthy())),
+
+		_ => {
+			return Err(InterpretError::new(
+				span_expr,
+				InterpretErrorKind::Unimplemented(error::Unimplemented)
+			))
+		}
+	})
+}
+
+//* Variables *//
+
+fn var_doesnt_exist<'ast>(name: &str, span: Span<'ast>) -> InterpretError<'ast> {
+	InterpretError::new(
+		span,
+		InterpretErrorKind::VarDoesntExist(error::VarDoesntExist(name.to_owned()))
+	)
+}
+
+pub(crate) fn var<'ast>(
+	i: &Interpreter<'ast>,
+	name: &str,
+	span: Span<'ast>
+) -> InterpretResult<'ast, Value<'ast>> {
+	i.context
+		.get(name)
+		.ok_or_else(|| var_doesnt_exist(name, span))
+}
+
+pub(crate) fn assign_var<'ast>(
+	i: &Interpreter<'ast>,
+	name: &str,
+	value: Value<'ast>,
+	span: Span<'ast>
+) -> InterpretResult<'ast, ()> {
+	match i.context.update(&name.to_owned(), value) {
+		Some(_) => Ok(()),
+		None => Err(var_doesnt_exist(name, span))
+	}
+}
+
+/// Variable `name` which the resolver has bound to the slot.
+///
+/// Fails if the variable has not been defined yet, e.g. when a function is called before
+/// a variable it refers to is defined.
+pub(crate) fn load<'ast>(
+	i: &Interpreter<'ast>,
+	binding: Binding,
+	name: &str,
+	span: Span<'ast>
+) -> InterpretResult<'ast, Value<'ast>> {
+	i.context
+		.get_at(binding)
+		.ok_or_else(|| var_doesnt_exist(name, span))
+}
+
+pub(crate) fn store<'ast>(
+	i: &Interpreter<'ast>,
+	binding: Binding,
+	name: &str,
+	value: Value<'ast>,
+	span: Span<'ast>
+) -> InterpretResult<'ast, ()> {
+	match i.context.set_at(binding, value) {
+		Some(_) => Ok(()),
+		None => Err(var_doesnt_exist(name, span))
+	}
+}
+
+/// Item registered under the name, which `value` must be a string of.
+pub(crate) fn external<'ast>(
+	i: &Interpreter<'ast>,
+	value: Value<'ast>,
+	span_value: Span<'ast>
+) -> InterpretResult<'ast, Value<'ast>> {
+	let value = match value {
+		Value::Str(s) => <value::Str as Into<String>>::into(s),
+
+		v => {
+			return Err(InterpretError::new(
+				span_value,
+				InterpretErrorKind::ArgTypeMismatch(error::ArgTypeMismatch {
+					expected: ValueKind::Str,
+					found: v.kind()
+				})
+			))
+		}
+	};
+
+	i.context.get_extern(&value).ok_or(InterpretError::new(
+		span_value,
+		InterpretErrorKind::InvalidExtern(error::InvalidExtern(value))
+	))
+}
+
+//* Functions *//
+
+/// Argument list of a function defined in the source.
+pub(crate) fn func_args(func: &ast::Func) -> ArgList {
+	if func.args.is_empty() {
+		return ArgList::new_empty();
+	}
+
+	let args = func
+		.args
+		.items()
+		.into_iter()
+		.map(|a| Arg::RequiredUntyped(Box::from(a.value())))
+		.collect::<Vec<_>>();
+
+	ArgList::new(args)
+}
+
+/// Place of a call in the source, made of the spans of its parts.
+pub(crate) fn call_site<'ast>(call: &ast::Call<'ast>) -> CallSite<'ast> {
+	CallSite {
+		callee: call.callee.span(),
+		_lp: call._lp.span(),
+		args: call.args.map_ref(ASTNode::span, ASTNode::span),
+		_rp: call._rp.span()
+	}
+}
+
+/// Checks that the value can be called, before the arguments are evaluated.
+pub(crate) fn check_callable<'ast>(
+	value: &Value<'ast>,
+	span_callee: Span<'ast>
+) -> InterpretResult<'ast, ()> {
+	match value {
+		Value::Func(_) | Value::IntrinsicFn(_) | Value::Class(_) => Ok(()),
+
+		v => Err(InterpretError::new(
+			span_callee,
+			InterpretErrorKind::ExprNotCallable(error::ExprNotCallable {
+				expected: vec![ValueKind::Func, ValueKind::Class],
+				found: v.kind()
+			})
+		))
+	}
+}
+
+/// Verifies the arguments against the argument list of the callee and calls it.
+pub(crate) fn call<'ast>(
+	i: &mut Interpreter<'ast>,
+	callee: Value<'ast>,
+	args: Vec<Value<'ast>>,
+	arg_spans: &[Span<'ast>],
+	call_site: CallSite<'ast>
+) -> InterpretResult<'ast, Value<'ast>> {
+	check_callable(&callee, call_site.callee)?;
+
+	let mut func = match callee {
+		Value::Func(f) => Box::new(f) as Box<dyn Callable>,
+		Value::IntrinsicFn(f) => Box::new(f) as Box<dyn Callable>,
+		Value::Class(c) => Box::new(c) as Box<dyn Callable>,
+		_ => unreachable!("Callee has been checked to be callable")
+	};
+
+	let parsed_args = func.arg_list().verify(&args).map_err(|e| {
+		match e {
+			ArgumentParseError::CountMismatch {
+				expected,
+				end_boundary,
+				found
+			} => {
+				// Parentheses are included in the span in case the argument list is empty
+				let span_args = Span::new(
+					call_site._lp.source(),
+					call_site._lp.file(),
+					Position::new(
+						call_site._lp.position().start(),
+						call_site._rp.position().end()
+					)
+				);
+
+				InterpretError::new(
+					span_args,
+					InterpretErrorKind::ArgCountMismatch(error::ArgCountMismatch {
+						expected,
+						end_boundary,
+						found
+					})
+				)
+			}
+
+			ArgumentParseError::IncorrectType {
+				index,
+				expected,
+				found
+			} => {
+				let arg_span = arg_spans.get(index).copied().unwrap();
+
+				InterpretError::new(
+					arg_span,
+					InterpretErrorKind::ArgTypeMismatch(error::ArgTypeMismatch { expected, found })
+				)
+			}
+		}
+	})?;
+
+	func.call(CallableData {
+		i,
+		args: parsed_args,
+		call_site
+	})
+}
+
+//* Access *//
+
+/// Checks that the value can be indexed, before the index is evaluated.
+pub(crate) fn check_indexable<'ast>(
+	value: &Value<'ast>,
+	span_list: Span<'ast>
+) -> InterpretResult<'ast, ()> {
+	match value {
+		Value::List(_) => Ok(()),
+		Value::ClassInstance(instance) if instance.get(overload::INDEX).is_some() => Ok(()),
+
+		v => Err(InterpretError::new(
+			span_list,
+			InterpretErrorKind::CannotIndexExpr(error::CannotIndexExpr {
+				expected: vec![ValueKind::List],
+				found: v.kind()
+			})
+		))
+	}
+}
+
+pub(crate) fn index<'ast>(
+	i: &mut Interpreter<'ast>,
+	value: Value<'ast>,
+	index: Value<'ast>,
+	span_acc: Span<'ast>,
+	span_list: Span<'ast>,
+	span_index: Span<'ast>
+) -> InterpretResult<'ast, Value<'ast>> {
+	check_indexable(&value, span_list)?;
+
+	match value {
+		Value::ClassInstance(instance) => {
+			overload::call(i, &instance, overload::INDEX, vec![index], span_acc)
+				.map(Option::unwrap_or_default)
+		}
+
+		Value::List(list) => {
+			let index = list_index(&index, span_index)?;
+			Ok(list.get(index).unwrap_or(Value::None))
+		}
+
+		_ => unreachable!("Value has been checked to be indexable")
+	}
+}
+
+fn list_index<'ast>(index: &Value<'ast>, span_index: Span<'ast>) -> InterpretResult<'ast, usize> {
+	let invalid = || {
+		InterpretError::new(
+			span_index,
+			InterpretErrorKind::InvalidIndex(error::InvalidIndex(index.clone()))
+		)
+	};
+
+	match index {
+		Value::Num(n) => f64_to_usize(Into::<f64>::into(*n)).ok_or_else(invalid),
+		_ => Err(invalid())
+	}
+}
+
+pub(crate) fn field<'ast>(
+	i: &mut Interpreter<'ast>,
+	value: Value<'ast>,
+	name: &str,
+	span_obj: Span<'ast>,
+	span_field: Span<'ast>
+) -> InterpretResult<'ast, Value<'ast>> {
+	let doesnt_exist = |class_name: &str| {
+		InterpretError::new(
+			span_field,
+			InterpretErrorKind::FieldDoesntExist(error::FieldDoesntExist {
+				class_name: class_name.to_owned(),
+				field_name: name.to_owned()
+			})
+		)
+	};
+
+	match value {
+		// Fields of an object take precedence over its methods
+		Value::Obj(obj) => Ok(obj
+			.get(name)
+			.or_else(|| i.method(Value::Obj(obj), name, span_obj))
+			.unwrap_or(Value::None)),
+
+		Value::Class(class) => class.get(name).ok_or_else(|| doesnt_exist(class.name())),
+
+		Value::ClassInstance(instance) => {
+			let mut value = instance
+				.get(name)
+				.ok_or_else(|| doesnt_exist(instance.name()))?;
+
+			bind_self(&mut value, instance);
+			Ok(value)
+		}
+
+		// Built-in values only have the methods of their kind
+		v if i.methods.contains_key(&v.kind()) => {
+			let kind = v.kind();
+
+			i.method(v, name, span_obj).ok_or_else(|| {
+				InterpretError::new(
+					span_field,
+					InterpretErrorKind::MethodDoesntExist(error::MethodDoesntExist {
+						kind,
+						method_name: name.to_owned()
+					})
+				)
+			})
+		}
+
+		v => Err(InterpretError::new(
+			span_obj,
+			InterpretErrorKind::CannotIndexExpr(error::CannotIndexExpr {
+				expected: vec![ValueKind::Obj, ValueKind::Class, ValueKind::ClassInstance],
+				found: v.kind()
+			})
+		))
+	}
+}
+
+//* Assignment *//
+
+/// Checks that an item of the value can be assigned, before the index is evaluated.
+pub(crate) fn check_index_assignable<'ast>(
+	value: &Value<'ast>,
+	span_list: Span<'ast>
+) -> InterpretResult<'ast, ()> {
+	match value {
+		Value::List(_) => Ok(()),
+		Value::ClassInstance(instance) if instance.get(overload::SET_INDEX).is_some() => Ok(()),
+
+		v => Err(InterpretError::new(
+			span_list,
+			InterpretErrorKind::ExprNotAssignable(error::ExprNotAssignable {
+				expected: vec![ValueKind::List, ValueKind::Obj],
+				found: v.kind()
+			})
+		))
+	}
+}
+
+/// Checks that the index is valid for the value, before the assigned value is evaluated.
+///
+/// Indices of lists are validated, while class instances are given any index.
+pub(crate) fn check_assign_index<'ast>(
+	value: &Value<'ast>,
+	index: &Value<'ast>,
+	span_index: Span<'ast>
+) -> InterpretResult<'ast, ()> {
+	match value {
+		Value::List(_) => list_index(index, span_index).map(|_| ()),
+		_ => Ok(())
+	}
+}
+
+pub(crate) fn assign_index<'ast>(
+	i: &mut Interpreter<'ast>,
+	target: Value<'ast>,
+	index: Value<'ast>,
+	value: Value<'ast>,
+	span_stmt: Span<'ast>,
+	span_index: Span<'ast>
+) -> InterpretResult<'ast, ()> {
+	match target {
+		Value::ClassInstance(instance) => {
+			let args = vec![index, value];
+			overload::call(i, &instance, overload::SET_INDEX, args, span_stmt).map(|_| ())
+		}
+
+		Value::List(list) => {
+			list.insert(list_index(&index, span_index)?, value);
+			Ok(())
+		}
+
+		_ => unreachable!("Value has been checked to be assignable")
+	}
+}
+
+/// Checks that the field of the value can be assigned, before the assigned value is evaluated.
+pub(crate) fn check_field_assignable<'ast>(
+	target: &Value<'ast>,
+	name: &str,
+	span_obj: Span<'ast>,
+	span_field: Span<'ast>
+) -> InterpretResult<'ast, ()> {
+	let instance = match target {
+		Value::Obj(_) => return Ok(()),
+		Value::ClassInstance(instance) => instance,
+
+		v => {
+			return Err(InterpretError::new(
+				span_obj,
+				InterpretErrorKind::ExprNotAssignable(error::ExprNotAssignable {
+					expected: vec![ValueKind::List, ValueKind::Obj],
+					found: v.kind()
+				})
+			));
+		}
+	};
+
+	let class_name = instance.name().to_owned();
+	let field_name = name.to_owned();
+
+	let Some(field_value) = instance.get(name) else {
+		return Err(InterpretError::new(
+			span_field,
+			InterpretErrorKind::FieldDoesntExist(error::FieldDoesntExist {
+				class_name,
+				field_name
+			})
+		));
+	};
+
+	// NOTE: Reassigning a function should only be invalid if the *actual* class
+	// is the one that has that function defined, *not its instance*
+	if matches!(field_value.kind(), ValueKind::Func | ValueKind::IntrinsicFn)
+		&& !instance.contains(name)
+	{
+		return Err(InterpretError::new(
+			span_field,
+			InterpretErrorKind::ClassFnReassign(error::ClassFnReassign {
+				class_name,
+				field_name
+			})
+		));
+	}
+
+	Ok(())
+}
+
+pub(crate) fn assign_field<'ast>(target: Value<'ast>, name: &str, value: Value<'ast>) {
+	match target {
+		Value::Obj(obj) => _ = obj.insert(name, value),
+		Value::ClassInstance(instance) => _ = instance.insert(name, value),
+		_ => unreachable!("Value has been checked to be assignable")
+	}
+}
+
+//* Loops *//
+
+/// Produces the pairs which the iterations of a `for` loop over the value are given,
+/// along with whether a single bound variable receives the key instead of the item.
+///
+/// Only objects are iterated over by their keys when a single variable is bound.
+pub(crate) fn iterate<'ast>(
+	value: Value<'ast>,
+	span_iter: Span<'ast>
+) -> InterpretResult<'ast, (ForIter<'ast>, bool)> {
+	let index = |idx: usize| Value::Num((idx as f64).into());
+	let not_iterable = |kind| {
+		InterpretError::new(
+			span_iter,
+			InterpretErrorKind::ExprNotIterable(error::ExprNotIterable(kind))
+		)
+	};
+
+	let next_pair: ForIter<'ast> = match value {
+		Value::List(list) => {
+			let mut items = list.unwrap_or_clone().into_iter().enumerate();
+			Box::new(move |_| Ok(items.next().map(|(idx, item)| (index(idx), item))))
+		}
+
+		Value::Str(str) => {
+			let chars = str.as_raw().chars().collect::<Vec<_>>();
+			let mut chars = chars.into_iter().enumerate();
+
+			Box::new(move |_| {
+				Ok(chars
+					.next()
+					.map(|(idx, char)| (index(idx), Value::Str(char.to_string().into()))))
+			})
+		}
+
+		Value::Obj(obj) => {
+			let mut entries = obj.entries().into_iter();
+
+			let next_pair: ForIter<'ast> = Box::new(move |_| {
+				Ok(entries
+					.next()
+					.map(|(name, value)| (Value::Str(name.into()), value)))
+			});
+
+			return Ok((next_pair, true));
+		}
+
+		// Class instances produce their items by calling `next` until it returns `none`
+		Value::ClassInstance(instance) => {
+			let Some(mut next) = instance.get("next") else {
+				return Err(not_iterable(ValueKind::ClassInstance));
+			};
+			bind_self(&mut next, instance);
+
+			let Value::Func(mut next) = next else {
+				return Err(not_iterable(ValueKind::ClassInstance));
+			};
+
+			if let Err(arg_parser::ArgumentParseError::CountMismatch {
+				expected,
+				end_boundary,
+				found
+			}) = next.arg_list().verify(&[])
+			{
+				return Err(InterpretError::new(
+					span_iter,
+					InterpretErrorKind::ArgCountMismatch(error::ArgCountMismatch {
+						expected,
+						end_boundary,
+						found
+					})
+				));
+			}
+
+			let call_site = CallSite {
+				callee: span_iter,
+				_lp: span_iter,
+				args: ast::Punctuated::new(),
+				_rp: span_iter
+			};
+			let mut idx = 0;
+
+			Box::new(move |i| {
+				let item = next.call(CallableData {
+					i,
+					args: HashMap::new(),
+					call_site: call_site.clone()
+				})?;
+
+				if matches!(item, Value::None) {
+					return Ok(None);
+				}
+
+				idx += 1;
+				Ok(Some((index(idx - 1), item)))
+			})
+		}
+
+		v => return Err(not_iterable(v.kind()))
+	};
+
+	Ok((next_pair, false))
+}
+
+/// Binds the variables of a `for` loop iteration in the current scope.
+pub(crate) fn bind_iteration<'ast>(
+	i: &Interpreter<'ast>,
+	(var, value_var): (&str, Option<&str>),
+	keyed: bool,
+	(key, item): (Value<'ast>, Value<'ast>)
+) {
+	match value_var {
+		Some(value_var) => {
+			i.context.insert(var, key);
+			i.context.insert(value_var, item);
+		}
+
+		None if keyed => _ = i.context.insert(var, key),
+		None => _ = i.context.insert(var, item)
+	}
+}
+
+//* Classes *//
+
+pub(crate) fn parent_class<'ast>(
+	value: Value<'ast>,
+	span: Span<'ast>
+) -> InterpretResult<'ast, Class<'ast>> {
+	match value {
+		Value::Class(class) => Ok(class),
+
+		v => Err(InterpretError::new(
+			span,
+			InterpretErrorKind::InvalidParentClass(error::InvalidParentClass(v.kind()))
+		))
+	}
+}
+
+/// Fields of a class, which are filled while its body is evaluated.
+pub(crate) type ClassFields<'ast> = Shared<HashMap<String, Value<'ast>>>;
+
+/// Defines the class in the current scope and enters the scope of its body,
+/// where `self` is the class and `super` is its parent.
+///
+/// Returns the scope to restore once the body has been evaluated, and the fields of the class.
+pub(crate) fn enter_class<'ast>(
+	i: &mut Interpreter<'ast>,
+	name: String,
+	parent: Option<Class<'ast>>,
+	layout: Rc<Layout>
+) -> (Context<'ast>, ClassFields<'ast>) {
+	use prog_parser::ast::{SelfKw, SuperKw};
+
+	let fields = Shared::new(HashMap::new());
+
+	let class = Class::new(name, parent.clone(), Shared::clone(&fields));
+	i.context
+		.insert(class.name().to_owned(), Value::Class(class.clone()));
+
+	let parent_ctx = i.context.swap(i.context.child_with(layout));
+	i.context.insert(SelfKw::KEYWORD, Value::Class(class));
+
+	if let Some(parent) = parent {
+		i.context.insert(SuperKw::KEYWORD, Value::Class(parent));
+	}
+
+	(parent_ctx, fields)
+}
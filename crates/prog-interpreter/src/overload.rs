@@ -0,0 +1,152 @@
+use prog_parser::{ast, Span};
+
+use crate::arg_parser::ArgumentParseError;
+use crate::value::{CallSite, ClassInstance};
+use crate::{
+	bind_self, error, Callable, CallableData, InterpretError, InterpretErrorKind, InterpretResult,
+	Interpreter, Value, ValueKind
+};
+
+/// Special method which overloads reading an item, `instance[index]`.
+pub(crate) const INDEX: &str = "__index";
+/// Special method which overloads assigning an item, `instance[index] = value`.
+pub(crate) const SET_INDEX: &str = "__setindex";
+/// Special method which overloads conversion into a string.
+pub(crate) const TO_STRING: &str = "__tostring";
+
+/// Special method which overloads the binary operator, if it can be overloaded.
+pub(crate) fn binary_method(op: ast::BinaryOpKind) -> Option<&'static str> {
+	use ast::BinaryOpKind as Op;
+
+	Some(match op {
+		Op::Plus => "__add",
+		Op::Minus => "__sub",
+		Op::Asterisk => "__mul",
+		Op::Slash => "__div",
+		Op::Sign => "__mod",
+		// `!=` results in the negation of `__eq`
+		Op::EqEq | Op::Neq => "__eq",
+		Op::Gt => "__gt",
+		Op::Lt => "__lt",
+		Op::Gte => "__ge",
+		Op::Lte => "__le",
+
+		Op::And | Op::Or | Op::LeftBracket | Op::Dot => return None
+	})
+}
+
+/// Special method which overloads the unary operator, if it can be overloaded.
+pub(crate) fn unary_method(op: ast::UnaryOpKind) -> Option<&'static str> {
+	use ast::UnaryOpKind as Op;
+
+	match op {
+		Op::Minus => Some("__neg"),
+		Op::Not => None
+	}
+}
+
+/// Calls the special method `name` of the instance with `args`, if the instance defines it.
+///
+/// Errors raised by the method point at both the `site` of the operation and the method definition.
+pub(crate) fn call<'ast>(
+	i: &mut Interpreter<'ast>,
+	instance: &ClassInstance<'ast>,
+	name: &str,
+	args: Vec<Value<'ast>>,
+	site: Span<'ast>
+) -> InterpretResult<'ast, Option<Value<'ast>>> {
+	let Some(mut method) = instance.get(name) else {
+		return Ok(None);
+	};
+	bind_self(&mut method, instance.clone());
+
+	let mut func = match method {
+		Value::Func(func) => func,
+
+		v => {
+			return Err(InterpretError::new(
+				site,
+				InterpretErrorKind::ExprNotCallable(error::ExprNotCallable {
+					expected: vec![ValueKind::Func],
+					found: v.kind()
+				})
+			));
+		}
+	};
+
+	let def = func.head_span();
+	let failed = |error: InterpretError<'ast>| {
+		// The trace of the method error already leads up to the operation
+		let trace = error.trace.clone();
+
+		InterpretError::new(
+			site,
+			InterpretErrorKind::SpecialMethodFailed(error::SpecialMethodFailed {
+				method: name.to_owned(),
+				def,
+				error: Box::new(error)
+			})
+		)
+		.with_trace(trace)
+	};
+
+	let parsed_args = func.arg_list().verify(&args).map_err(|e| {
+		let kind = match e {
+			ArgumentParseError::CountMismatch {
+				expected,
+				end_boundary,
+				found
+			} => InterpretErrorKind::ArgCountMismatch(error::ArgCountMismatch {
+				expected,
+				end_boundary,
+				found
+			}),
+
+			ArgumentParseError::IncorrectType {
+				expected, found, ..
+			} => InterpretErrorKind::ArgTypeMismatch(error::ArgTypeMismatch { expected, found })
+		};
+
+		failed(InterpretError::new(def, kind))
+	})?;
+
+	let call_site = CallSite {
+		callee: site,
+		_lp: site,
+		args: ast::Punctuated::new(),
+		_rp: site
+	};
+
+	func.call(CallableData {
+		i,
+		args: parsed_args,
+		call_site
+	})
+	.map(Some)
+	.map_err(failed)
+}
+
+/// Converts the value into a string, using `__tostring` for the class instances which define it.
+pub(crate) fn stringify<'ast>(
+	i: &mut Interpreter<'ast>,
+	value: &Value<'ast>,
+	site: Span<'ast>
+) -> InterpretResult<'ast, String> {
+	let Value::ClassInstance(instance) = value else {
+		return Ok(value.to_string());
+	};
+
+	match call(i, instance, TO_STRING, vec![], site)? {
+		Some(Value::Str(str)) => Ok(str.into()),
+
+		Some(v) => Err(InterpretError::new(
+			site,
+			InterpretErrorKind::ConversionFailed(error::ConversionFailed::new(
+				ValueKind::Str.to_string(),
+				v.kind()
+			))
+		)),
+
+		None => Ok(value.to_string())
+	}
+}
@@ -0,0 +1,584 @@
+//! Static resolution of the variables, which runs before a program is evaluated.
+//!
+//! Every scope which the evaluation creates is laid out in advance, declaring all of the variables
+//! defined in it, including the ones defined further down. Every name is then bound to the innermost
+//! scope which declares it and to its slot there, reporting the variables which are not defined at all,
+//! defined twice in the same scope, or used before their definition.
+//!
+//! Scopes are opened by the program, `do` blocks and loop bodies, iterations of `for` loops,
+//! branches of `try` statements, functions and class bodies. Branches of `if` statements
+//! share the scope around them.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use prog_parser::ast::{self, SelfKw, SuperKw};
+use prog_parser::{ASTNode, Span};
+
+use crate::context::Layout;
+use crate::{error, Context, InterpretError, InterpretErrorKind};
+
+/// Position of a variable relative to the scope which refers to it:
+/// the number of scopes to go up, and the slot in the scope reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Binding {
+	pub(crate) depth: usize,
+	pub(crate) slot: usize
+}
+
+/// Identity of a node among all of the sources evaluated by an interpreter.
+pub(crate) type NodeKey = (usize, usize);
+
+pub(crate) fn key(span: Span) -> NodeKey {
+	(span.source().as_ptr() as usize, span.position().start())
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Resolution {
+	/// Bindings of the names which are referred to, assigned or defined, by the keys of the names.
+	bindings: HashMap<NodeKey, Binding>,
+	/// Layouts of the scopes, by the keys of the nodes which open them.
+	pub(crate) scopes: HashMap<NodeKey, Rc<Layout>>,
+	/// Variables which the program adds to the scope it is evaluated in.
+	pub(crate) declared: Vec<String>
+}
+
+impl Resolution {
+	pub(crate) fn binding(&self, name: &ast::Ident) -> Binding {
+		match self.bindings.get(&key(name.span())) {
+			Some(&binding) => binding,
+			None => panic!("Name `{name}` has not been resolved")
+		}
+	}
+
+	/// Layout of the scope opened by the node at the span.
+	pub(crate) fn layout(&self, span: Span) -> Rc<Layout> {
+		match self.scopes.get(&key(span)) {
+			Some(layout) => Rc::clone(layout),
+			None => panic!("Scope at {:?} has not been resolved", span.position())
+		}
+	}
+}
+
+/// Resolves the program, which is going to be evaluated in the context.
+pub(crate) fn resolve_program<'ast>(
+	ctx: &Context<'ast>,
+	program: &ast::Program<'ast>
+) -> Result<Resolution, Vec<InterpretError<'ast>>> {
+	let mut resolver = Resolver::new(ctx);
+
+	resolver.hoist(&program.stmts);
+	resolver.stmts(&program.stmts);
+	resolver.finish()
+}
+
+/// Resolves the expression, which is going to be evaluated in the context.
+pub(crate) fn resolve_expr<'ast>(
+	ctx: &Context<'ast>,
+	expr: &ast::Expr<'ast>
+) -> Result<Resolution, Vec<InterpretError<'ast>>> {
+	let mut resolver = Resolver::new(ctx);
+
+	resolver.expr(expr);
+	resolver.finish()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ScopeKind {
+	Block,
+	/// Scope of a function's arguments and body, which is evaluated once the function is called.
+	Func,
+	/// Body of a class, where `self` and `super` are the class and its parent.
+	Class
+}
+
+/// State of a variable at the point which the resolver has reached.
+#[derive(Debug, Clone, Copy)]
+enum Var<'ast> {
+	/// Defined before the program is evaluated, implicitly by the scope, or by a class definition.
+	Existing,
+	/// Declared by the context, but never defined.
+	Undefined,
+	/// Defined further down, by the definition with the name at the span.
+	Declared(Span<'ast>),
+	/// Defined by the definition with the name at the span.
+	Defined(Span<'ast>)
+}
+
+#[derive(Debug)]
+struct Scope<'ast> {
+	kind: ScopeKind,
+	layout: Layout,
+	/// States of the variables, by their slots.
+	vars: Vec<Var<'ast>>
+}
+
+impl<'ast> Scope<'ast> {
+	fn new(kind: ScopeKind) -> Self {
+		Self {
+			kind,
+			layout: Layout::default(),
+			vars: vec![]
+		}
+	}
+
+	/// Slot of the variable, declaring it in the given state if it is not declared yet.
+	fn declare(&mut self, name: &str, var: Var<'ast>) -> usize {
+		if let Some(slot) = self.layout.slot(name) {
+			return slot;
+		}
+
+		self.vars.push(var);
+		self.layout.declare(name.to_owned())
+	}
+}
+
+struct Resolver<'ast> {
+	resolution: Resolution,
+	errors: Vec<InterpretError<'ast>>,
+	/// Scopes entered at the current point, the outermost one being the one the program is evaluated in.
+	scopes: Vec<Scope<'ast>>,
+	/// Number of variables declared by the context the program is evaluated in.
+	existing: usize,
+	/// Contexts around the one the program is evaluated in.
+	outer: Option<Context<'ast>>
+}
+
+impl<'ast> Resolver<'ast> {
+	fn new(ctx: &Context<'ast>) -> Self {
+		let inner = ctx.inner();
+		let layout = inner.layout().clone();
+
+		let vars = (0..layout.len())
+			.map(|slot| match inner.is_defined(slot) {
+				true => Var::Existing,
+				false => Var::Undefined
+			})
+			.collect();
+
+		Self {
+			resolution: Resolution::default(),
+			errors: vec![],
+			existing: layout.len(),
+			scopes: vec![Scope {
+				kind: ScopeKind::Block,
+				layout,
+				vars
+			}],
+			outer: ctx.parent()
+		}
+	}
+
+	fn finish(mut self) -> Result<Resolution, Vec<InterpretError<'ast>>> {
+		if !self.errors.is_empty() {
+			return Err(self.errors);
+		}
+
+		let Some(top) = self.scopes.pop() else {
+			panic!("Scope of the program has been exited");
+		};
+
+		self.resolution.declared = top.layout.names()[self.existing..].to_vec();
+		Ok(self.resolution)
+	}
+
+	fn error(&mut self, span: Span<'ast>, kind: InterpretErrorKind<'ast>) {
+		self.errors.push(InterpretError::new(span, kind));
+	}
+
+	fn scope(&mut self) -> &mut Scope<'ast> {
+		self.scopes
+			.last_mut()
+			.expect("Attempted to resolve outside of a scope")
+	}
+
+	fn enter(&mut self, kind: ScopeKind) { self.scopes.push(Scope::new(kind)); }
+
+	/// Exits the innermost scope, which is opened by the node at the span.
+	fn exit(&mut self, span: Span<'ast>) {
+		let scope = self
+			.scopes
+			.pop()
+			.expect("Attempted to exit a scope which was not entered");
+
+		self.resolution
+			.scopes
+			.insert(key(span), Rc::new(scope.layout));
+	}
+
+	/// Declares the variables defined by the statements in the innermost scope,
+	/// including the ones defined in the branches of `if` statements.
+	fn hoist(&mut self, stmts: &[ast::Stmt<'ast>]) {
+		for stmt in stmts {
+			let name = match stmt {
+				ast::Stmt::VarDefine(stmt) => stmt.name(),
+				ast::Stmt::ClassDef(stmt) => stmt.name,
+
+				ast::Stmt::If(stmt) => {
+					self.hoist(&stmt.stmts);
+
+					for branch in stmt.b_elifs.iter() {
+						self.hoist(&branch.stmts);
+					}
+
+					if let Some(ref branch) = stmt.b_else {
+						self.hoist(&branch.stmts);
+					}
+
+					continue;
+				}
+
+				_ => continue
+			};
+
+			let scope = self.scope();
+			let slot = scope.declare(name.value(), Var::Declared(name.span()));
+
+			// Variables left undefined by the previous evaluations are defined by this one
+			if let Var::Undefined = scope.vars[slot] {
+				scope.vars[slot] = Var::Declared(name.span());
+			}
+		}
+	}
+
+	/// Defines the variable which has been declared in the innermost scope, with `def` or `class`.
+	fn define(&mut self, name: &ast::Ident<'ast>) {
+		let scope = self.scope();
+		let Some(slot) = scope.layout.slot(name.value()) else {
+			panic!("Variable `{name}` has not been declared");
+		};
+
+		let previous = std::mem::replace(&mut scope.vars[slot], Var::Defined(name.span()));
+
+		self.resolution
+			.bindings
+			.insert(key(name.span()), Binding { depth: 0, slot });
+
+		if let Var::Defined(def_name) = previous {
+			let kind = InterpretErrorKind::VarRedef(error::VarRedef { def_name });
+			self.error(name.span(), kind);
+		}
+	}
+
+	/// Defines a variable which the innermost scope is entered with, such as an argument.
+	fn bind(&mut self, name: &str, span: Span<'ast>) {
+		let scope = self.scope();
+		let slot = scope.declare(name, Var::Existing);
+
+		if let Var::Defined(def_name) = std::mem::replace(&mut scope.vars[slot], Var::Defined(span))
+		{
+			let kind = InterpretErrorKind::VarRedef(error::VarRedef { def_name });
+			self.error(span, kind);
+		}
+	}
+
+	/// Binds the name to the variable it refers to.
+	fn reference(&mut self, name: &ast::Ident<'ast>) {
+		let span = name.span();
+		let name = name.value();
+
+		match self.lookup(name) {
+			Ok(binding) => {
+				self.resolution.bindings.insert(key(span), binding);
+			}
+
+			Err(kind) => self.error(span, kind)
+		}
+	}
+
+	fn lookup(&self, name: &str) -> Result<Binding, InterpretErrorKind<'ast>> {
+		let doesnt_exist =
+			|| InterpretErrorKind::VarDoesntExist(error::VarDoesntExist(name.to_owned()));
+
+		// Functions are called once the scopes around them are evaluated further,
+		// so the variables of those scopes may be used before their definitions
+		let mut deferred = false;
+
+		for (depth, scope) in self.scopes.iter().rev().enumerate() {
+			let Some(slot) = scope.layout.slot(name) else {
+				deferred |= scope.kind == ScopeKind::Func;
+				continue;
+			};
+
+			return match scope.vars[slot] {
+				Var::Undefined => Err(doesnt_exist()),
+
+				Var::Declared(def_name) if !deferred => Err(InterpretErrorKind::VarUsedBeforeDef(
+					error::VarUsedBeforeDef {
+						name: name.to_owned(),
+						def_name
+					}
+				)),
+
+				_ => Ok(Binding { depth, slot })
+			};
+		}
+
+		self.outer_binding(name).ok_or_else(doesnt_exist)
+	}
+
+	/// Binding of the variable which is defined by the contexts around the one the program is evaluated in.
+	fn outer_binding(&self, name: &str) -> Option<Binding> {
+		let mut depth = self.scopes.len();
+		let mut current = self.outer.clone();
+
+		while let Some(ctx) = current {
+			let inner = ctx.inner();
+
+			if let Some(slot) = inner.layout().slot(name) {
+				return inner.is_defined(slot).then_some(Binding { depth, slot });
+			}
+
+			depth += 1;
+			current = ctx.parent();
+		}
+
+		None
+	}
+
+	//* Statements *//
+
+	fn stmts(&mut self, stmts: &[ast::Stmt<'ast>]) {
+		for stmt in stmts {
+			self.stmt(stmt);
+		}
+	}
+
+	fn stmt(&mut self, stmt: &ast::Stmt<'ast>) {
+		use ast::Stmt;
+
+		match stmt {
+			Stmt::VarDefine(stmt) => {
+				if let Some(value) = stmt.value() {
+					self.expr(&value);
+				}
+
+				self.define(&stmt.name());
+			}
+
+			Stmt::VarAssign(stmt) => {
+				self.expr(&stmt.value);
+				self.reference(&stmt.name);
+			}
+
+			Stmt::DoBlock(stmt) => self.block(stmt),
+			Stmt::Return(stmt) => self.expr(&stmt.value),
+			Stmt::Call(call) => self.call(call),
+
+			Stmt::WhileLoop(stmt) => {
+				self.expr(&stmt.cond);
+				self.block(&stmt.block);
+			}
+
+			// Every iteration has its own scope, holding the variables
+			Stmt::ForLoop(stmt) => {
+				self.expr(&stmt.iter);
+				self.enter(ScopeKind::Block);
+
+				let value = stmt.value.as_ref().map(|(_, value)| value);
+				for var in std::iter::once(&stmt.var).chain(value) {
+					self.bind(var.value(), var.span());
+				}
+
+				self.block(&stmt.block);
+				self.exit(stmt._for.span());
+			}
+
+			Stmt::Break(_) | Stmt::Continue(_) => (),
+
+			Stmt::If(stmt) => {
+				self.expr(&stmt.cond);
+				self.stmts(&stmt.stmts);
+
+				for branch in stmt.b_elifs.iter() {
+					self.expr(&branch.cond);
+					self.stmts(&branch.stmts);
+				}
+
+				if let Some(ref branch) = stmt.b_else {
+					self.stmts(&branch.stmts);
+				}
+			}
+
+			Stmt::Throw(stmt) => self.expr(&stmt.value),
+
+			Stmt::Try(stmt) => {
+				self.scoped(stmt._try.span(), None, &stmt.stmts);
+
+				if let Some(ref branch) = stmt.b_catch {
+					self.scoped(branch._catch.span(), Some(&branch.name), &branch.stmts);
+				}
+
+				if let Some(ref branch) = stmt.b_finally {
+					self.scoped(branch._finally.span(), None, &branch.stmts);
+				}
+			}
+
+			Stmt::ExprAssign(ast::ExprAssign::IndexAssign(stmt)) => {
+				self.term(&stmt.acc.list);
+				self.expr(&stmt.acc.index);
+				self.expr(&stmt.value);
+			}
+
+			Stmt::ExprAssign(ast::ExprAssign::FieldAssign(stmt)) => {
+				self.term(&stmt.acc.object);
+				self.expr(&stmt.value);
+			}
+
+			Stmt::ClassDef(stmt) => self.class_def(stmt),
+
+			// Statements which failed to parse fail once they are reached
+			Stmt::Error(_) => ()
+		}
+	}
+
+	fn block(&mut self, block: &ast::DoBlock<'ast>) {
+		self.enter(ScopeKind::Block);
+		self.hoist(&block.stmts);
+		self.stmts(&block.stmts);
+		self.exit(block._do.span());
+	}
+
+	/// Resolves the statements in a scope of their own, opened by the node at the span,
+	/// optionally binding a variable in it.
+	fn scoped(
+		&mut self,
+		span: Span<'ast>,
+		var: Option<&ast::Ident<'ast>>,
+		stmts: &[ast::Stmt<'ast>]
+	) {
+		self.enter(ScopeKind::Block);
+
+		if let Some(var) = var {
+			self.bind(var.value(), var.span());
+		}
+
+		self.hoist(stmts);
+		self.stmts(stmts);
+		self.exit(span);
+	}
+
+	fn class_def(&mut self, stmt: &ast::ClassDef<'ast>) {
+		if let Some((_, ref parent)) = stmt.parent {
+			self.expr(parent);
+		}
+
+		self.define(&stmt.name);
+		self.enter(ScopeKind::Class);
+
+		let scope = self.scope();
+		scope.declare(SelfKw::KEYWORD, Var::Existing);
+
+		if stmt.parent.is_some() {
+			scope.declare(SuperKw::KEYWORD, Var::Existing);
+		}
+
+		// Fields are not variables, only their values are resolved
+		for field in stmt.fields.iter() {
+			if let Some(value) = field.value() {
+				self.expr(&value);
+			}
+		}
+
+		self.exit(stmt._class.span());
+	}
+
+	//* Expressions *//
+
+	fn expr(&mut self, expr: &ast::Expr<'ast>) {
+		match expr {
+			ast::Expr::Binary(expr) => {
+				self.term(&expr.lhs);
+				self.term(&expr.rhs);
+			}
+
+			ast::Expr::Unary(expr) => self.term(&expr.operand),
+			ast::Expr::Term(term) => self.term(term)
+		}
+	}
+
+	fn term(&mut self, term: &ast::Term<'ast>) {
+		use ast::Term;
+
+		match term {
+			Term::Expr(expr) => self.expr(expr),
+			Term::ParenExpr(expr) => self.expr(&expr.expr),
+
+			Term::Lit(_) => (),
+
+			Term::InterpStr(str) => {
+				for expr in str.exprs.iter() {
+					self.expr(expr);
+				}
+			}
+
+			Term::Ident(ident) => self.reference(ident),
+			Term::Func(func) => self.func(func),
+
+			Term::List(list) => {
+				for item in list.items.items() {
+					self.expr(item);
+				}
+			}
+
+			Term::Obj(obj) => {
+				for entry in obj.fields.items() {
+					self.expr(&entry.value);
+				}
+			}
+
+			Term::Extern(ext) => self.expr(&ext.value),
+			Term::Call(call) => self.call(call),
+
+			Term::IndexAcc(acc) => {
+				self.term(&acc.list);
+				self.expr(&acc.index);
+			}
+
+			Term::FieldAcc(acc) => self.term(&acc.object)
+		}
+	}
+
+	fn func(&mut self, func: &ast::Func<'ast>) {
+		let args = match func.args {
+			ast::FuncArgs::WithSelf { ref args, .. } => args.as_ref().map(|(_, args)| args),
+			ast::FuncArgs::WithoutSelf { ref args } => Some(args)
+		};
+
+		// Methods which are defined in the body of a derived class see the instance through `super`
+		let binds_super = matches!(func.args, ast::FuncArgs::WithSelf { .. })
+			&& self
+				.scopes
+				.iter()
+				.rev()
+				.find(|scope| scope.layout.slot(SuperKw::KEYWORD).is_some())
+				.is_some_and(|scope| scope.kind == ScopeKind::Class);
+
+		self.enter(ScopeKind::Func);
+
+		if let ast::FuncArgs::WithSelf { ref _self, .. } = func.args {
+			self.bind(SelfKw::KEYWORD, _self.span());
+		}
+
+		if binds_super {
+			self.scope().declare(SuperKw::KEYWORD, Var::Existing);
+		}
+
+		for arg in args.map(|args| args.items()).unwrap_or_default() {
+			self.bind(arg.value(), arg.span());
+		}
+
+		// The function's block shares the scope with its arguments
+		self.hoist(&func.block.stmts);
+		self.stmts(&func.block.stmts);
+		self.exit(func._func.span());
+	}
+
+	fn call(&mut self, call: &ast::Call<'ast>) {
+		self.term(&call.callee);
+
+		for arg in call.args.items() {
+			self.expr(arg);
+		}
+	}
+}
@@ -7,6 +7,7 @@ use prog_macros::get_argument;
 use prog_parser::ASTNode;
 
 use crate::arg_parser::{Arg, ArgList};
+use crate::value::{CallSite, Obj};
 use crate::{
 	error, Callable, CallableData, InterpretError, InterpretErrorKind, InterpretResult, Primitive,
 	Shared, Value, ValueKind
@@ -16,6 +17,7 @@ use crate::{
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Class<'i> {
 	name: String,
+	parent: Option<Box<Class<'i>>>,
 	fields: Shared<HashMap<String, Value<'i>>>
 }
 
@@ -23,21 +25,40 @@ pub struct Class<'i> {
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ClassInstance<'i> {
 	class: Class<'i>,
-	fields: Shared<HashMap<String, Value<'i>>>
+	fields: Shared<HashMap<String, Value<'i>>>,
+	/// Ancestor class which the fields are looked up in when the instance is accessed through `super`.
+	view: Option<Class<'i>>
 }
 
 impl<'i> Class<'i> {
-	pub(crate) fn new(name: String, fields: Shared<HashMap<String, Value<'i>>>) -> Self {
-		Self { name, fields }
+	pub(crate) fn new(
+		name: String,
+		parent: Option<Class<'i>>,
+		fields: Shared<HashMap<String, Value<'i>>>
+	) -> Self {
+		Self {
+			name,
+			parent: parent.map(Box::new),
+			fields
+		}
 	}
 
 	pub fn name(&self) -> &str { &self.name }
 
+	/// Class which this one extends, if any.
+	pub fn parent(&self) -> Option<&Class<'i>> { self.parent.as_deref() }
+
+	/// Iterates over the class itself and all of its ancestors, starting with the class.
+	pub fn ancestry(&self) -> impl Iterator<Item = &Class<'i>> {
+		std::iter::successors(Some(self), |class| class.parent())
+	}
+
 	pub fn contains<N>(&self, name: N) -> bool
 	where
 		N: AsRef<str>
 	{
-		self.fields.borrow().contains_key(name.as_ref())
+		self.ancestry()
+			.any(|class| class.fields.borrow().contains_key(name.as_ref()))
 	}
 
 	pub fn get<N>(&self, name: N) -> Option<Value<'i>>
@@ -45,26 +66,83 @@ impl<'i> Class<'i> {
 		Value<'i>: Clone,
 		N: AsRef<str>
 	{
-		self.fields.borrow().get(name.as_ref()).cloned()
+		self.ancestry()
+			.find_map(|class| class.fields.borrow().get(name.as_ref()).cloned())
 	}
 
+	/// Names of the fields which have to be initialized on construction,
+	/// including the ones inherited from the ancestors and not overridden with a value.
 	pub(crate) fn uninits(&self) -> HashSet<String> {
-		self.fields
-			.borrow()
-			.iter()
-			.filter(|&(_, val)| val.kind() == ValueKind::None)
-			.map(|(name, _)| name.to_owned())
-			.collect()
+		let mut seen = HashSet::new();
+		let mut uninits = HashSet::new();
+
+		for class in self.ancestry() {
+			for (name, value) in class.fields.borrow().iter() {
+				if seen.insert(name.to_owned()) && value.kind() == ValueKind::None {
+					uninits.insert(name.to_owned());
+				}
+			}
+		}
+
+		uninits
 	}
 }
 
 impl<'i> ClassInstance<'i> {
 	pub(crate) fn new(class: Class<'i>, fields: Shared<HashMap<String, Value<'i>>>) -> Self {
-		Self { class, fields }
+		Self {
+			class,
+			fields,
+			view: None
+		}
 	}
 
 	pub fn name(&self) -> &str { self.class.name() }
 
+	pub fn class(&self) -> &Class<'i> { &self.class }
+
+	/// Whether the instance was constructed from `class` or from one of its descendants.
+	pub fn is_instance_of(&self, class: &Class<'i>) -> bool {
+		self.class
+			.ancestry()
+			.any(|ancestor| Shared::ptr_eq(&ancestor.fields, &class.fields))
+	}
+
+	/// Names of the fields which hold data, including the ones with default values in the class,
+	/// sorted alphabetically. Methods are not included.
+	pub fn field_names(&self) -> Vec<String> {
+		let mut names = self.fields.borrow().keys().cloned().collect::<HashSet<_>>();
+
+		for class in self.class.ancestry() {
+			let fields = class.fields.borrow();
+			let data = fields
+				.iter()
+				.filter(|(_, value)| !matches!(value, Value::Func(_) | Value::IntrinsicFn(_)))
+				.map(|(name, _)| name.clone());
+
+			names.extend(data);
+		}
+
+		let mut names = names.into_iter().collect::<Vec<_>>();
+		names.sort();
+
+		names
+	}
+
+	/// The same instance, with its fields looked up in `ancestor` instead of its own class.
+	pub(crate) fn viewed_as(&self, ancestor: Class<'i>) -> Self {
+		Self {
+			class: self.class.clone(),
+			fields: Shared::clone(&self.fields),
+			view: Some(ancestor)
+		}
+	}
+
+	/// The instance itself, regardless of which class it is viewed as.
+	pub(crate) fn receiver(&self) -> Self {
+		Self::new(self.class.clone(), Shared::clone(&self.fields))
+	}
+
 	pub fn contains<N>(&self, name: N) -> bool
 	where
 		N: AsRef<str>
@@ -81,7 +159,7 @@ impl<'i> ClassInstance<'i> {
 			.borrow()
 			.get(name.as_ref())
 			.cloned()
-			.or(self.class.get(name))
+			.or_else(|| self.view.as_ref().unwrap_or(&self.class).get(name))
 	}
 
 	pub fn insert<N>(&self, name: N, value: Value<'i>) -> Option<Value<'i>>
@@ -93,7 +171,7 @@ impl<'i> ClassInstance<'i> {
 }
 
 impl<'intref, 'int: 'intref> Callable<'intref, 'int> for Class<'int> {
-	fn arg_list(&self) -> Cow<crate::arg_parser::ArgList> {
+	fn arg_list(&self) -> Cow<'_, crate::arg_parser::ArgList> {
 		if self.uninits().is_empty() {
 			return Cow::Owned(ArgList::new_empty());
 		}
@@ -107,13 +185,19 @@ impl<'intref, 'int: 'intref> Callable<'intref, 'int> for Class<'int> {
 	fn call(
 		&mut self,
 		CallableData {
+			i,
 			mut args,
-			call_site,
-			..
+			call_site
 		}: CallableData<'intref, 'int>
 	) -> InterpretResult<'int, Value<'int>> {
 		let args = get_argument!(args => fields: Obj?).unwrap_or_default();
+		i.traced(&call_site, |_| self.construct(args, &call_site))
+	}
+}
 
+impl<'i> Class<'i> {
+	/// Creates an instance with the fields given on construction.
+	fn construct(&self, args: Obj<'i>, call_site: &CallSite<'i>) -> InterpretResult<'i, Value<'i>> {
 		let mut fields = HashMap::new();
 		let mut uninits = self.uninits();
 
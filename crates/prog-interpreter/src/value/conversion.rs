@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+
+use prog_parser::Span;
+
+use super::{Bool, Class, ClassInstance, Func, IntrinsicFn, List, Num, Obj, Str};
+use crate::error::ConversionFailed;
+use crate::{InterpretError, InterpretErrorKind, InterpretResult, Value, ValueKind};
+
+/// Conversion of a runtime [`Value`] into a Rust type.
+///
+/// Can be derived for structs with named fields, which are then converted from an [`Obj`] or a [`ClassInstance`].
+pub trait FromValue<'i>: Sized {
+	fn from_value(value: Value<'i>) -> Result<Self, ConversionFailed>;
+
+	/// Unlike [`FromValue::from_value`], reports the failure as an [`InterpretError`] pointing at `span`.
+	fn from_value_at(value: Value<'i>, span: Span<'i>) -> InterpretResult<'i, Self> {
+		Self::from_value(value)
+			.map_err(|e| InterpretError::new(span, InterpretErrorKind::ConversionFailed(e)))
+	}
+}
+
+/// Conversion of a Rust type into a runtime [`Value`].
+///
+/// Can be derived for structs with named fields, which are then converted into an [`Obj`].
+/// Unlike [`FromValue`], the derive never produces a [`ClassInstance`], as classes are only defined
+/// by the programs at runtime. Such an object can be turned into an instance by calling the class with it.
+pub trait IntoValue<'i> {
+	fn into_value(self) -> Value<'i>;
+}
+
+/// Fields of a value which a struct is being converted from.
+pub struct Fields<'i>(Value<'i>);
+
+impl<'i> Fields<'i> {
+	/// Accepts an [`Obj`] or a [`ClassInstance`].
+	pub fn new(value: Value<'i>) -> Result<Self, ConversionFailed> {
+		match value {
+			Value::Obj(_) | Value::ClassInstance(_) => Ok(Self(value)),
+			v => Err(ConversionFailed::new(ValueKind::Obj.to_string(), v.kind()))
+		}
+	}
+
+	/// Converts the field `name`, treating a missing field as `none`.
+	pub fn get<T>(&self, name: &str) -> Result<T, ConversionFailed>
+	where
+		T: FromValue<'i>
+	{
+		let value = match self.0 {
+			Value::Obj(ref obj) => obj.get(name),
+			Value::ClassInstance(ref inst) => inst.get(name),
+			_ => unreachable!()
+		};
+
+		T::from_value(value.unwrap_or_default()).map_err(|e| e.in_field(name))
+	}
+}
+
+impl<'i> FromValue<'i> for Value<'i> {
+	fn from_value(value: Value<'i>) -> Result<Self, ConversionFailed> { Ok(value) }
+}
+
+impl<'i> IntoValue<'i> for Value<'i> {
+	fn into_value(self) -> Value<'i> { self }
+}
+
+impl<'i> IntoValue<'i> for () {
+	fn into_value(self) -> Value<'i> { Value::None }
+}
+
+macro_rules! impl_variant_conversion {
+	($($variant:ident($ty:ty)),*) => {
+		$(
+			impl<'i> FromValue<'i> for $ty {
+				fn from_value(value: Value<'i>) -> Result<Self, ConversionFailed> {
+					match value {
+						Value::$variant(inner) => Ok(inner),
+						v => Err(ConversionFailed::new(ValueKind::$variant.to_string(), v.kind()))
+					}
+				}
+			}
+
+			impl<'i> IntoValue<'i> for $ty {
+				fn into_value(self) -> Value<'i> { Value::$variant(self) }
+			}
+		)*
+	};
+}
+
+impl_variant_conversion!(
+	Num(Num),
+	Bool(Bool),
+	Str(Str),
+	Func(Func<'i>),
+	IntrinsicFn(IntrinsicFn<'i>),
+	List(List<'i>),
+	Obj(Obj<'i>),
+	Class(Class<'i>),
+	ClassInstance(ClassInstance<'i>)
+);
+
+impl<'i> FromValue<'i> for f64 {
+	fn from_value(value: Value<'i>) -> Result<Self, ConversionFailed> {
+		Num::from_value(value).map(f64::from)
+	}
+}
+
+impl<'i> IntoValue<'i> for f64 {
+	fn into_value(self) -> Value<'i> { Value::Num(self.into()) }
+}
+
+impl<'i> FromValue<'i> for i64 {
+	fn from_value(value: Value<'i>) -> Result<Self, ConversionFailed> {
+		let num = f64::from_value(value)?;
+
+		let is_whole = num.fract() == 0.0;
+		// `i64::MAX as f64` rounds up to 2^63, which is already out of range
+		let is_in_range = (num >= i64::MIN as f64) && (num < 9223372036854775808.0);
+
+		if !is_whole || !is_in_range {
+			return Err(ConversionFailed::new("integer", ValueKind::Num));
+		}
+
+		Ok(num as i64)
+	}
+}
+
+impl<'i> IntoValue<'i> for i64 {
+	fn into_value(self) -> Value<'i> { Value::Num((self as f64).into()) }
+}
+
+impl<'i> FromValue<'i> for bool {
+	fn from_value(value: Value<'i>) -> Result<Self, ConversionFailed> {
+		Bool::from_value(value).map(bool::from)
+	}
+}
+
+impl<'i> IntoValue<'i> for bool {
+	fn into_value(self) -> Value<'i> { Value::Bool(self.into()) }
+}
+
+impl<'i> FromValue<'i> for String {
+	fn from_value(value: Value<'i>) -> Result<Self, ConversionFailed> {
+		Str::from_value(value).map(String::from)
+	}
+}
+
+impl<'i> IntoValue<'i> for String {
+	fn into_value(self) -> Value<'i> { Value::Str(self.into()) }
+}
+
+impl<'i> IntoValue<'i> for &str {
+	fn into_value(self) -> Value<'i> { Value::Str(self.into()) }
+}
+
+impl<'i, T> FromValue<'i> for Vec<T>
+where
+	T: FromValue<'i>
+{
+	fn from_value(value: Value<'i>) -> Result<Self, ConversionFailed> {
+		List::from_value(value)?
+			.unwrap_or_clone()
+			.into_iter()
+			.enumerate()
+			.map(|(idx, item)| T::from_value(item).map_err(|e| e.in_index(idx)))
+			.collect()
+	}
+}
+
+impl<'i, T> IntoValue<'i> for Vec<T>
+where
+	T: IntoValue<'i>
+{
+	fn into_value(self) -> Value<'i> {
+		let items = self.into_iter().map(T::into_value).collect::<Vec<_>>();
+		Value::List(items.into())
+	}
+}
+
+impl<'i, T> FromValue<'i> for HashMap<String, T>
+where
+	T: FromValue<'i>
+{
+	fn from_value(value: Value<'i>) -> Result<Self, ConversionFailed> {
+		Obj::from_value(value)?
+			.unwrap_or_clone()
+			.into_iter()
+			.map(|(name, value)| {
+				let value = T::from_value(value).map_err(|e| e.in_field(&name))?;
+				Ok((name, value))
+			})
+			.collect()
+	}
+}
+
+impl<'i, T> IntoValue<'i> for HashMap<String, T>
+where
+	T: IntoValue<'i>
+{
+	fn into_value(self) -> Value<'i> {
+		let entries = self
+			.into_iter()
+			.map(|(name, value)| (name, value.into_value()))
+			.collect::<HashMap<_, _>>();
+
+		Value::Obj(entries.into())
+	}
+}
+
+impl<'i, T> FromValue<'i> for Option<T>
+where
+	T: FromValue<'i>
+{
+	fn from_value(value: Value<'i>) -> Result<Self, ConversionFailed> {
+		match value {
+			Value::None => Ok(None),
+			v => T::from_value(v).map(Some)
+		}
+	}
+}
+
+impl<'i, T> IntoValue<'i> for Option<T>
+where
+	T: IntoValue<'i>
+{
+	fn into_value(self) -> Value<'i> {
+		match self {
+			Some(value) => value.into_value(),
+			None => Value::None
+		}
+	}
+}
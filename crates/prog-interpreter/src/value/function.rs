@@ -2,19 +2,24 @@ use std::borrow::Cow;
 use std::fmt::{self, Debug, Display};
 use std::rc::Rc;
 
-use prog_parser::{ast, ASTNode};
+use prog_parser::{ast, ASTNode, Position, Span};
 
 use crate::arg_parser::{ArgList, ParsedArg};
+use crate::vm::{self, Chunk};
 use crate::{Callable, CallableData, Context, Evaluatable, InterpretResult, Primitive, Value};
 
 #[derive(Clone)]
 pub struct Func<'ast> {
 	pub(crate) ast: Rc<ast::Func<'ast>>,
 	pub(crate) args: ArgList,
-	pub(crate) ctx: Context<'ast>
+	/// Scope of the function, whose parent is the captured scope it has been created in.
+	/// Every call evaluates in a separate copy of it, see [`Context::frame`].
+	pub(crate) ctx: Context<'ast>,
+	/// Bytecode of the body, if the function has been created by the virtual machine.
+	pub(crate) code: Option<Rc<Chunk<'ast>>>
 }
 
-impl Func<'_> {
+impl<'ast> Func<'ast> {
 	pub(crate) fn args_str(&self) -> Vec<&str> {
 		match &self.ast.args {
 			ast::FuncArgs::WithSelf { _self, args } => {
@@ -34,6 +39,15 @@ impl Func<'_> {
 			}
 		}
 	}
+
+	/// Span of the head of the function, `func(...)`.
+	pub(crate) fn head_span(&self) -> Span<'ast> {
+		let source = self.ast._func.source();
+		let file = self.ast._func.file();
+		let position = Position::new(self.ast._func.start(), self.ast._rp.end());
+
+		Span::new(source, file, position)
+	}
 }
 
 impl Primitive for Func<'_> {
@@ -41,30 +55,47 @@ impl Primitive for Func<'_> {
 }
 
 impl<'intref, 'int: 'intref> Callable<'intref, 'int> for Func<'int> {
-	fn arg_list(&self) -> Cow<ArgList> { Cow::Borrowed(&self.args) }
+	fn arg_list(&self) -> Cow<'_, ArgList> { Cow::Borrowed(&self.args) }
 
 	fn call(
 		&mut self,
-		CallableData { i, mut args, .. }: CallableData<'intref, 'int>
+		CallableData {
+			i,
+			mut args,
+			call_site
+		}: CallableData<'intref, 'int>
 	) -> InterpretResult<'int, Value<'int>> {
+		// Recursive calls must not overwrite the arguments and variables of the calls in progress
+		let mut frame = self.ctx.frame();
+
 		for (name, value) in args.drain() {
 			let ParsedArg::Regular(value) = value else {
 				panic!("Runtime function received variadic arguments");
 			};
 
-			self.ctx.insert(name, value);
+			frame.insert(name, value);
 		}
 
-		Context::swap_in_place(&mut i.context, &mut self.ctx);
-		// Unlike a vector of statements, a function must produce a final value
-		let stmts = ast::Program {
-			stmts: Rc::clone(&self.ast.block.stmts)
-		};
+		i.traced(&call_site, |i| {
+			Context::swap_in_place(&mut i.context, &mut frame);
+
+			let result = match self.code {
+				Some(ref code) => vm::run_program(i, code),
+
+				// Unlike a vector of statements, a function must produce a final value
+				None => {
+					let stmts = ast::Program {
+						stmts: Rc::clone(&self.ast.block.stmts)
+					};
+
+					stmts.evaluate(i)
+				}
+			};
 
-		let result = stmts.evaluate(i);
-		Context::swap_in_place(&mut i.context, &mut self.ctx);
+			Context::swap_in_place(&mut i.context, &mut frame);
 
-		result
+			result
+		})
 	}
 }
 
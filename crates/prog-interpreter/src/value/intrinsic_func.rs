@@ -2,27 +2,86 @@ use std::borrow::Cow;
 use std::fmt::{self, Debug, Display};
 use std::rc::Rc;
 
-use crate::arg_parser::ArgList;
+use prog_parser::{ast, Span};
+
+use crate::arg_parser::{Arg, ArgList, ParsedArg};
 use crate::{Callable, CallableData, InterpretResult, Primitive, Value};
 
 pub type IntrinsicFnPtr<'int> =
 	for<'intref> fn(CallableData<'intref, 'int>) -> InterpretResult<'int, Value<'int>>;
 
-#[derive(Debug, Clone)]
+type IntrinsicFnBody<'int> =
+	dyn for<'intref> Fn(CallableData<'intref, 'int>) -> InterpretResult<'int, Value<'int>> + 'int;
+
+/// Function implemented in Rust, either by the interpreter itself or by the host application.
+#[derive(Clone)]
 pub struct IntrinsicFn<'int> {
-	pub(crate) ptr: IntrinsicFnPtr<'int>,
+	pub(crate) func: Rc<IntrinsicFnBody<'int>>,
 	pub(crate) args: Rc<ArgList>
 }
 
 impl<'int> IntrinsicFn<'int> {
-	pub(crate) fn new(ptr: IntrinsicFnPtr<'int>, args: ArgList) -> Self {
+	pub(crate) fn new(ptr: IntrinsicFnPtr<'int>, args: ArgList) -> Self { Self::from_fn(ptr, args) }
+
+	pub(crate) fn from_fn<F>(func: F, args: ArgList) -> Self
+	where
+		F: for<'intref> Fn(CallableData<'intref, 'int>) -> InterpretResult<'int, Value<'int>>
+			+ 'int
+	{
 		Self {
-			ptr,
+			func: Rc::new(func),
 			args: Rc::new(args)
 		}
 	}
 
-	pub fn address(&self) -> usize { self.ptr as usize }
+	/// Binds the first argument to `receiver`, producing a method of a built-in value.
+	///
+	/// The receiver is passed as if it was the first argument of the call, written at `span`,
+	/// so that the errors of the function keep pointing at the right arguments.
+	///
+	/// # Panics
+	///
+	/// Panics if the function does not take the receiver as its first required argument.
+	pub(crate) fn bind_receiver(&self, receiver: Value<'int>, span: Span<'int>) -> Self {
+		let mut args = ArgList::clone(&self.args);
+
+		let name = match args.remove(0) {
+			Some(Arg::Required(name, _) | Arg::RequiredUntyped(name)) => name,
+			arg => panic!("Method must take its receiver as a required argument, found `{arg:?}`")
+		};
+
+		let func = Rc::clone(&self.func);
+
+		Self::from_fn(
+			move |mut data| {
+				let (pairs, tail) = data.call_site.args.unwrap();
+				let mut call_args = ast::Punctuated::new();
+
+				if pairs.is_empty() && tail.is_none() {
+					call_args.push_item(span);
+				} else {
+					call_args.push_pair((span, span));
+				}
+
+				for pair in pairs {
+					call_args.push_pair(pair);
+				}
+
+				if let Some(item) = tail {
+					call_args.push_item(item);
+				}
+
+				data.call_site.args = call_args;
+				data.args
+					.insert(name.to_string(), ParsedArg::Regular(receiver.clone()));
+
+				func(data)
+			},
+			args
+		)
+	}
+
+	pub fn address(&self) -> usize { Rc::as_ptr(&self.func) as *const () as usize }
 }
 
 impl Primitive for IntrinsicFn<'_> {
@@ -30,13 +89,13 @@ impl Primitive for IntrinsicFn<'_> {
 }
 
 impl<'intref, 'int: 'intref> Callable<'intref, 'int> for IntrinsicFn<'int> {
-	fn arg_list(&self) -> Cow<ArgList> { Cow::Borrowed(&self.args) }
+	fn arg_list(&self) -> Cow<'_, ArgList> { Cow::Borrowed(&self.args) }
 
 	fn call(
 		&mut self,
 		data: CallableData<'intref, 'int>
 	) -> crate::InterpretResult<'int, Value<'int>> {
-		(self.ptr)(data)
+		(self.func)(data)
 	}
 }
 
@@ -44,9 +103,18 @@ impl PartialEq for IntrinsicFn<'_> {
 	fn eq(&self, other: &Self) -> bool { self.address() == other.address() }
 }
 
+impl Debug for IntrinsicFn<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("IntrinsicFn")
+			.field("address", &format_args!("{:#x}", self.address()))
+			.field("args", &self.args)
+			.finish()
+	}
+}
+
 impl Display for IntrinsicFn<'_> {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-		write!(f, "<intrinsic func() @ {:?}>", self.ptr)
+		write!(f, "<intrinsic func() @ {:#x}>", self.address())
 	}
 }
 
@@ -56,7 +124,7 @@ impl serde::Serialize for IntrinsicFn<'_> {
 	where
 		S: serde::Serializer
 	{
-		let address = format!("{:?}", self.ptr);
+		let address = format!("{:#x}", self.address());
 		serializer.serialize_newtype_struct("IntrinsicFn", &address)
 	}
 }
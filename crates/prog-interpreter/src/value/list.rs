@@ -1,3 +1,4 @@
+use std::cell::{Ref, RefMut};
 use std::fmt::{self, Display};
 
 use prog_lexer::TokenKind;
@@ -35,6 +36,26 @@ impl<'ast> List<'ast> {
 			}
 		}
 	}
+
+	pub fn len(&self) -> usize { self.0.borrow().len() }
+
+	pub fn is_empty(&self) -> bool { self.0.borrow().is_empty() }
+
+	pub fn push(&self, value: Value<'ast>) { self.0.borrow_mut().push(value) }
+
+	pub fn pop(&self) -> Option<Value<'ast>> { self.0.borrow_mut().pop() }
+
+	/// Removes the item at `index`, shifting the following items to the left.
+	pub fn remove(&self, index: usize) -> Option<Value<'ast>> {
+		let mut inner_list = self.0.borrow_mut();
+		(index < inner_list.len()).then(|| inner_list.remove(index))
+	}
+
+	pub(crate) fn items(&self) -> Ref<'_, Vec<Value<'ast>>> { self.0.borrow() }
+
+	pub(crate) fn items_mut(&self) -> RefMut<'_, Vec<Value<'ast>>> { self.0.borrow_mut() }
+
+	pub fn unwrap_or_clone(self) -> Vec<Value<'ast>> { Shared::unwrap_or_clone(self.0) }
 }
 
 impl Primitive for List<'_> {
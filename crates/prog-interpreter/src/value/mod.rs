@@ -1,6 +1,7 @@
 pub(crate) mod boolean;
 pub(crate) mod class;
 pub(crate) mod control_flow;
+pub(crate) mod conversion;
 pub(crate) mod function;
 pub(crate) mod intrinsic_func;
 pub(crate) mod list;
@@ -11,6 +12,7 @@ pub(crate) mod string;
 pub use boolean::Bool;
 pub use class::{Class, ClassInstance};
 pub use control_flow::CtrlFlow;
+pub use conversion::{Fields, FromValue, IntoValue};
 pub use function::Func;
 pub use intrinsic_func::IntrinsicFn;
 pub use list::List;
@@ -37,7 +39,7 @@ pub trait Primitive {
 
 /// Represents runtime values which can be invoked.
 pub(crate) trait Callable<'intref, 'int: 'intref>: Primitive {
-	fn arg_list(&self) -> Cow<crate::arg_parser::ArgList>;
+	fn arg_list(&self) -> Cow<'_, crate::arg_parser::ArgList>;
 
 	fn call(
 		&mut self,
@@ -45,13 +47,94 @@ pub(crate) trait Callable<'intref, 'int: 'intref>: Primitive {
 	) -> crate::InterpretResult<'int, Value<'int>>;
 }
 
+/// Data passed to a function when it is called.
 #[derive(Debug)]
-pub(crate) struct CallableData<'intref, 'int: 'intref> {
+pub struct CallableData<'intref, 'int: 'intref> {
 	pub(crate) i: &'intref mut crate::Interpreter<'int>,
 	pub(crate) args: crate::arg_parser::ParsedArgList<'int>,
 	pub(crate) call_site: CallSite<'int>
 }
 
+impl<'int> CallableData<'_, 'int> {
+	/// Interpreter which performs the call.
+	pub fn interpreter(&mut self) -> &mut crate::Interpreter<'int> { self.i }
+
+	/// Span of the whole call expression.
+	pub fn span(&self) -> Span<'int> {
+		use prog_parser::ASTNode;
+		self.call_site.span()
+	}
+
+	/// Span of the `n`-th argument expression, if there is one.
+	pub fn arg_span(&self, n: usize) -> Option<Span<'int>> {
+		self.call_site.args.nth_item(n).copied()
+	}
+
+	/// Takes a required argument and converts it into `T`.
+	///
+	/// # Panics
+	///
+	/// Panics if the argument does not exist or is variadic,
+	/// which is always a mismatch between the argument list and the function.
+	pub fn arg<T>(&mut self, name: &str) -> crate::InterpretResult<'int, T>
+	where
+		T: FromValue<'int>
+	{
+		match self.opt_arg(name)? {
+			Some(value) => Ok(value),
+			None => panic!("Argument `{name}` does not exist")
+		}
+	}
+
+	/// Takes an optional argument and converts it into `T`.
+	///
+	/// # Panics
+	///
+	/// Panics if the argument is variadic.
+	pub fn opt_arg<T>(&mut self, name: &str) -> crate::InterpretResult<'int, Option<T>>
+	where
+		T: FromValue<'int>
+	{
+		use crate::arg_parser::ParsedArg;
+		use prog_parser::ASTNode;
+
+		// Parsed arguments do not keep track of their positions
+		let span_args = self.call_site.args.span();
+
+		match self.args.remove(name) {
+			Some(ParsedArg::Regular(value)) => T::from_value_at(value, span_args).map(Some),
+			Some(ParsedArg::Variadic(_)) => panic!("Argument `{name}` is not a regular argument"),
+			None => Ok(None)
+		}
+	}
+
+	/// Takes a variadic argument.
+	///
+	/// # Panics
+	///
+	/// Panics if the argument does not exist or is not variadic.
+	pub fn variadic(&mut self, name: &str) -> Vec<Value<'int>> {
+		use crate::arg_parser::ParsedArg;
+
+		match self.args.remove(name) {
+			Some(ParsedArg::Variadic(values)) => values,
+			Some(ParsedArg::Regular(_)) => panic!("Argument `{name}` is not a variadic argument"),
+			None => panic!("Argument `{name}` does not exist")
+		}
+	}
+
+	/// Constructs an error which points at the call expression.
+	pub fn error<M>(&self, message: M) -> crate::InterpretError<'int>
+	where
+		M: Into<String>
+	{
+		crate::InterpretError::new(
+			self.span(),
+			crate::InterpretErrorKind::NativeFnFailed(crate::error::NativeFnFailed(message.into()))
+		)
+	}
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct CallSite<'s> {
 	pub(crate) callee: Span<'s>,
@@ -72,7 +155,7 @@ impl<'s> prog_parser::ASTNode<'s> for CallSite<'s> {
 	}
 }
 
-#[derive(Debug, Clone, PartialEq, prog_macros::EnumKind)]
+#[derive(Debug, Clone, Default, PartialEq, prog_macros::EnumKind)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Value<'i> {
 	Num(Num),
@@ -86,6 +169,7 @@ pub enum Value<'i> {
 	ClassInstance(ClassInstance<'i>),
 
 	CtrlFlow(CtrlFlow<'i>),
+	#[default]
 	None
 }
 
@@ -135,10 +219,6 @@ impl Display for Value<'_> {
 	}
 }
 
-impl Default for Value<'_> {
-	fn default() -> Self { Self::None }
-}
-
 impl From<Num> for Value<'_> {
 	fn from(num: Num) -> Self { Self::Num(num) }
 }
@@ -32,11 +32,28 @@ impl<'ast> Obj<'ast> {
 		self.0.borrow_mut().insert(name.into(), value)
 	}
 
+	pub fn len(&self) -> usize { self.0.borrow().len() }
+
+	pub fn is_empty(&self) -> bool { self.0.borrow().is_empty() }
+
+	/// Entries of the object, sorted by their names.
+	pub fn entries(&self) -> Vec<(String, Value<'ast>)> {
+		let mut entries = self
+			.0
+			.borrow()
+			.iter()
+			.map(|(name, value)| (name.clone(), value.clone()))
+			.collect::<Vec<_>>();
+		entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+		entries
+	}
+
 	pub fn unwrap_or_clone(self) -> HashMap<String, Value<'ast>> { Shared::unwrap_or_clone(self.0) }
 }
 
 impl Primitive for Obj<'_> {
-	fn is_truthy(&self) -> bool { self.0.borrow().is_empty() }
+	fn is_truthy(&self) -> bool { !self.is_empty() }
 }
 
 impl<'ast> From<HashMap<String, Value<'ast>>> for Obj<'ast> {
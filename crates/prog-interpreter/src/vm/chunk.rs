@@ -0,0 +1,199 @@
+use std::rc::Rc;
+
+use prog_parser::{ast, Span};
+
+use crate::arg_parser::ArgList;
+use crate::context::Layout;
+use crate::resolver::Binding;
+use crate::value::CallSite;
+use crate::{InterpretError, Value};
+
+/// Instruction of the virtual machine.
+///
+/// Operands refer to the tables of the chunk by their indices, except for the jump targets,
+/// which are indices of instructions, and the lengths and depths.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Op {
+	/// Spends a unit of fuel on the statement or the expression at the span.
+	Step(usize),
+	/// Checks the size of the value on top of the stack, produced by the node at the span.
+	CheckSize(usize),
+
+	Const(usize),
+	None,
+	Pop,
+
+	Load(usize),
+	/// Defines the variable laid out at the slot of the current scope.
+	Define(usize),
+	Assign(usize),
+
+	Binary {
+		op: ast::BinaryOpKind,
+		span: usize,
+		span_rhs: usize
+	},
+	Unary {
+		op: ast::UnaryOpKind,
+		span: usize
+	},
+	/// Jumps if the value on top of the stack is falsy, keeping it, pops it otherwise.
+	And(usize),
+	/// Jumps if the value on top of the stack is truthy, keeping it, pops it otherwise.
+	Or(usize),
+
+	Jump(usize),
+	/// Pops the condition and jumps if it is falsy.
+	JumpIfFalsy(usize),
+
+	/// Appends the popped value and the segment to the string below it.
+	Interp {
+		segment: usize,
+		span: usize
+	},
+	Func(usize),
+	/// Collects the given number of items into a list.
+	List(usize),
+	/// Collects the values of the entries named by the shape into an object.
+	Obj(usize),
+	Extern(usize),
+
+	CheckCallable(usize),
+	Call(usize),
+
+	CheckIndexable(usize),
+	Index {
+		span: usize,
+		span_list: usize,
+		span_index: usize
+	},
+	Field {
+		name: usize,
+		span_obj: usize,
+		span_field: usize
+	},
+
+	CheckIndexAssignable(usize),
+	CheckAssignIndex(usize),
+	AssignIndex {
+		span: usize,
+		span_index: usize
+	},
+	CheckFieldAssignable {
+		name: usize,
+		span_obj: usize,
+		span_field: usize
+	},
+	AssignField(usize),
+
+	/// Leaves the chunk with the popped value.
+	Return(usize),
+	/// Leaves the chunk with a `break` which has no loop around it in the chunk.
+	Break(usize),
+	/// Leaves the chunk with a `continue` which has no loop around it in the chunk.
+	Continue(usize),
+
+	EnterScope(usize),
+	ExitScope,
+	/// Leaves the scopes entered past the depth.
+	Unwind(usize),
+
+	/// Starts iterating over the popped value.
+	Iter(usize),
+	/// Enters the scope of the next iteration and binds its variables,
+	/// or jumps to `exit` once there are no iterations left.
+	Next {
+		exit: usize,
+		vars: usize,
+		layout: usize
+	},
+	EndIter,
+
+	Throw(usize),
+	Try(usize),
+	/// Fails with an error which is known at compile time.
+	Fail(usize),
+
+	/// Defines the class and enters the scope of its body.
+	Class(usize),
+	ClassField(usize),
+	EndClass
+}
+
+/// Bytecode of a program, a function body or a branch of a `try` statement.
+#[derive(Debug, Default)]
+pub(crate) struct Chunk<'ast> {
+	pub(crate) ops: Vec<Op>,
+
+	pub(crate) spans: Vec<Span<'ast>>,
+	pub(crate) names: Vec<String>,
+	pub(crate) consts: Vec<Value<'ast>>,
+	/// Names of the entries of the object literals, in the order of their values.
+	pub(crate) shapes: Vec<Vec<usize>>,
+	/// Variables bound by the iterations of the `for` loops.
+	pub(crate) vars: Vec<(usize, Option<usize>)>,
+	/// Variables which are loaded and assigned.
+	pub(crate) refs: Vec<VarRef>,
+	/// Layouts of the scopes entered by the chunk.
+	pub(crate) layouts: Vec<Rc<Layout>>,
+
+	pub(crate) funcs: Vec<FuncProto<'ast>>,
+	pub(crate) calls: Vec<CallProto<'ast>>,
+	pub(crate) tries: Vec<TryProto<'ast>>,
+	pub(crate) classes: Vec<ClassProto>,
+	pub(crate) errors: Vec<InterpretError<'ast>>
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct VarRef {
+	pub(crate) name: usize,
+	pub(crate) span: usize,
+	pub(crate) binding: Binding
+}
+
+/// Function which a function expression creates.
+#[derive(Debug)]
+pub(crate) struct FuncProto<'ast> {
+	pub(crate) ast: Rc<ast::Func<'ast>>,
+	pub(crate) args: ArgList,
+	pub(crate) layout: Rc<Layout>,
+	pub(crate) code: Rc<Chunk<'ast>>
+}
+
+#[derive(Debug)]
+pub(crate) struct CallProto<'ast> {
+	pub(crate) site: CallSite<'ast>,
+	pub(crate) arg_spans: Vec<Span<'ast>>
+}
+
+/// Branches of a `try` statement, each of which is run in its own scope.
+#[derive(Debug)]
+pub(crate) struct TryProto<'ast> {
+	pub(crate) body: Branch<'ast>,
+	pub(crate) catch: Option<(String, Branch<'ast>)>,
+	pub(crate) finally: Option<Branch<'ast>>,
+	/// Loop of the enclosing chunk which `break` and `continue` leaving the branches apply to.
+	pub(crate) exits: Option<LoopExits>
+}
+
+#[derive(Debug)]
+pub(crate) struct Branch<'ast> {
+	pub(crate) code: Chunk<'ast>,
+	pub(crate) layout: Rc<Layout>
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LoopExits {
+	/// Depth of the scopes outside of the iterations.
+	pub(crate) depth: usize,
+	pub(crate) brk: usize,
+	pub(crate) cont: usize
+}
+
+#[derive(Debug)]
+pub(crate) struct ClassProto {
+	pub(crate) name: String,
+	/// Span of the parent class expression, whose value is on the stack if there is one.
+	pub(crate) parent: Option<usize>,
+	pub(crate) layout: Rc<Layout>
+}
@@ -0,0 +1,672 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use prog_parser::{ast, ASTNode, Span};
+
+use super::chunk::{
+	Branch, CallProto, Chunk, ClassProto, FuncProto, LoopExits, Op, TryProto, VarRef
+};
+use crate::resolver::Resolution;
+use crate::{error, ops, value, InterpretError, InterpretErrorKind, Value};
+
+/// Loop which `break` and `continue` statements of the chunk apply to.
+#[derive(Debug)]
+struct Loop {
+	/// Depth of the scopes outside of the iterations.
+	depth: usize,
+	/// Instruction which starts the next iteration.
+	cont: usize,
+	/// Jumps and `try` statements which are patched to exit the loop once its end is known.
+	breaks: Vec<usize>,
+	tries: Vec<usize>
+}
+
+/// Compiles statements into a chunk, mirroring the order in which the tree walker evaluates them.
+#[derive(Debug)]
+pub(crate) struct Compiler<'r, 'ast> {
+	chunk: Chunk<'ast>,
+	resolution: &'r Resolution,
+	names: HashMap<String, usize>,
+	/// Number of scopes entered by the chunk at the current instruction.
+	depth: usize,
+	loops: Vec<Loop>,
+	/// Are the sizes of the values limited?
+	check_sizes: bool
+}
+
+impl<'r, 'ast> Compiler<'r, 'ast> {
+	pub(crate) fn new(check_sizes: bool, resolution: &'r Resolution) -> Self {
+		Self {
+			chunk: Chunk::default(),
+			resolution,
+			names: HashMap::new(),
+			depth: 0,
+			loops: vec![],
+			check_sizes
+		}
+	}
+
+	pub(crate) fn finish(self) -> Chunk<'ast> { self.chunk }
+
+	/// Compiles the statements into a separate chunk.
+	fn nested(&self, stmts: &[ast::Stmt<'ast>]) -> Chunk<'ast> {
+		let mut compiler = Self::new(self.check_sizes, self.resolution);
+		compiler.stmts(stmts);
+		compiler.finish()
+	}
+
+	fn emit(&mut self, op: Op) -> usize {
+		self.chunk.ops.push(op);
+		self.chunk.ops.len() - 1
+	}
+
+	/// Index of the next instruction.
+	fn here(&self) -> usize { self.chunk.ops.len() }
+
+	/// Points the jump at `at` to the next instruction.
+	fn patch(&mut self, at: usize) {
+		let target = self.here();
+
+		match &mut self.chunk.ops[at] {
+			Op::Jump(t) | Op::JumpIfFalsy(t) | Op::And(t) | Op::Or(t) => *t = target,
+			Op::Next { exit, .. } => *exit = target,
+			op => panic!("Attempted to patch `{op:?}`, which is not a jump")
+		}
+	}
+
+	fn span(&mut self, span: Span<'ast>) -> usize {
+		self.chunk.spans.push(span);
+		self.chunk.spans.len() - 1
+	}
+
+	fn name(&mut self, name: &str) -> usize {
+		if let Some(&idx) = self.names.get(name) {
+			return idx;
+		}
+
+		self.chunk.names.push(name.to_owned());
+		self.names
+			.insert(name.to_owned(), self.chunk.names.len() - 1);
+		self.chunk.names.len() - 1
+	}
+
+	/// Variable which the name has been bound to.
+	fn var(&mut self, name: &ast::Ident<'ast>) -> usize {
+		let var = VarRef {
+			name: self.name(name.value()),
+			span: self.span(name.span()),
+			binding: self.resolution.binding(name)
+		};
+
+		self.chunk.refs.push(var);
+		self.chunk.refs.len() - 1
+	}
+
+	/// Layout of the scope opened by the node at the span.
+	fn layout(&mut self, span: Span<'ast>) -> usize {
+		self.chunk.layouts.push(self.resolution.layout(span));
+		self.chunk.layouts.len() - 1
+	}
+
+	/// Statements compiled into a separate chunk, which is run in the scope opened by the node at the span.
+	fn branch(&self, span: Span<'ast>, stmts: &[ast::Stmt<'ast>]) -> Branch<'ast> {
+		Branch {
+			code: self.nested(stmts),
+			layout: self.resolution.layout(span)
+		}
+	}
+
+	fn constant(&mut self, value: Value<'ast>) -> usize {
+		self.chunk.consts.push(value);
+		self.chunk.consts.len() - 1
+	}
+
+	fn fail(&mut self, error: InterpretError<'ast>) {
+		self.chunk.errors.push(error);
+		self.emit(Op::Fail(self.chunk.errors.len() - 1));
+	}
+
+	fn check_size(&mut self, span: Span<'ast>) {
+		if self.check_sizes {
+			let span = self.span(span);
+			self.emit(Op::CheckSize(span));
+		}
+	}
+
+	/// Leaves the scopes entered since the loop started.
+	fn unwind(&mut self, depth: usize) {
+		if self.depth > depth {
+			self.emit(Op::Unwind(depth));
+		}
+	}
+
+	/// Leaves the chunk with the value on top of the stack, returned by the node at the span.
+	pub(crate) fn ret(&mut self, span: Span<'ast>) {
+		let span = self.span(span);
+		self.emit(Op::Return(span));
+	}
+
+	/// Enters the scope opened by the node at the span.
+	fn enter_scope(&mut self, span: Span<'ast>) {
+		let layout = self.layout(span);
+		self.emit(Op::EnterScope(layout));
+		self.depth += 1;
+	}
+
+	fn exit_scope(&mut self) {
+		self.emit(Op::ExitScope);
+		self.depth -= 1;
+	}
+
+	//* Statements *//
+
+	pub(crate) fn stmts(&mut self, stmts: &[ast::Stmt<'ast>]) {
+		for stmt in stmts {
+			self.stmt(stmt);
+		}
+	}
+
+	fn stmt(&mut self, stmt: &ast::Stmt<'ast>) {
+		use ast::Stmt;
+
+		let span = self.span(stmt.span());
+		self.emit(Op::Step(span));
+
+		match stmt {
+			Stmt::VarDefine(stmt) => self.var_define(stmt),
+
+			Stmt::VarAssign(stmt) => {
+				self.expr(&stmt.value);
+
+				let var = self.var(&stmt.name);
+				self.emit(Op::Assign(var));
+			}
+
+			Stmt::DoBlock(stmt) => self.block(stmt),
+
+			Stmt::Return(stmt) => {
+				self.expr(&stmt.value);
+				self.ret(stmt.span());
+			}
+
+			// Unlike call terms, the results of call statements are not checked
+			Stmt::Call(call) => {
+				self.call(call);
+				self.emit(Op::Pop);
+			}
+
+			Stmt::WhileLoop(stmt) => self.while_loop(stmt),
+			Stmt::ForLoop(stmt) => self.for_loop(stmt),
+
+			Stmt::Break(stmt) => {
+				let Some(depth) = self.loops.last().map(|l| l.depth) else {
+					let span = self.span(stmt.span());
+					self.emit(Op::Break(span));
+					return;
+				};
+
+				self.unwind(depth);
+				let jump = self.emit(Op::Jump(usize::MAX));
+				self.loops.last_mut().unwrap().breaks.push(jump);
+			}
+
+			Stmt::Continue(stmt) => {
+				let Some((depth, cont)) = self.loops.last().map(|l| (l.depth, l.cont)) else {
+					let span = self.span(stmt.span());
+					self.emit(Op::Continue(span));
+					return;
+				};
+
+				self.unwind(depth);
+				self.emit(Op::Jump(cont));
+			}
+
+			Stmt::If(stmt) => self.if_cond(stmt),
+
+			Stmt::Throw(stmt) => {
+				self.expr(&stmt.value);
+
+				let span = self.span(stmt.span());
+				self.emit(Op::Throw(span));
+			}
+
+			Stmt::Try(stmt) => self.try_catch(stmt),
+
+			Stmt::ExprAssign(ast::ExprAssign::IndexAssign(stmt)) => self.index_assign(stmt),
+			Stmt::ExprAssign(ast::ExprAssign::FieldAssign(stmt)) => self.field_assign(stmt),
+
+			Stmt::ClassDef(stmt) => self.class_def(stmt),
+
+			Stmt::Error(stmt) => {
+				let kind = InterpretErrorKind::ParseFailed(stmt.error.kind.clone());
+				self.fail(InterpretError::new(stmt.error.span, kind));
+			}
+		}
+	}
+
+	fn var_define(&mut self, stmt: &ast::VarDefine<'ast>) {
+		match stmt.value() {
+			Some(value) => self.expr(&value),
+			None => _ = self.emit(Op::None)
+		}
+
+		let slot = self.resolution.binding(&stmt.name()).slot;
+		self.emit(Op::Define(slot));
+	}
+
+	fn block(&mut self, block: &ast::DoBlock<'ast>) {
+		self.enter_scope(block._do.span());
+		self.stmts(&block.stmts);
+		self.exit_scope();
+	}
+
+	fn start_loop(&mut self, cont: usize) {
+		self.loops.push(Loop {
+			depth: self.depth,
+			cont,
+			breaks: vec![],
+			tries: vec![]
+		});
+	}
+
+	/// Points the `break` statements of the innermost loop at the next instruction.
+	fn end_loop(&mut self) {
+		let exit = self.here();
+		let Some(Loop { breaks, tries, .. }) = self.loops.pop() else {
+			panic!("Attempted to end a loop outside of one");
+		};
+
+		for jump in breaks {
+			self.patch(jump);
+		}
+
+		for idx in tries {
+			if let Some(ref mut exits) = self.chunk.tries[idx].exits {
+				exits.brk = exit;
+			}
+		}
+	}
+
+	fn while_loop(&mut self, stmt: &ast::WhileLoop<'ast>) {
+		let cond = self.here();
+		self.expr(&stmt.cond);
+		let exit = self.emit(Op::JumpIfFalsy(usize::MAX));
+
+		self.start_loop(cond);
+		self.block(&stmt.block);
+		self.emit(Op::Jump(cond));
+
+		self.patch(exit);
+		self.end_loop();
+	}
+
+	fn for_loop(&mut self, stmt: &ast::ForLoop<'ast>) {
+		self.expr(&stmt.iter);
+
+		let span_iter = self.span(stmt.iter.span());
+		self.emit(Op::Iter(span_iter));
+
+		let var = self.name(stmt.var.value());
+		let value_var = stmt.value.as_ref().map(|(_, v)| self.name(v.value()));
+		self.chunk.vars.push((var, value_var));
+
+		let vars = self.chunk.vars.len() - 1;
+		let layout = self.layout(stmt._for.span());
+		let next = self.emit(Op::Next {
+			exit: usize::MAX,
+			vars,
+			layout
+		});
+
+		// Every iteration has its own scope, holding the variables
+		self.start_loop(next);
+		self.depth += 1;
+		self.block(&stmt.block);
+		self.exit_scope();
+		self.emit(Op::Jump(next));
+
+		self.patch(next);
+		self.end_loop();
+		self.emit(Op::EndIter);
+	}
+
+	fn if_cond(&mut self, stmt: &ast::If<'ast>) {
+		let mut exits = vec![];
+
+		let branches = std::iter::once((&stmt.cond, &stmt.stmts))
+			.chain(stmt.b_elifs.iter().map(|b| (&b.cond, &b.stmts)));
+
+		// Branches do not have scopes of their own
+		for (cond, stmts) in branches {
+			self.expr(cond);
+			let next = self.emit(Op::JumpIfFalsy(usize::MAX));
+
+			self.stmts(stmts);
+			exits.push(self.emit(Op::Jump(usize::MAX)));
+			self.patch(next);
+		}
+
+		if let Some(ref branch) = stmt.b_else {
+			self.stmts(&branch.stmts);
+		}
+
+		for jump in exits {
+			self.patch(jump);
+		}
+	}
+
+	fn try_catch(&mut self, stmt: &ast::Try<'ast>) {
+		let exits = self.loops.last().map(|l| LoopExits {
+			depth: l.depth,
+			brk: usize::MAX,
+			cont: l.cont
+		});
+
+		let proto = TryProto {
+			body: self.branch(stmt._try.span(), &stmt.stmts),
+			catch: stmt
+				.b_catch
+				.as_ref()
+				.map(|b| (b.name.value_owned(), self.branch(b._catch.span(), &b.stmts))),
+			finally: stmt
+				.b_finally
+				.as_ref()
+				.map(|b| self.branch(b._finally.span(), &b.stmts)),
+			exits
+		};
+
+		self.chunk.tries.push(proto);
+		let idx = self.chunk.tries.len() - 1;
+
+		if let Some(l) = self.loops.last_mut() {
+			l.tries.push(idx);
+		}
+
+		self.emit(Op::Try(idx));
+	}
+
+	fn index_assign(&mut self, stmt: &ast::IndexAssign<'ast>) {
+		let span = self.span(stmt.span());
+		let span_list = self.span(stmt.acc.list.span());
+		let span_index = self.span(stmt.acc.index.span());
+
+		self.term(&stmt.acc.list);
+		self.emit(Op::CheckIndexAssignable(span_list));
+
+		self.expr(&stmt.acc.index);
+		self.emit(Op::CheckAssignIndex(span_index));
+
+		self.expr(&stmt.value);
+		self.emit(Op::AssignIndex { span, span_index });
+	}
+
+	fn field_assign(&mut self, stmt: &ast::FieldAssign<'ast>) {
+		let name = self.name(stmt.acc.field.value());
+		let span_obj = self.span(stmt.acc.object.span());
+		let span_field = self.span(stmt.acc.field.span());
+
+		self.term(&stmt.acc.object);
+		self.emit(Op::CheckFieldAssignable {
+			name,
+			span_obj,
+			span_field
+		});
+
+		self.expr(&stmt.value);
+		self.emit(Op::AssignField(name));
+	}
+
+	fn class_def(&mut self, stmt: &ast::ClassDef<'ast>) {
+		let parent = stmt.parent.as_ref().map(|(_, expr)| {
+			self.expr(expr);
+			self.span(expr.span())
+		});
+
+		self.chunk.classes.push(ClassProto {
+			name: stmt.name.value_owned(),
+			parent,
+			layout: self.resolution.layout(stmt._class.span())
+		});
+		self.emit(Op::Class(self.chunk.classes.len() - 1));
+		self.depth += 1;
+
+		let mut positions = HashMap::new();
+
+		for field in stmt.fields.iter() {
+			let name = field.name();
+
+			// Redefinitions fail before their values are evaluated
+			if let Some(&def_name) = positions.get(name.value()) {
+				let kind = InterpretErrorKind::ClassFieldRedef(error::ClassFieldRedef { def_name });
+				self.fail(InterpretError::new(name.span(), kind));
+				break;
+			}
+
+			positions.insert(name.value_owned(), name.span());
+
+			match field.value() {
+				Some(value) => self.expr(&value),
+				None => _ = self.emit(Op::None)
+			}
+
+			let name = self.name(name.value());
+			self.emit(Op::ClassField(name));
+		}
+
+		self.emit(Op::EndClass);
+		self.depth -= 1;
+	}
+
+	//* Expressions *//
+
+	pub(crate) fn expr(&mut self, expr: &ast::Expr<'ast>) {
+		let span = self.span(expr.span());
+		self.emit(Op::Step(span));
+
+		match expr {
+			ast::Expr::Binary(expr) => self.binary(expr),
+
+			ast::Expr::Unary(expr) => {
+				self.term(&expr.operand);
+
+				let span = self.span(expr.span());
+				self.emit(Op::Unary {
+					op: expr.op.kind,
+					span
+				});
+			}
+
+			ast::Expr::Term(term) => self.term(term)
+		}
+
+		self.check_size(expr.span());
+	}
+
+	fn binary(&mut self, expr: &ast::BinaryExpr<'ast>) {
+		use ast::BinaryOpKind as BinOp;
+
+		self.term(&expr.lhs);
+
+		// Logical operators short-circuit, resulting in the operand which decides the outcome
+		let short_circuit = match expr.op.kind {
+			BinOp::And => Some(self.emit(Op::And(usize::MAX))),
+			BinOp::Or => Some(self.emit(Op::Or(usize::MAX))),
+			_ => None
+		};
+
+		self.term(&expr.rhs);
+
+		match short_circuit {
+			Some(jump) => self.patch(jump),
+
+			None => {
+				let span = self.span(expr.span());
+				let span_rhs = self.span(expr.rhs.span());
+
+				self.emit(Op::Binary {
+					op: expr.op.kind,
+					span,
+					span_rhs
+				});
+			}
+		}
+	}
+
+	fn term(&mut self, term: &ast::Term<'ast>) {
+		use ast::Term;
+
+		match term {
+			Term::Expr(expr) => self.expr(expr),
+			Term::ParenExpr(expr) => self.expr(&expr.expr),
+
+			Term::Lit(lit) => self.lit(lit),
+			Term::InterpStr(str) => self.interp_str(str),
+
+			Term::Ident(ident) => {
+				let var = self.var(ident);
+				self.emit(Op::Load(var));
+			}
+
+			Term::Func(func) => self.func(func),
+
+			Term::List(list) => {
+				let items = list.items.items();
+
+				for item in &items {
+					self.expr(item);
+				}
+
+				self.emit(Op::List(items.len()));
+			}
+
+			Term::Obj(obj) => self.obj(obj),
+
+			Term::Extern(ext) => {
+				self.expr(&ext.value);
+
+				let span = self.span(ext.value.span());
+				self.emit(Op::Extern(span));
+			}
+
+			Term::Call(call) => self.call(call),
+
+			Term::IndexAcc(acc) => {
+				let span = self.span(acc.span());
+				let span_list = self.span(acc.list.span());
+				let span_index = self.span(acc.index.span());
+
+				self.term(&acc.list);
+				self.emit(Op::CheckIndexable(span_list));
+
+				self.expr(&acc.index);
+				self.emit(Op::Index {
+					span,
+					span_list,
+					span_index
+				});
+			}
+
+			Term::FieldAcc(acc) => {
+				self.term(&acc.object);
+
+				let name = self.name(acc.field.value());
+				let span_obj = self.span(acc.object.span());
+				let span_field = self.span(acc.field.span());
+
+				self.emit(Op::Field {
+					name,
+					span_obj,
+					span_field
+				});
+			}
+		}
+
+		// Terms are checked as well, since receivers of methods and callees are not expressions
+		self.check_size(term.span());
+	}
+
+	fn lit(&mut self, lit: &ast::Lit<'ast>) {
+		use ast::LitKind;
+
+		let value = match lit.kind {
+			LitKind::Num(lit) => Value::Num(value::Num::from(lit)),
+			LitKind::Bool(lit) => Value::Bool(value::Bool::from(lit)),
+			LitKind::Str(ref lit) => Value::Str(value::Str::from(lit.as_str())),
+
+			LitKind::None => {
+				self.emit(Op::None);
+				return;
+			}
+		};
+
+		let idx = self.constant(value);
+		self.emit(Op::Const(idx));
+	}
+
+	fn interp_str(&mut self, str: &ast::InterpStr<'ast>) {
+		let first = Value::Str(value::Str::from(str.segments[0].value.as_str()));
+		let idx = self.constant(first);
+		self.emit(Op::Const(idx));
+
+		for (expr, segment) in str.exprs.iter().zip(&str.segments[1..]) {
+			self.expr(expr);
+
+			let span = self.span(expr.span());
+			let segment = self.name(&segment.value);
+			self.emit(Op::Interp { segment, span });
+		}
+	}
+
+	fn func(&mut self, func: &ast::Func<'ast>) {
+		let proto = FuncProto {
+			ast: Rc::new(func.clone()),
+			args: ops::func_args(func),
+			layout: self.resolution.layout(func._func.span()),
+			code: Rc::new(self.nested(&func.block.stmts))
+		};
+
+		self.chunk.funcs.push(proto);
+		self.emit(Op::Func(self.chunk.funcs.len() - 1));
+	}
+
+	fn obj(&mut self, obj: &ast::Obj<'ast>) {
+		let mut shape = vec![];
+		let mut positions = HashMap::new();
+
+		for entry in obj.fields.items() {
+			self.expr(&entry.value);
+
+			// Redefinitions fail after their values are evaluated
+			if let Some(&def_name) = positions.get(entry.name.value()) {
+				let kind = InterpretErrorKind::ObjEntryRedef(error::ObjEntryRedef { def_name });
+				self.fail(InterpretError::new(entry.name.span(), kind));
+				return;
+			}
+
+			positions.insert(entry.name.value_owned(), entry.name.span());
+			shape.push(self.name(entry.name.value()));
+		}
+
+		self.chunk.shapes.push(shape);
+		self.emit(Op::Obj(self.chunk.shapes.len() - 1));
+	}
+
+	fn call(&mut self, call: &ast::Call<'ast>) {
+		let args = call.args.items();
+
+		self.chunk.calls.push(CallProto {
+			site: ops::call_site(call),
+			arg_spans: args.iter().map(|arg| arg.span()).collect()
+		});
+		let idx = self.chunk.calls.len() - 1;
+
+		self.term(&call.callee);
+		self.emit(Op::CheckCallable(idx));
+
+		for arg in args {
+			self.expr(arg);
+		}
+
+		self.emit(Op::Call(idx));
+	}
+}
@@ -0,0 +1,512 @@
+//! Bytecode compiler and stack-based virtual machine, an alternative to walking the syntax tree.
+//!
+//! Programs are compiled into [`Chunk`]s of instructions, which operate on a stack of values.
+//! Variables live in the [`Context`] scopes, where they are accessed by the slots which the resolver
+//! has bound them to, and every other operation is delegated to [`ops`], which the tree walker uses as well,
+//! so both backends behave the same.
+
+mod chunk;
+mod compiler;
+
+pub(crate) use chunk::Chunk;
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use prog_parser::{ast, ASTNode};
+
+use chunk::{Branch, Op, TryProto};
+use compiler::Compiler;
+
+use crate::context::Layout;
+use crate::ops::{self, ClassFields};
+use crate::resolver::Resolution;
+use crate::value::{self, CtrlFlow};
+use crate::{
+	error, overload, Context, ForIter, InterpretError, InterpretErrorKind, InterpretResult,
+	Interpreter, Value
+};
+
+pub(crate) fn compile_program<'ast>(
+	i: &Interpreter<'ast>,
+	program: &ast::Program<'ast>,
+	resolution: &Resolution
+) -> Chunk<'ast> {
+	let mut compiler = Compiler::new(i.limits.max_size.is_some(), resolution);
+	compiler.stmts(&program.stmts);
+	compiler.finish()
+}
+
+/// Compiles the expression into a chunk which returns its value.
+pub(crate) fn compile_expr<'ast>(
+	i: &Interpreter<'ast>,
+	expr: &ast::Expr<'ast>,
+	resolution: &Resolution
+) -> Chunk<'ast> {
+	let mut compiler = Compiler::new(i.limits.max_size.is_some(), resolution);
+	compiler.expr(expr);
+	compiler.ret(expr.span());
+	compiler.finish()
+}
+
+/// Runs the chunk of a program or a function body, which must produce a final value.
+pub(crate) fn run_program<'ast>(
+	i: &mut Interpreter<'ast>,
+	chunk: &Chunk<'ast>
+) -> InterpretResult<'ast, Value<'ast>> {
+	let ctrl = run(i, chunk)?;
+	ops::program_result(ctrl)
+}
+
+/// Runs the chunk in the current scope, resulting in the control flow which left it, if any.
+///
+/// Scopes entered by the chunk are left even if it fails midway.
+fn run<'ast>(
+	i: &mut Interpreter<'ast>,
+	chunk: &Chunk<'ast>
+) -> InterpretResult<'ast, Option<CtrlFlow<'ast>>> {
+	let mut machine = Machine::default();
+	let result = machine.run(i, chunk);
+
+	if let Some(ctx) = machine.scopes.into_iter().next() {
+		i.context = ctx;
+	}
+
+	result
+}
+
+/// Runs the branch in a scope of its own, optionally binding a variable in it.
+fn run_scoped<'ast>(
+	i: &mut Interpreter<'ast>,
+	branch: &Branch<'ast>,
+	var: Option<(&str, Value<'ast>)>
+) -> InterpretResult<'ast, Option<CtrlFlow<'ast>>> {
+	let original_ctx = i
+		.context
+		.swap(i.context.child_with(Rc::clone(&branch.layout)));
+
+	if let Some((name, value)) = var {
+		i.context.insert(name, value);
+	}
+
+	let result = run(i, &branch.code);
+	i.context.swap(original_ctx);
+
+	result
+}
+
+fn run_try<'ast>(
+	i: &mut Interpreter<'ast>,
+	proto: &TryProto<'ast>
+) -> InterpretResult<'ast, Option<CtrlFlow<'ast>>> {
+	let mut result = run_scoped(i, &proto.body, None).map_err(|error| i.with_trace(error));
+
+	if let (Err(error), Some((name, catch))) = (&result, &proto.catch) {
+		if error.kind.is_catchable() {
+			let value = crate::error_value(error);
+			result = run_scoped(i, catch, Some((name, value)));
+		}
+	}
+
+	// Control flow and errors of the `finally` branch take precedence over the pending result
+	if let Some(ref finally) = proto.finally {
+		if let Some(ctrl) = run_scoped(i, finally, None)? {
+			return Ok(Some(ctrl));
+		}
+	}
+
+	result
+}
+
+/// State of a chunk being run.
+#[derive(Default)]
+struct Machine<'ast> {
+	stack: Vec<Value<'ast>>,
+	/// Scopes to restore when the entered ones are left, the outermost one first.
+	scopes: Vec<Context<'ast>>,
+	/// Iterations of the `for` loops in progress, along with whether they are keyed.
+	iters: Vec<(ForIter<'ast>, bool)>,
+	/// Fields of the classes whose bodies are in progress.
+	classes: Vec<ClassFields<'ast>>
+}
+
+impl<'ast> Machine<'ast> {
+	fn pop(&mut self) -> Value<'ast> {
+		self.stack
+			.pop()
+			.expect("Attempted to pop a value off an empty stack")
+	}
+
+	fn peek(&self) -> &Value<'ast> {
+		self.stack
+			.last()
+			.expect("Attempted to peek at a value of an empty stack")
+	}
+
+	/// Pops the given number of values, in the order they were pushed in.
+	fn pop_many(&mut self, len: usize) -> Vec<Value<'ast>> {
+		self.stack.split_off(self.stack.len() - len)
+	}
+
+	fn enter_scope(&mut self, i: &mut Interpreter<'ast>, layout: &Rc<Layout>) {
+		let child = i.context.child_with(Rc::clone(layout));
+		self.scopes.push(i.context.swap(child));
+	}
+
+	fn exit_scope(&mut self, i: &mut Interpreter<'ast>) {
+		let ctx = self
+			.scopes
+			.pop()
+			.expect("Attempted to exit a scope which was not entered");
+		i.context.swap(ctx);
+	}
+
+	fn unwind(&mut self, i: &mut Interpreter<'ast>, depth: usize) {
+		if let Some(ctx) = self.scopes.drain(depth..).next() {
+			i.context.swap(ctx);
+		}
+	}
+
+	fn run(
+		&mut self,
+		i: &mut Interpreter<'ast>,
+		chunk: &Chunk<'ast>
+	) -> InterpretResult<'ast, Option<CtrlFlow<'ast>>> {
+		let mut pc = 0;
+
+		while let Some(&op) = chunk.ops.get(pc) {
+			pc += 1;
+
+			match op {
+				Op::Step(span) => {
+					i.limits
+						.step()
+						.map_err(|kind| InterpretError::new(chunk.spans[span], kind))?;
+				}
+
+				Op::CheckSize(span) => {
+					i.limits
+						.check_size(self.peek())
+						.map_err(|kind| InterpretError::new(chunk.spans[span], kind))?;
+				}
+
+				Op::Const(idx) => self.stack.push(chunk.consts[idx].clone()),
+				Op::None => self.stack.push(Value::None),
+
+				Op::Pop => {
+					self.pop();
+				}
+
+				Op::Load(var) => {
+					let var = chunk.refs[var];
+					let value = ops::load(
+						i,
+						var.binding,
+						&chunk.names[var.name],
+						chunk.spans[var.span]
+					)?;
+					self.stack.push(value);
+				}
+
+				Op::Define(slot) => {
+					let value = self.pop();
+					i.context.define_at(slot, value);
+				}
+
+				Op::Assign(var) => {
+					let var = chunk.refs[var];
+					let value = self.pop();
+
+					let (name, span) = (&chunk.names[var.name], chunk.spans[var.span]);
+					ops::store(i, var.binding, name, value, span)?;
+				}
+
+				Op::Binary { op, span, span_rhs } => {
+					let rhs = self.pop();
+					let lhs = self.pop();
+
+					let value =
+						ops::binary(i, op, lhs, rhs, chunk.spans[span], chunk.spans[span_rhs])?;
+					self.stack.push(value);
+				}
+
+				Op::Unary { op, span } => {
+					let operand = self.pop();
+					let value = ops::unary(i, op, operand, chunk.spans[span])?;
+					self.stack.push(value);
+				}
+
+				Op::And(target) if !self.peek().is_truthy() => pc = target,
+				Op::Or(target) if self.peek().is_truthy() => pc = target,
+
+				Op::And(_) | Op::Or(_) => {
+					self.pop();
+				}
+
+				Op::Jump(target) => pc = target,
+
+				Op::JumpIfFalsy(target) => {
+					if !self.pop().is_truthy() {
+						pc = target;
+					}
+				}
+
+				Op::Interp { segment, span } => {
+					let value = self.pop();
+					let value = overload::stringify(i, &value, chunk.spans[span])?;
+
+					let Value::Str(str) = self.pop() else {
+						panic!("Attempted to interpolate into a value which is not a string");
+					};
+
+					let mut str = String::from(str);
+					str.push_str(&value);
+					str.push_str(&chunk.names[segment]);
+
+					self.stack.push(Value::Str(value::Str::from(str)));
+				}
+
+				Op::Func(idx) => {
+					let proto = &chunk.funcs[idx];
+
+					self.stack.push(Value::Func(value::Func {
+						ast: Rc::clone(&proto.ast),
+						args: proto.args.clone(),
+						ctx: i.context.child_with(Rc::clone(&proto.layout)),
+						code: Some(Rc::clone(&proto.code))
+					}));
+				}
+
+				Op::List(len) => {
+					let items = self.pop_many(len);
+					self.stack.push(Value::List(value::List::from(items)));
+				}
+
+				Op::Obj(shape) => {
+					let names = &chunk.shapes[shape];
+					let values = self.pop_many(names.len());
+
+					let entries = names
+						.iter()
+						.map(|&name| chunk.names[name].clone())
+						.zip(values)
+						.collect::<HashMap<_, _>>();
+
+					self.stack.push(Value::Obj(value::Obj::from(entries)));
+				}
+
+				Op::Extern(span) => {
+					let value = self.pop();
+					let value = ops::external(i, value, chunk.spans[span])?;
+					self.stack.push(value);
+				}
+
+				Op::CheckCallable(call) => {
+					ops::check_callable(self.peek(), chunk.calls[call].site.callee)?;
+				}
+
+				Op::Call(call) => {
+					let proto = &chunk.calls[call];
+					let args = self.pop_many(proto.arg_spans.len());
+					let callee = self.pop();
+
+					let value = ops::call(i, callee, args, &proto.arg_spans, proto.site.clone())?;
+					self.stack.push(value);
+				}
+
+				Op::CheckIndexable(span) => ops::check_indexable(self.peek(), chunk.spans[span])?,
+
+				Op::Index {
+					span,
+					span_list,
+					span_index
+				} => {
+					let index = self.pop();
+					let list = self.pop();
+
+					let spans = (
+						chunk.spans[span],
+						chunk.spans[span_list],
+						chunk.spans[span_index]
+					);
+					let value = ops::index(i, list, index, spans.0, spans.1, spans.2)?;
+					self.stack.push(value);
+				}
+
+				Op::Field {
+					name,
+					span_obj,
+					span_field
+				} => {
+					let object = self.pop();
+					let name = &chunk.names[name];
+
+					let value = ops::field(
+						i,
+						object,
+						name,
+						chunk.spans[span_obj],
+						chunk.spans[span_field]
+					)?;
+					self.stack.push(value);
+				}
+
+				Op::CheckIndexAssignable(span) => {
+					ops::check_index_assignable(self.peek(), chunk.spans[span])?;
+				}
+
+				Op::CheckAssignIndex(span) => {
+					let [list, index] = &self.stack[self.stack.len() - 2..] else {
+						panic!("Attempted to check an index missing from the stack");
+					};
+
+					ops::check_assign_index(list, index, chunk.spans[span])?;
+				}
+
+				Op::AssignIndex { span, span_index } => {
+					let value = self.pop();
+					let index = self.pop();
+					let list = self.pop();
+
+					ops::assign_index(
+						i,
+						list,
+						index,
+						value,
+						chunk.spans[span],
+						chunk.spans[span_index]
+					)?;
+				}
+
+				Op::CheckFieldAssignable {
+					name,
+					span_obj,
+					span_field
+				} => {
+					let (span_obj, span_field) = (chunk.spans[span_obj], chunk.spans[span_field]);
+					ops::check_field_assignable(
+						self.peek(),
+						&chunk.names[name],
+						span_obj,
+						span_field
+					)?;
+				}
+
+				Op::AssignField(name) => {
+					let value = self.pop();
+					let object = self.pop();
+
+					ops::assign_field(object, &chunk.names[name], value);
+				}
+
+				Op::Return(span) => {
+					let value = self.pop();
+					return Ok(Some(CtrlFlow::Return(chunk.spans[span], Box::new(value))));
+				}
+
+				Op::Break(span) => return Ok(Some(CtrlFlow::Break(chunk.spans[span]))),
+				Op::Continue(span) => return Ok(Some(CtrlFlow::Continue(chunk.spans[span]))),
+
+				Op::EnterScope(layout) => self.enter_scope(i, &chunk.layouts[layout]),
+				Op::ExitScope => self.exit_scope(i),
+				Op::Unwind(depth) => self.unwind(i, depth),
+
+				Op::Iter(span) => {
+					let value = self.pop();
+					self.iters.push(ops::iterate(value, chunk.spans[span])?);
+				}
+
+				Op::Next { exit, vars, layout } => {
+					let (next_pair, keyed) = self
+						.iters
+						.last_mut()
+						.expect("Attempted to iterate outside of a loop");
+					let keyed = *keyed;
+
+					match next_pair(i)? {
+						Some(pair) => {
+							let (var, value_var) = chunk.vars[vars];
+							let vars = (
+								chunk.names[var].as_str(),
+								value_var.map(|v| chunk.names[v].as_str())
+							);
+
+							self.enter_scope(i, &chunk.layouts[layout]);
+							ops::bind_iteration(i, vars, keyed, pair);
+						}
+
+						None => pc = exit
+					}
+				}
+
+				Op::EndIter => {
+					self.iters.pop();
+				}
+
+				Op::Throw(span) => {
+					let value = self.pop();
+
+					return Err(InterpretError::new(
+						chunk.spans[span],
+						InterpretErrorKind::Thrown(error::Thrown(value))
+					));
+				}
+
+				Op::Try(idx) => {
+					let proto = &chunk.tries[idx];
+
+					// `break` and `continue` leaving the branches apply to the loop around the statement
+					match (run_try(i, proto)?, proto.exits) {
+						(None, _) => (),
+
+						(Some(CtrlFlow::Break(_)), Some(exits)) => {
+							self.unwind(i, exits.depth);
+							pc = exits.brk;
+						}
+
+						(Some(CtrlFlow::Continue(_)), Some(exits)) => {
+							self.unwind(i, exits.depth);
+							pc = exits.cont;
+						}
+
+						(Some(ctrl), _) => return Ok(Some(ctrl))
+					}
+				}
+
+				Op::Fail(idx) => return Err(chunk.errors[idx].clone()),
+
+				Op::Class(idx) => {
+					let proto = &chunk.classes[idx];
+
+					let parent = match proto.parent {
+						Some(span) => Some(ops::parent_class(self.pop(), chunk.spans[span])?),
+						None => None
+					};
+
+					let layout = Rc::clone(&proto.layout);
+					let (parent_ctx, fields) =
+						ops::enter_class(i, proto.name.clone(), parent, layout);
+					self.scopes.push(parent_ctx);
+					self.classes.push(fields);
+				}
+
+				Op::ClassField(name) => {
+					let value = self.pop();
+					let fields = self
+						.classes
+						.last()
+						.expect("Attempted to define a field outside of a class");
+
+					fields.borrow_mut().insert(chunk.names[name].clone(), value);
+				}
+
+				Op::EndClass => {
+					self.classes.pop();
+					self.exit_scope(i);
+				}
+			}
+		}
+
+		Ok(None)
+	}
+}
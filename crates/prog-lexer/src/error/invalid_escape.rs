@@ -0,0 +1,20 @@
+use ariadne::Label;
+use prog_utils::pretty_errors::{color_generator, AriadneCompatible, Span};
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct InvalidEscape;
+
+impl<'s> AriadneCompatible<'s> for InvalidEscape {
+	fn message(&self) -> &'static str { "invalid escape sequence" }
+
+	fn note(&self) -> Option<&str> {
+		Some("valid escape sequences are `\\n`, `\\t`, `\\r`, `\\0`, `\\\\`, `\\\"`, `\\{`, `\\}` and `\\u{...}`")
+	}
+
+	fn labels(&self, span: Span<'s>) -> Vec<ariadne::Label<Span<'s>>> {
+		let mut colors = color_generator();
+
+		vec![Label::new(span).with_color(colors.next())]
+	}
+}
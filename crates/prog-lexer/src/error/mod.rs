@@ -1,8 +1,12 @@
+mod invalid_escape;
 mod malformed_number;
 mod unexpected_char;
+mod unterminated_string;
 
+pub use invalid_escape::InvalidEscape;
 pub use malformed_number::MalformedNumber;
 pub use unexpected_char::UnexpectedChar;
+pub use unterminated_string::UnterminatedString;
 
 use prog_utils::pretty_errors::{PrettyError, PrettyErrorKind};
 
@@ -11,8 +15,10 @@ pub type LexError<'s> = PrettyError<'s, LexErrorKind>;
 #[derive(Debug, Clone, prog_macros::AriadneCompatible)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum LexErrorKind {
+	InvalidEscape(InvalidEscape),
 	MalformedNumber(MalformedNumber),
-	UnexpectedChar(UnexpectedChar)
+	UnexpectedChar(UnexpectedChar),
+	UnterminatedString(UnterminatedString)
 }
 
 impl PrettyErrorKind<'_> for LexErrorKind {}
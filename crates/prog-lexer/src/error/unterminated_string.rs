@@ -0,0 +1,22 @@
+use ariadne::Label;
+use prog_utils::pretty_errors::{color_generator, AriadneCompatible, Span};
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct UnterminatedString;
+
+impl<'s> AriadneCompatible<'s> for UnterminatedString {
+	fn message(&self) -> &'static str { "unterminated string" }
+
+	fn note(&self) -> Option<&str> {
+		Some("use triple-quoted strings (`\"\"\"...\"\"\"`) for strings spanning multiple lines")
+	}
+
+	fn labels(&self, span: Span<'s>) -> Vec<ariadne::Label<Span<'s>>> {
+		let mut colors = color_generator();
+
+		vec![Label::new(span)
+			.with_message("string starts here")
+			.with_color(colors.next())]
+	}
+}
@@ -10,6 +10,19 @@ use prog_utils::pretty_errors::{Position, Span};
 
 pub type LexResult<'s, T> = Result<T, LexError<'s>>;
 
+/// Delimiter of the multi-line strings.
+const TRIPLE_QUOTE: &str = "\"\"\"";
+
+/// Interpolated string, an embedded expression of which is being lexed.
+#[derive(Debug, Clone, Copy)]
+struct OpenString {
+	/// Index of the opening quote.
+	start: usize,
+	triple: bool,
+	/// Number of the braces opened by the embedded expression.
+	depth: usize
+}
+
 fn unexpected_char<'src>(
 	ls: &mut LexStream<'src>,
 	found: char,
@@ -27,8 +40,20 @@ fn unexpected_char<'src>(
 }
 
 pub fn lex<'src>(source: &'src str, file: &'src str) -> LexResult<'src, TokenStream<'src>> {
+	let mut ts = lex_with_comments(source, file)?;
+	ts.filter_comments();
+
+	Ok(ts)
+}
+
+/// Same as [`lex`], but keeps the [`TokenKind::Comment`] tokens in the stream.
+pub fn lex_with_comments<'src>(
+	source: &'src str,
+	file: &'src str
+) -> LexResult<'src, TokenStream<'src>> {
 	let mut ls = LexStream::new(source, file);
 	let mut ts = TokenStream::new();
+	let mut strings: Vec<OpenString> = vec![];
 
 	while let Some((start_index, char)) = ls.next() {
 		let kind = match char {
@@ -49,12 +74,41 @@ pub fn lex<'src>(source: &'src str, file: &'src str) -> LexResult<'src, TokenStr
 
 			'(' => TokenKind::LeftParen,
 			')' => TokenKind::RightParen,
-			'{' => TokenKind::LeftBrace,
-			'}' => TokenKind::RightBrace,
+			'{' => {
+				if let Some(string) = strings.last_mut() {
+					string.depth += 1;
+				}
+
+				TokenKind::LeftBrace
+			}
+
+			'}' => match strings.last_mut() {
+				// The embedded expression is over, and so the string continues
+				Some(string) if string.depth == 0 => {
+					let OpenString { start, triple, .. } = *string;
+
+					if string_segment(&mut ls, start, triple, false)? {
+						TokenKind::StringMiddle
+					} else {
+						strings.pop();
+						TokenKind::StringEnd
+					}
+				}
+
+				Some(string) => {
+					string.depth -= 1;
+					TokenKind::RightBrace
+				}
+
+				None => TokenKind::RightBrace
+			},
 			'[' => TokenKind::LeftBracket,
 			']' => TokenKind::RightBracket,
 
-			'"' => string(&mut ls)?,
+			'"' => string(&mut ls, &mut strings, start_index, false)?,
+			'r' if ls.peek_matches_exact('"', true) => {
+				string(&mut ls, &mut strings, start_index, true)?
+			}
 
 			c if c.is_ascii_alphabetic() || c == '_' => ident_or_keyword(&mut ls, c),
 			c if c.is_ascii_digit() => number(&mut ls, c)?,
@@ -79,13 +133,15 @@ pub fn lex<'src>(source: &'src str, file: &'src str) -> LexResult<'src, TokenStr
 		ts.push(Token::new(kind, span));
 	}
 
+	if let Some(string) = strings.last() {
+		return Err(unterminated_string(&ls, string.start, source.len()));
+	}
+
 	ts.push(Token::new(
 		TokenKind::Eof,
 		Span::new(source, file, Position::new(source.len(), source.len()))
 	));
 
-	ts.filter_comments();
-
 	Ok(ts)
 }
 
@@ -163,43 +219,208 @@ fn neq<'src>(ls: &mut LexStream<'src>) -> LexResult<'src, TokenKind> {
 	Ok(TokenKind::Neq)
 }
 
-// TODO: escape sequences
-fn string<'src>(ls: &mut LexStream<'src>) -> LexResult<'src, TokenKind> {
-	let start_index = ls.position() - 1;
+/// Lexes a string literal after its opening quote, which is preceded by `r` in raw strings.
+///
+/// If the string is interpolated, only its segment up to the first embedded expression is lexed.
+fn string<'src>(
+	ls: &mut LexStream<'src>,
+	strings: &mut Vec<OpenString>,
+	start: usize,
+	raw: bool
+) -> LexResult<'src, TokenKind> {
+	let triple = ls.source()[ls.position()..].starts_with("\"\"");
 
-	let mut closed = false;
-	let mut last_char = (start_index, '"');
+	if triple {
+		ls.next();
+		ls.next();
+	}
 
-	while let Some((index, next_char)) = ls.peek() {
-		last_char = (*index, *next_char);
+	if !string_segment(ls, start, triple, raw)? {
+		return Ok(TokenKind::String);
+	}
 
-		if *next_char == '\n' {
-			break;
+	strings.push(OpenString {
+		start,
+		triple,
+		depth: 0
+	});
+
+	Ok(TokenKind::StringStart)
+}
+
+/// Lexes the contents of the string which starts at `start` up to its closing quotes or an embedded expression.
+///
+/// Return value of `true` indicates that the segment ends with the opening brace of an embedded expression.
+fn string_segment<'src>(
+	ls: &mut LexStream<'src>,
+	start: usize,
+	triple: bool,
+	raw: bool
+) -> LexResult<'src, bool> {
+	let delimiter = if triple { TRIPLE_QUOTE } else { "\"" };
+
+	loop {
+		let Some(&(index, char)) = ls.peek() else {
+			return Err(unterminated_string(ls, start, ls.source().len()));
+		};
+
+		if char == '\n' && !triple {
+			return Err(unterminated_string(ls, start, index));
 		}
 
-		if *next_char == '"' {
-			closed = true;
-			ls.next();
+		if ls.source()[index..].starts_with(delimiter) {
+			for _ in 0..delimiter.len() {
+				ls.next();
+			}
 
-			break;
+			return Ok(false);
 		}
 
 		ls.next();
+
+		if raw {
+			continue;
+		}
+
+		if char == '{' {
+			return Ok(true);
+		}
+
+		if char != '\\' {
+			continue;
+		}
+
+		match escape_sequence(&ls.source()[index..]) {
+			Ok((_, len)) => {
+				// The backslash has already been consumed
+				while ls.position() < index + len {
+					ls.next();
+				}
+			}
+
+			Err(len) => {
+				return Err(LexError::from_raw_parts(
+					ls.source(),
+					ls.file(),
+					Position::new(index, index + len),
+					LexErrorKind::InvalidEscape(error::InvalidEscape)
+				));
+			}
+		}
 	}
+}
 
-	if !closed {
-		return Err(LexError::from_raw_parts(
-			ls.source(),
-			ls.file(),
-			Position::new(last_char.0, last_char.0 + 1),
-			LexErrorKind::UnexpectedChar(error::UnexpectedChar {
-				found: last_char.1,
-				expected: Some('"')
-			})
-		));
+fn unterminated_string<'src>(ls: &LexStream<'src>, start: usize, end: usize) -> LexError<'src> {
+	LexError::from_raw_parts(
+		ls.source(),
+		ls.file(),
+		Position::new(start, end),
+		LexErrorKind::UnterminatedString(error::UnterminatedString)
+	)
+}
+
+/// Decodes the escape sequence at the start of `text`, which begins with a backslash.
+///
+/// Returns the decoded character along with the length of the sequence in bytes,
+/// or the length of its invalid part.
+fn escape_sequence(text: &str) -> Result<(char, usize), usize> {
+	let Some(char) = text[1..].chars().next() else {
+		return Err(1);
+	};
+
+	let decoded = match char {
+		'n' => '\n',
+		't' => '\t',
+		'r' => '\r',
+		'0' => '\0',
+		'\\' => '\\',
+		'"' => '"',
+		'{' => '{',
+		'}' => '}',
+		'u' => return unicode_escape_sequence(text),
+		_ => return Err(1 + char.len_utf8())
+	};
+
+	Ok((decoded, 1 + char.len_utf8()))
+}
+
+/// Decodes a `\u{...}` escape sequence with up to 6 hexadecimal digits.
+fn unicode_escape_sequence(text: &str) -> Result<(char, usize), usize> {
+	let Some(rest) = text[2..].strip_prefix('{') else {
+		return Err(2);
+	};
+
+	let digits = rest
+		.find(|c: char| !c.is_ascii_hexdigit())
+		.unwrap_or(rest.len());
+
+	// Length of `\u{` and the digits
+	let len = 3 + digits;
+
+	if digits == 0 || digits > 6 || !rest[digits..].starts_with('}') {
+		return Err(len);
+	}
+
+	let code = u32::from_str_radix(&rest[..digits], 16).map_err(|_| len)?;
+	char::from_u32(code).map(|c| (c, len + 1)).ok_or(len + 1)
+}
+
+/// Decodes the value of a string literal token or of a segment of an interpolated string,
+/// stripping its delimiters and replacing the escape sequences.
+///
+/// Leading line break of a triple-quoted string is not a part of its value.
+pub fn string_value(literal: &str) -> String {
+	let (raw, literal) = match literal.strip_prefix('r') {
+		Some(literal) => (true, literal),
+		None => (false, literal)
+	};
+
+	// An unescaped quote always terminates a regular string, so three of them can only delimit a triple-quoted one
+	let triple_start = literal.starts_with(TRIPLE_QUOTE);
+	let triple_end = literal.len() > TRIPLE_QUOTE.len() && literal.ends_with(TRIPLE_QUOTE);
+
+	let opening = if triple_start { TRIPLE_QUOTE.len() } else { 1 };
+	let closing = if literal.ends_with('{') || !triple_end {
+		1
+	} else {
+		TRIPLE_QUOTE.len()
+	};
+
+	let mut contents = &literal[opening..literal.len() - closing];
+
+	if triple_start {
+		contents = contents.strip_prefix('\n').unwrap_or(contents);
+	}
+
+	if raw {
+		return contents.to_owned();
+	}
+
+	let mut value = String::with_capacity(contents.len());
+	let mut rest = contents;
+
+	while let Some(index) = rest.find('\\') {
+		value.push_str(&rest[..index]);
+		rest = &rest[index..];
+
+		// The tokens are validated by the lexer, so the sequences are only kept as is for robustness
+		let len = match escape_sequence(rest) {
+			Ok((char, len)) => {
+				value.push(char);
+				len
+			}
+
+			Err(len) => {
+				value.push_str(&rest[..len]);
+				len
+			}
+		};
+
+		rest = &rest[len..];
 	}
 
-	Ok(TokenKind::String)
+	value.push_str(rest);
+	value
 }
 
 fn ident_or_keyword(ls: &mut LexStream<'_>, c: char) -> TokenKind {
@@ -285,9 +506,7 @@ mod tests {
 	fn quick_lex(input: &str) -> Box<[TokenKind]> {
 		let ts = lex(input, "<stdin").unwrap();
 		let tokens = ts.unwrap();
-		let kinds = tokens.into_iter().map(|t| t.kind()).collect::<Box<[_]>>();
-
-		kinds
+		tokens.into_iter().map(|t| t.kind()).collect::<Box<[_]>>()
 	}
 
 	#[test]
@@ -319,4 +538,79 @@ mod tests {
 			String, Ident, Ident, Ident, Eof
 		]);
 	}
+
+	#[test]
+	fn test_comments() {
+		let source = "a // line\n/* block */ b";
+		let ts = lex_with_comments(source, "<stdin>").unwrap();
+		let kinds = ts.buffer().iter().map(|t| t.kind()).collect::<Vec<_>>();
+
+		assert_eq!(kinds, [Ident, Comment, Comment, Ident, Eof]);
+		assert_eq!(*quick_lex(source), [Ident, Ident, Eof]);
+	}
+
+	fn lex_error(input: &str) -> (LexErrorKind, &str) {
+		let error = lex(input, "<stdin>").unwrap_err();
+		let position = error.span.position();
+
+		(error.kind, &input[position.start()..position.end()])
+	}
+
+	#[test]
+	fn test_string_kinds() {
+		assert_eq!(*quick_lex(r#"r"raw \q" """multi
+line""" "\"""#), [
+			String, String, String, Eof
+		]);
+	}
+
+	#[test]
+	fn test_string_values() {
+		assert_eq!(
+			string_value(r#""a\tb\n\"c\" \\ \u{1F600}""#),
+			"a\tb\n\"c\" \\ 😀"
+		);
+		assert_eq!(string_value(r#"r"C:\path\n""#), r"C:\path\n");
+		assert_eq!(
+			string_value("\"\"\"\n\tline \"one\"\n\tline\\ttwo\"\"\""),
+			"\tline \"one\"\n\tline\ttwo"
+		);
+		assert_eq!(string_value(r#""""#), "");
+	}
+
+	#[test]
+	fn test_interpolation() {
+		assert_eq!(*quick_lex(r#""a {b} c {{ d = "e {f}" }} g""#), [
+			StringStart, Ident, StringMiddle, LeftBrace, Ident, Eq, StringStart, Ident, StringEnd,
+			RightBrace, StringEnd, Eof
+		]);
+
+		assert_eq!(string_value(r#""a \{ {"#), "a { ");
+		assert_eq!(string_value("} b {"), " b ");
+		assert_eq!(string_value("}\n\"\"\""), "\n");
+		assert_eq!(string_value("\"\"\"\na {"), "a ");
+	}
+
+	#[test]
+	fn test_string_errors() {
+		let (kind, span) = lex_error(r#"print("ab\qc")"#);
+		assert!(matches!(kind, LexErrorKind::InvalidEscape(_)));
+		assert_eq!(span, r"\q");
+
+		let (kind, span) = lex_error(r#""\u{110000}""#);
+		assert!(matches!(kind, LexErrorKind::InvalidEscape(_)));
+		assert_eq!(span, r"\u{110000}");
+
+		let (kind, span) = lex_error(r#""\u{12"#);
+		assert!(matches!(kind, LexErrorKind::InvalidEscape(_)));
+		assert_eq!(span, r"\u{12");
+
+		let (kind, span) = lex_error("def a = \"abc\ndef b");
+		assert!(matches!(kind, LexErrorKind::UnterminatedString(_)));
+		assert_eq!(span, "\"abc");
+
+		let (kind, span) = lex_error("\"a {b");
+		assert!(matches!(kind, LexErrorKind::UnterminatedString(_)));
+		assert_eq!(span, "\"a {b");
+	}
 }
@@ -88,6 +88,10 @@ pub enum TokenKind {
 	Break,
 	/// `continue`
 	Continue,
+	/// `for`
+	For,
+	/// `in`
+	In,
 	/// `if`
 	If,
 	/// `then`
@@ -106,14 +110,30 @@ pub enum TokenKind {
 	Not,
 	/// `class`
 	Class,
+	/// `extends`
+	Extends,
 	/// `extern`
 	Extern,
+	/// `throw`
+	Throw,
+	/// `try`
+	Try,
+	/// `catch`
+	Catch,
+	/// `finally`
+	Finally,
 
 	// Special tokens
 	Ident,
 	Comment,
 	Number,
 	String,
+	/// Segment of an interpolated string up to its first embedded expression, `"...{`
+	StringStart,
+	/// Segment of an interpolated string between two embedded expressions, `}...{`
+	StringMiddle,
+	/// Segment of an interpolated string after its last embedded expression, `}..."`
+	StringEnd,
 
 	// Operator tokens
 	/// `+`
@@ -180,6 +200,8 @@ impl TokenKind {
 			"while" => Some(Self::While),
 			"break" => Some(Self::Break),
 			"continue" => Some(Self::Continue),
+			"for" => Some(Self::For),
+			"in" => Some(Self::In),
 			"if" => Some(Self::If),
 			"then" => Some(Self::Then),
 			"elseif" => Some(Self::ElseIf),
@@ -189,7 +211,12 @@ impl TokenKind {
 			"or" => Some(Self::Or),
 			"not" => Some(Self::Not),
 			"class" => Some(Self::Class),
+			"extends" => Some(Self::Extends),
 			"extern" => Some(Self::Extern),
+			"throw" => Some(Self::Throw),
+			"try" => Some(Self::Try),
+			"catch" => Some(Self::Catch),
+			"finally" => Some(Self::Finally),
 
 			_ => None
 		}
@@ -209,6 +236,8 @@ impl Display for TokenKind {
 			Self::While => write!(f, "while"),
 			Self::Break => write!(f, "break"),
 			Self::Continue => write!(f, "continue"),
+			Self::For => write!(f, "for"),
+			Self::In => write!(f, "in"),
 			Self::If => write!(f, "if"),
 			Self::Then => write!(f, "then"),
 			Self::ElseIf => write!(f, "elseif"),
@@ -218,11 +247,18 @@ impl Display for TokenKind {
 			Self::Or => write!(f, "or"),
 			Self::Not => write!(f, "not"),
 			Self::Class => write!(f, "class"),
+			Self::Extends => write!(f, "extends"),
 			Self::Extern => write!(f, "extern"),
+			Self::Throw => write!(f, "throw"),
+			Self::Try => write!(f, "try"),
+			Self::Catch => write!(f, "catch"),
+			Self::Finally => write!(f, "finally"),
 			Self::Ident => write!(f, "identifier"),
 			Self::Comment => write!(f, "comment"),
 			Self::Number => write!(f, "number"),
 			Self::String => write!(f, "string"),
+			Self::StringStart => write!(f, "string"),
+			Self::StringMiddle | Self::StringEnd => write!(f, "}}"),
 			Self::Plus => write!(f, "+"),
 			Self::Minus => write!(f, "-"),
 			Self::Asterisk => write!(f, "*"),
@@ -0,0 +1,585 @@
+use std::collections::HashMap;
+
+use prog_parser::ast::{self, Expr, Stmt, Term};
+use prog_parser::{ASTNode, Position};
+
+pub type DefId = usize;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DefKind {
+	Variable,
+	Function,
+	Parameter,
+	Class,
+	Field,
+	Method
+}
+
+/// Binding introduced by a `def`, a function parameter, or a class definition.
+#[derive(Debug)]
+pub struct Definition {
+	pub name: String,
+	pub kind: DefKind,
+	/// Position of the defined name.
+	pub name_pos: Position,
+	/// Position of the whole definition.
+	pub pos: Position,
+	/// Fields of a class, or classes and functions defined within a function.
+	pub children: Vec<DefId>
+}
+
+/// Usage of a name, along with the definitions it may refer to.
+#[derive(Debug)]
+pub struct Reference {
+	pub pos: Position,
+	pub targets: Vec<DefId>
+}
+
+/// Definitions and references of a single file.
+#[derive(Debug, Default)]
+pub struct Analysis {
+	pub defs: Vec<Definition>,
+	pub refs: Vec<Reference>,
+	/// Classes and functions which are not nested in other ones.
+	pub outline: Vec<DefId>
+}
+
+impl Analysis {
+	pub fn new(program: &ast::Program) -> Self {
+		let mut walker = Walker::default();
+
+		walker.push_scope();
+		walker.walk_stmts(&program.stmts);
+		walker.pop_scope();
+
+		walker.finish()
+	}
+
+	/// Returns the definitions of the name at `offset`, which may be either a reference or a definition itself.
+	pub fn definitions_at(&self, offset: usize) -> Vec<DefId> {
+		if let Some(id) = self.defs.iter().position(|d| contains(d.name_pos, offset)) {
+			return vec![id];
+		}
+
+		self.refs
+			.iter()
+			.find(|r| contains(r.pos, offset))
+			.map(|r| r.targets.clone())
+			.unwrap_or_default()
+	}
+
+	/// Returns the position of the name at `offset`, be it a reference or a definition.
+	pub fn name_at(&self, offset: usize) -> Option<Position> {
+		let defs = self.defs.iter().map(|d| d.name_pos);
+		let refs = self.refs.iter().map(|r| r.pos);
+
+		defs.chain(refs).find(|&pos| contains(pos, offset))
+	}
+
+	/// Returns the positions of all of the references to the definition.
+	pub fn references_to(&self, id: DefId) -> impl Iterator<Item = Position> + '_ {
+		self.refs
+			.iter()
+			.filter(move |r| r.targets.contains(&id))
+			.map(|r| r.pos)
+	}
+}
+
+fn contains(pos: Position, offset: usize) -> bool { pos.start() <= offset && offset <= pos.end() }
+
+/// Field access, which is resolved once all of the classes are known.
+struct FieldAccess {
+	pos: Position,
+	name: String,
+	/// Class of `self` or the parent class of `super`, if the field is accessed on either.
+	class: Option<DefId>
+}
+
+/// Name which has not been defined by the time it was used,
+/// e.g. a recursive call or a call to a function defined further down.
+struct Unresolved {
+	pos: Position,
+	name: String,
+	scopes: Vec<usize>
+}
+
+#[derive(Default)]
+struct Walker {
+	analysis: Analysis,
+	/// Names defined in all of the scopes, including the ones which have been exited.
+	scopes: Vec<HashMap<String, Vec<DefId>>>,
+	/// Indices of the scopes which are currently entered, with the innermost one last.
+	chain: Vec<usize>,
+	unresolved: Vec<Unresolved>,
+	field_accesses: Vec<FieldAccess>,
+	/// Parent classes of the classes which extend a class defined in the same file.
+	class_parents: HashMap<DefId, DefId>,
+	/// Class whose body is being walked.
+	class: Option<DefId>,
+	/// Class or function which the new classes and functions are nested in.
+	parent: Option<DefId>
+}
+
+impl Walker {
+	fn push_scope(&mut self) {
+		self.chain.push(self.scopes.len());
+		self.scopes.push(HashMap::new());
+	}
+
+	fn pop_scope(&mut self) { self.chain.pop(); }
+
+	fn define(&mut self, name: &ast::Ident, kind: DefKind, pos: Position) -> DefId {
+		let id = self.analysis.defs.len();
+
+		self.analysis.defs.push(Definition {
+			name: name.value_owned(),
+			kind,
+			name_pos: name.position(),
+			pos,
+			children: vec![]
+		});
+
+		id
+	}
+
+	fn bind(&mut self, name: &str, id: DefId) {
+		let scope = *self.chain.last().expect("no scope has been entered");
+
+		self.scopes[scope]
+			.entry(name.to_owned())
+			.or_default()
+			.push(id);
+	}
+
+	fn add_to_outline(&mut self, id: DefId) {
+		match self.parent {
+			Some(parent) => self.analysis.defs[parent].children.push(id),
+			None => self.analysis.outline.push(id)
+		}
+	}
+
+	/// Returns the innermost definition of the name which is visible at this point.
+	fn lookup(&self, name: &str) -> Option<DefId> {
+		self.chain
+			.iter()
+			.rev()
+			.find_map(|&scope| self.scopes[scope].get(name)?.last().copied())
+	}
+
+	fn reference(&mut self, name: &ast::Ident) {
+		let pos = name.position();
+		let name = name.value();
+
+		match self.lookup(name) {
+			Some(id) => self.analysis.refs.push(Reference {
+				pos,
+				targets: vec![id]
+			}),
+
+			None => self.unresolved.push(Unresolved {
+				pos,
+				name: name.to_owned(),
+				scopes: self.chain.clone()
+			})
+		}
+	}
+
+	fn finish(mut self) -> Analysis {
+		for name in std::mem::take(&mut self.unresolved) {
+			let target = name
+				.scopes
+				.iter()
+				.rev()
+				.find_map(|&scope| self.scopes[scope].get(&name.name)?.first().copied());
+
+			if let Some(id) = target {
+				self.analysis.refs.push(Reference {
+					pos: name.pos,
+					targets: vec![id]
+				});
+			}
+		}
+
+		for access in std::mem::take(&mut self.field_accesses) {
+			let is_field = |d: &Definition| {
+				matches!(d.kind, DefKind::Field | DefKind::Method) && d.name == access.name
+			};
+
+			// Fields are inherited, so the closest class to define the field is the one it belongs to
+			let defs = &self.analysis.defs;
+			let ancestry =
+				std::iter::successors(access.class, |class| self.class_parents.get(class).copied());
+			let mut targets = ancestry
+				.map(|class| {
+					let fields = defs[class].children.iter().copied();
+					fields.filter(|&id| is_field(&defs[id])).collect::<Vec<_>>()
+				})
+				.find(|fields| !fields.is_empty())
+				.unwrap_or_default();
+
+			// Not knowing the type of the object, any field with the same name may be the one
+			if targets.is_empty() {
+				targets = (0..defs.len()).filter(|&id| is_field(&defs[id])).collect();
+			}
+
+			if !targets.is_empty() {
+				self.analysis.refs.push(Reference {
+					pos: access.pos,
+					targets
+				});
+			}
+		}
+
+		self.analysis
+	}
+
+	fn walk_stmts(&mut self, stmts: &[Stmt]) {
+		for stmt in stmts {
+			self.walk_stmt(stmt);
+		}
+	}
+
+	fn walk_block(&mut self, stmts: &[Stmt]) {
+		self.push_scope();
+		self.walk_stmts(stmts);
+		self.pop_scope();
+	}
+
+	fn walk_stmt(&mut self, stmt: &Stmt) {
+		match stmt {
+			Stmt::VarDefine(stmt) => self.walk_var_define(stmt, None),
+			Stmt::VarAssign(stmt) => {
+				self.walk_expr(&stmt.value);
+				self.reference(&stmt.name);
+			}
+			Stmt::DoBlock(stmt) => self.walk_block(&stmt.stmts),
+			Stmt::Return(stmt) => self.walk_expr(&stmt.value),
+			Stmt::Call(stmt) => self.walk_call(stmt),
+			Stmt::WhileLoop(stmt) => {
+				self.walk_expr(&stmt.cond);
+				self.walk_block(&stmt.block.stmts);
+			}
+			Stmt::ForLoop(stmt) => {
+				self.walk_expr(&stmt.iter);
+				self.push_scope();
+
+				let value = stmt.value.as_ref().map(|(_, value)| value);
+
+				for var in std::iter::once(&stmt.var).chain(value) {
+					let id = self.define(var, DefKind::Variable, var.position());
+					self.bind(var.value(), id);
+				}
+
+				self.walk_block(&stmt.block.stmts);
+				self.pop_scope();
+			}
+			Stmt::Break(_) | Stmt::Continue(_) => (),
+			Stmt::If(stmt) => {
+				self.walk_expr(&stmt.cond);
+				self.walk_block(&stmt.stmts);
+
+				for b in stmt.b_elifs.iter() {
+					self.walk_expr(&b.cond);
+					self.walk_block(&b.stmts);
+				}
+
+				if let Some(ref b) = stmt.b_else {
+					self.walk_block(&b.stmts);
+				}
+			}
+			Stmt::Throw(stmt) => self.walk_expr(&stmt.value),
+			Stmt::Try(stmt) => {
+				self.walk_block(&stmt.stmts);
+
+				if let Some(ref b) = stmt.b_catch {
+					self.push_scope();
+
+					let id = self.define(&b.name, DefKind::Variable, b.name.position());
+					self.bind(b.name.value(), id);
+
+					self.walk_block(&b.stmts);
+					self.pop_scope();
+				}
+
+				if let Some(ref b) = stmt.b_finally {
+					self.walk_block(&b.stmts);
+				}
+			}
+			Stmt::ExprAssign(ast::ExprAssign::IndexAssign(stmt)) => {
+				self.walk_index_acc(&stmt.acc);
+				self.walk_expr(&stmt.value);
+			}
+			Stmt::ExprAssign(ast::ExprAssign::FieldAssign(stmt)) => {
+				self.walk_field_acc(&stmt.acc);
+				self.walk_expr(&stmt.value);
+			}
+			Stmt::ClassDef(stmt) => self.walk_class_def(stmt),
+			Stmt::Error(_) => ()
+		}
+	}
+
+	fn walk_var_define(&mut self, stmt: &ast::VarDefine, class: Option<DefId>) {
+		let name = stmt.name();
+		let value = match stmt {
+			ast::VarDefine::WithValue { value, .. } => Some(value),
+			ast::VarDefine::NoValue { .. } => None
+		};
+
+		let is_func = matches!(value, Some(Expr::Term(Term::Func(_))));
+		let kind = match (class, is_func) {
+			(Some(_), true) => DefKind::Method,
+			(Some(_), false) => DefKind::Field,
+			(None, true) => DefKind::Function,
+			(None, false) => DefKind::Variable
+		};
+
+		let id = self.define(&name, kind, stmt.position());
+
+		match class {
+			Some(class) => self.analysis.defs[class].children.push(id),
+			None if is_func => self.add_to_outline(id),
+			None => ()
+		}
+
+		// Functions can refer to themselves, while the values of variables cannot
+		if class.is_none() && is_func {
+			self.bind(name.value(), id);
+		}
+
+		if let Some(value) = value {
+			let parent = self.parent;
+
+			if is_func {
+				self.parent = Some(id);
+			}
+
+			self.walk_expr(value);
+			self.parent = parent;
+		}
+
+		if class.is_none() && !is_func {
+			self.bind(name.value(), id);
+		}
+	}
+
+	fn walk_class_def(&mut self, stmt: &ast::ClassDef) {
+		if let Some((_, parent)) = &stmt.parent {
+			self.walk_expr(parent);
+		}
+
+		let id = self.define(&stmt.name, DefKind::Class, stmt.position());
+
+		if let Some((_, Expr::Term(Term::Ident(parent)))) = &stmt.parent {
+			let parent = self.lookup(parent.value());
+
+			if let Some(parent) = parent.filter(|&p| self.analysis.defs[p].kind == DefKind::Class) {
+				self.class_parents.insert(id, parent);
+			}
+		}
+
+		self.add_to_outline(id);
+		self.bind(stmt.name.value(), id);
+
+		let class = self.class.replace(id);
+		let parent = self.parent.replace(id);
+		self.push_scope();
+
+		for field in &stmt.fields {
+			self.walk_var_define(field, Some(id));
+		}
+
+		self.pop_scope();
+		self.parent = parent;
+		self.class = class;
+	}
+
+	fn walk_expr(&mut self, expr: &Expr) {
+		match expr {
+			Expr::Binary(expr) => {
+				self.walk_term(&expr.lhs);
+				self.walk_term(&expr.rhs);
+			}
+			Expr::Unary(expr) => self.walk_term(&expr.operand),
+			Expr::Term(term) => self.walk_term(term)
+		}
+	}
+
+	fn walk_term(&mut self, term: &Term) {
+		match term {
+			Term::Expr(expr) => self.walk_expr(expr),
+			Term::ParenExpr(expr) => self.walk_expr(&expr.expr),
+			Term::Lit(_) => (),
+			Term::InterpStr(string) => {
+				for expr in &string.exprs {
+					self.walk_expr(expr);
+				}
+			}
+			Term::Ident(ident) => self.reference(ident),
+			Term::Func(func) => self.walk_func(func),
+			Term::List(list) => {
+				for item in list.items.items() {
+					self.walk_expr(item);
+				}
+			}
+			Term::Obj(obj) => {
+				for entry in obj.fields.items() {
+					self.walk_expr(&entry.value);
+				}
+			}
+			Term::Extern(ext) => self.walk_expr(&ext.value),
+			Term::Call(call) => self.walk_call(call),
+			Term::IndexAcc(acc) => self.walk_index_acc(acc),
+			Term::FieldAcc(acc) => self.walk_field_acc(acc)
+		}
+	}
+
+	fn walk_func(&mut self, func: &ast::Func) {
+		self.push_scope();
+
+		let args = match func.args {
+			ast::FuncArgs::WithSelf { ref args, .. } => args.as_ref().map(|(_, args)| args),
+			ast::FuncArgs::WithoutSelf { ref args } => Some(args)
+		};
+
+		for arg in args.map(|a| a.items()).unwrap_or_default() {
+			let id = self.define(arg, DefKind::Parameter, arg.position());
+			self.bind(arg.value(), id);
+		}
+
+		// The function's block shares the scope with its parameters
+		self.walk_stmts(&func.block.stmts);
+		self.pop_scope();
+	}
+
+	fn walk_call(&mut self, call: &ast::Call) {
+		self.walk_term(&call.callee);
+
+		for arg in call.args.items() {
+			self.walk_expr(arg);
+		}
+	}
+
+	fn walk_index_acc(&mut self, acc: &ast::IndexAcc) {
+		self.walk_term(&acc.list);
+		self.walk_expr(&acc.index);
+	}
+
+	fn walk_field_acc(&mut self, acc: &ast::FieldAcc) {
+		let keyword = match *acc.object {
+			Term::Ident(ref ident) => Some(ident.value()),
+			_ => None
+		};
+
+		let class = match keyword {
+			Some(ast::SelfKw::KEYWORD) => self.class,
+			Some(ast::SuperKw::KEYWORD) => {
+				self.class.and_then(|c| self.class_parents.get(&c).copied())
+			}
+
+			_ => {
+				self.walk_term(&acc.object);
+				None
+			}
+		};
+
+		self.field_accesses.push(FieldAccess {
+			pos: acc.field.position(),
+			name: acc.field.value_owned(),
+			class
+		});
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn analyze(source: &str) -> Analysis {
+		let ts = prog_lexer::lex(source, "<test>").unwrap();
+		let program = prog_parser::ParseStream::new(&ts)
+			.parse::<ast::Program>()
+			.unwrap();
+
+		Analysis::new(&program)
+	}
+
+	fn offset_of(source: &str, needle: &str, nth: usize) -> usize {
+		source.match_indices(needle).nth(nth).unwrap().0
+	}
+
+	#[test]
+	fn test_scoped_definitions() {
+		let source = "def x = 1\ndo\n\tdef x = 2\n\tprint(x)\nend\nprint(x)";
+		let analysis = analyze(source);
+
+		let inner = analysis.definitions_at(offset_of(source, "x", 2));
+		let outer = analysis.definitions_at(offset_of(source, "x", 3));
+
+		assert_eq!(
+			analysis.defs[inner[0]].pos.start(),
+			offset_of(source, "def x = 2", 0)
+		);
+		assert_eq!(analysis.defs[outer[0]].pos.start(), 0);
+	}
+
+	#[test]
+	fn test_forward_references() {
+		let source =
+			"def f = func(n) do\n\treturn g(n)\nend\ndef g = func(n) do\n\treturn f(n)\nend";
+		let analysis = analyze(source);
+
+		let g = analysis.definitions_at(offset_of(source, "g(n)", 0));
+		assert_eq!(
+			analysis.defs[g[0]].name_pos.start(),
+			offset_of(source, "g = ", 0)
+		);
+
+		let f = analysis.definitions_at(offset_of(source, "f = ", 0));
+		assert_eq!(analysis.references_to(f[0]).count(), 1);
+	}
+
+	#[test]
+	fn test_class_fields() {
+		let source = "class A\n\tdef x = 1\n\tdef get = func(self) do\n\t\treturn self.x\n\tend\nend\ndef a = A()\nprint(a.x)";
+		let analysis = analyze(source);
+
+		let x = analysis.definitions_at(offset_of(source, "x", 0));
+		assert_eq!(analysis.defs[x[0]].kind, DefKind::Field);
+		assert_eq!(analysis.references_to(x[0]).count(), 2);
+
+		let class = analysis.outline[0];
+		assert_eq!(analysis.defs[class].kind, DefKind::Class);
+		assert_eq!(analysis.defs[class].children.len(), 2);
+	}
+
+	#[test]
+	fn test_inherited_fields() {
+		let source = "class A\n\tdef x = 1\nend\nclass B extends A\n\tdef get = func(self) do\n\t\treturn self.x + super.x\n\tend\nend";
+		let analysis = analyze(source);
+
+		let a = analysis.definitions_at(offset_of(source, "A", 1));
+		assert_eq!(analysis.defs[a[0]].kind, DefKind::Class);
+
+		let x = analysis.definitions_at(offset_of(source, "x", 0));
+		assert_eq!(analysis.references_to(x[0]).count(), 2);
+	}
+
+	#[test]
+	fn test_catch_variable() {
+		let source = "try
+	throw 1
+catch err
+	print(err)
+end
+print(err)";
+		let analysis = analyze(source);
+
+		let err = analysis.definitions_at(offset_of(source, "err", 1));
+		assert_eq!(analysis.defs[err[0]].kind, DefKind::Variable);
+		assert_eq!(analysis.references_to(err[0]).count(), 1);
+
+		assert!(analysis
+			.definitions_at(offset_of(source, "err", 2))
+			.is_empty());
+	}
+}
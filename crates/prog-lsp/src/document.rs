@@ -0,0 +1,175 @@
+use lsp_types::{Diagnostic, DiagnosticSeverity, Range};
+use prog_parser::{ast, ParseStream};
+use prog_utils::pretty_errors::{PrettyError, PrettyErrorKind};
+
+use crate::analysis::Analysis;
+
+/// Open text document along with the results of its analysis.
+#[derive(Debug)]
+pub struct Document {
+	text: String,
+	/// Byte offsets at which each of the lines starts.
+	line_starts: Vec<usize>,
+	pub analysis: Analysis,
+	pub diagnostics: Vec<Diagnostic>
+}
+
+impl Document {
+	pub fn new(text: String) -> Self {
+		let line_starts = std::iter::once(0)
+			.chain(text.match_indices('\n').map(|(idx, _)| idx + 1))
+			.collect();
+
+		let mut document = Self {
+			text,
+			line_starts,
+			analysis: Analysis::default(),
+			diagnostics: vec![]
+		};
+
+		document.analyze();
+		document
+	}
+
+	fn analyze(&mut self) {
+		let ts = match prog_lexer::lex(&self.text, "") {
+			Ok(ts) => ts,
+			Err(error) => {
+				self.diagnostics = vec![self.diagnostic(&error)];
+				return;
+			}
+		};
+
+		let (program, errors) = ast::Program::parse_recovering(&ParseStream::new(&ts));
+
+		self.analysis = Analysis::new(&program);
+		self.diagnostics = errors.iter().map(|e| self.diagnostic(e)).collect();
+	}
+
+	fn diagnostic<'s, K>(&self, error: &PrettyError<'s, K>) -> Diagnostic
+	where
+		K: PrettyErrorKind<'s>
+	{
+		let mut message = error.kind.message().to_owned();
+
+		if let Some(note) = error.kind.note() {
+			message = format!("{message}\n{note}");
+		}
+
+		Diagnostic {
+			range: self.range(error.span.position()),
+			severity: Some(DiagnosticSeverity::ERROR),
+			source: Some(String::from("prog")),
+			message,
+			..Default::default()
+		}
+	}
+
+	pub fn text(&self) -> &str { &self.text }
+
+	/// Converts a byte offset into a line and a UTF-16 character index.
+	pub fn position(&self, offset: usize) -> lsp_types::Position {
+		let mut offset = offset.min(self.text.len());
+
+		// Spans of invalid input may end in the middle of a character, which then counts as a whole
+		while !self.text.is_char_boundary(offset) {
+			offset -= 1;
+		}
+
+		let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+
+		let line_start = self.line_starts[line];
+		let character = self.text[line_start..offset].encode_utf16().count();
+
+		lsp_types::Position::new(line as u32, character as u32)
+	}
+
+	/// Converts a line and a UTF-16 character index into a byte offset.
+	pub fn offset(&self, position: lsp_types::Position) -> usize {
+		let Some(&line_start) = self.line_starts.get(position.line as usize) else {
+			return self.text.len();
+		};
+
+		let mut utf16_len = 0;
+
+		for (idx, c) in self.text[line_start..].char_indices() {
+			if utf16_len >= position.character as usize || c == '\n' {
+				return line_start + idx;
+			}
+
+			utf16_len += c.len_utf16();
+		}
+
+		self.text.len()
+	}
+
+	pub fn range(&self, position: prog_parser::Position) -> Range {
+		Range::new(
+			self.position(position.start()),
+			self.position(position.end())
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_position_conversion() {
+		let document = Document::new(String::from("def a = 1\ndef b = \"ü\"\nprint(a)"));
+
+		let offset = document.text().find("print").unwrap();
+		assert_eq!(document.position(offset), lsp_types::Position::new(2, 0));
+		assert_eq!(document.offset(lsp_types::Position::new(2, 0)), offset);
+
+		let offset = document.text().find('=').unwrap();
+		assert_eq!(document.position(offset), lsp_types::Position::new(0, 6));
+
+		let offset = document.text().rfind('"').unwrap();
+		assert_eq!(document.position(offset), lsp_types::Position::new(1, 10));
+		assert_eq!(document.offset(lsp_types::Position::new(1, 10)), offset);
+	}
+
+	#[test]
+	fn test_position_conversion_non_ascii() {
+		let document = Document::new(String::from("def é = \"ü\"\ndef 𝔵 = é + \"ß\""));
+
+		let offset = document.text().find('=').unwrap();
+		assert_eq!(document.position(offset), lsp_types::Position::new(0, 6));
+
+		let offset = document.text().rfind('é').unwrap();
+		assert_eq!(document.position(offset), lsp_types::Position::new(1, 9));
+		assert_eq!(document.offset(lsp_types::Position::new(1, 9)), offset);
+
+		// Characters outside of the basic plane take two UTF-16 code units
+		let offset = document.text().rfind('"').unwrap();
+		assert_eq!(document.position(offset), lsp_types::Position::new(1, 15));
+		assert_eq!(document.offset(lsp_types::Position::new(1, 15)), offset);
+	}
+
+	#[test]
+	fn test_position_inside_character() {
+		let document = Document::new(String::from("def a = \"ü\""));
+
+		// Offsets in the middle of a character are moved back to its start
+		let offset = document.text().find('ü').unwrap();
+		assert_eq!(document.position(offset + 1), document.position(offset));
+		assert_eq!(
+			document.position(offset + 1),
+			lsp_types::Position::new(0, 9)
+		);
+	}
+
+	#[test]
+	fn test_diagnostics() {
+		let document = Document::new(String::from("def a = )\nprint(a b)\nprint(a)"));
+		let lines = document
+			.diagnostics
+			.iter()
+			.map(|d| d.range.start.line)
+			.collect::<Vec<_>>();
+
+		assert_eq!(lines, [0, 1]);
+	}
+}
@@ -0,0 +1,19 @@
+mod analysis;
+mod document;
+mod server;
+
+use lsp_server::Connection;
+use server::{Server, ServerResult};
+
+fn main() -> ServerResult<()> {
+	// The standard output is used for communicating with the client
+	let (connection, io_threads) = Connection::stdio();
+
+	let capabilities = serde_json::to_value(server::capabilities())?;
+	connection.initialize(capabilities)?;
+
+	Server::new(connection).run()?;
+	io_threads.join()?;
+
+	Ok(())
+}
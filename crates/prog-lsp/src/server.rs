@@ -0,0 +1,357 @@
+use std::collections::HashMap;
+use std::error::Error;
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::notification::{
+	DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+	Notification as LspNotification, PublishDiagnostics
+};
+use lsp_types::request::{
+	DocumentSymbolRequest, GotoDefinition, HoverRequest, References, Request as LspRequest
+};
+use lsp_types::{
+	DocumentSymbol, DocumentSymbolParams, DocumentSymbolResponse, GotoDefinitionParams,
+	GotoDefinitionResponse, Hover, HoverContents, HoverParams, HoverProviderCapability, Location,
+	MarkupContent, MarkupKind, OneOf, PublishDiagnosticsParams, ReferenceParams,
+	ServerCapabilities, SymbolKind, TextDocumentPositionParams, TextDocumentSyncCapability,
+	TextDocumentSyncKind, Uri
+};
+
+use crate::analysis::{DefId, DefKind};
+use crate::document::Document;
+
+pub type ServerResult<T> = Result<T, Box<dyn Error + Sync + Send>>;
+
+pub fn capabilities() -> ServerCapabilities {
+	ServerCapabilities {
+		text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+		definition_provider: Some(OneOf::Left(true)),
+		references_provider: Some(OneOf::Left(true)),
+		hover_provider: Some(HoverProviderCapability::Simple(true)),
+		document_symbol_provider: Some(OneOf::Left(true)),
+		..Default::default()
+	}
+}
+
+pub struct Server {
+	connection: Connection,
+	documents: HashMap<Uri, Document>
+}
+
+impl Server {
+	pub fn new(connection: Connection) -> Self {
+		Self {
+			connection,
+			documents: HashMap::new()
+		}
+	}
+
+	/// Handles the incoming messages until the client requests a shutdown.
+	pub fn run(mut self) -> ServerResult<()> {
+		let receiver = self.connection.receiver.clone();
+
+		for message in &receiver {
+			match message {
+				Message::Request(request) => {
+					if self.connection.handle_shutdown(&request)? {
+						return Ok(());
+					}
+
+					let response = self.handle_request(request);
+					self.connection.sender.send(Message::Response(response))?;
+				}
+
+				Message::Notification(notification) => self.handle_notification(notification)?,
+				Message::Response(_) => ()
+			}
+		}
+
+		Ok(())
+	}
+
+	fn handle_request(&self, request: Request) -> Response {
+		match request.method.as_str() {
+			GotoDefinition::METHOD => self.respond::<GotoDefinition, _>(request, Self::definition),
+			References::METHOD => self.respond::<References, _>(request, Self::references),
+			HoverRequest::METHOD => self.respond::<HoverRequest, _>(request, Self::hover),
+			DocumentSymbolRequest::METHOD => {
+				self.respond::<DocumentSymbolRequest, _>(request, Self::document_symbols)
+			}
+
+			method => Response::new_err(
+				request.id,
+				ErrorCode::MethodNotFound as i32,
+				format!("unsupported request `{method}`")
+			)
+		}
+	}
+
+	fn respond<R, F>(&self, request: Request, handler: F) -> Response
+	where
+		R: LspRequest,
+		F: FnOnce(&Self, R::Params) -> R::Result
+	{
+		match serde_json::from_value::<R::Params>(request.params) {
+			Ok(params) => Response::new_ok(request.id, handler(self, params)),
+			Err(err) => {
+				Response::new_err(request.id, ErrorCode::InvalidParams as i32, err.to_string())
+			}
+		}
+	}
+
+	fn handle_notification(&mut self, notification: Notification) -> ServerResult<()> {
+		match notification.method.as_str() {
+			DidOpenTextDocument::METHOD => {
+				let Some(params) = Self::params::<DidOpenTextDocument>(notification) else {
+					return Ok(());
+				};
+
+				self.update(params.text_document.uri, params.text_document.text)
+			}
+
+			DidChangeTextDocument::METHOD => {
+				let Some(mut params) = Self::params::<DidChangeTextDocument>(notification) else {
+					return Ok(());
+				};
+
+				// The documents are synchronized in full, so the last change contains the whole text
+				match params.content_changes.pop() {
+					Some(change) => self.update(params.text_document.uri, change.text),
+					None => Ok(())
+				}
+			}
+
+			DidCloseTextDocument::METHOD => {
+				let Some(params) = Self::params::<DidCloseTextDocument>(notification) else {
+					return Ok(());
+				};
+
+				self.documents.remove(&params.text_document.uri);
+				self.publish_diagnostics(params.text_document.uri, vec![])
+			}
+
+			_ => Ok(())
+		}
+	}
+
+	/// Parameters of the notification, which is logged and skipped if they are malformed,
+	/// so that a single bad notification does not shut the server down.
+	fn params<N: LspNotification>(notification: Notification) -> Option<N::Params> {
+		match notification.extract::<N::Params>(N::METHOD) {
+			Ok(params) => Some(params),
+			Err(err) => {
+				eprintln!("Skipping malformed `{}` notification: {err}", N::METHOD);
+				None
+			}
+		}
+	}
+
+	fn update(&mut self, uri: Uri, text: String) -> ServerResult<()> {
+		let document = Document::new(text);
+		let diagnostics = document.diagnostics.clone();
+
+		self.documents.insert(uri.clone(), document);
+		self.publish_diagnostics(uri, diagnostics)
+	}
+
+	fn publish_diagnostics(
+		&self,
+		uri: Uri,
+		diagnostics: Vec<lsp_types::Diagnostic>
+	) -> ServerResult<()> {
+		let params = PublishDiagnosticsParams::new(uri, diagnostics, None);
+		let notification = Notification::new(PublishDiagnostics::METHOD.to_owned(), params);
+
+		self.connection
+			.sender
+			.send(Message::Notification(notification))?;
+
+		Ok(())
+	}
+
+	/// Returns the document and the definitions of the name at the position.
+	fn lookup(&self, params: &TextDocumentPositionParams) -> Option<(&Document, Vec<DefId>)> {
+		let document = self.documents.get(&params.text_document.uri)?;
+		let offset = document.offset(params.position);
+
+		Some((document, document.analysis.definitions_at(offset)))
+	}
+
+	fn definition(&self, params: GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+		let params = params.text_document_position_params;
+		let (document, defs) = self.lookup(&params)?;
+
+		let locations = defs
+			.into_iter()
+			.map(|id| {
+				let range = document.range(document.analysis.defs[id].name_pos);
+				Location::new(params.text_document.uri.clone(), range)
+			})
+			.collect::<Vec<_>>();
+
+		(!locations.is_empty()).then_some(GotoDefinitionResponse::Array(locations))
+	}
+
+	fn references(&self, params: ReferenceParams) -> Option<Vec<Location>> {
+		let include_declaration = params.context.include_declaration;
+		let params = params.text_document_position;
+		let (document, defs) = self.lookup(&params)?;
+
+		let mut positions = vec![];
+
+		for id in defs {
+			if include_declaration {
+				positions.push(document.analysis.defs[id].name_pos);
+			}
+
+			positions.extend(document.analysis.references_to(id));
+		}
+
+		let locations = positions
+			.into_iter()
+			.map(|pos| Location::new(params.text_document.uri.clone(), document.range(pos)))
+			.collect::<Vec<_>>();
+
+		(!locations.is_empty()).then_some(locations)
+	}
+
+	fn hover(&self, params: HoverParams) -> Option<Hover> {
+		let params = params.text_document_position_params;
+		let (document, defs) = self.lookup(&params)?;
+
+		let offset = document.offset(params.position);
+		let name = document.analysis.name_at(offset)?;
+
+		let contents = defs
+			.into_iter()
+			.map(|id| {
+				let def = &document.analysis.defs[id];
+				let pos = def.pos;
+
+				// Only the first line is shown, as the definitions of functions and classes span their bodies
+				let text = &document.text()[pos.start()..pos.end()];
+				let header = text.lines().next().unwrap_or_default().trim_end();
+				let line = document.position(pos.start()).line + 1;
+
+				format!(
+					"```prog\n{header}\n```\n{} defined on line {line}",
+					kind_name(def.kind)
+				)
+			})
+			.collect::<Vec<_>>();
+
+		if contents.is_empty() {
+			return None;
+		}
+
+		Some(Hover {
+			contents: HoverContents::Markup(MarkupContent {
+				kind: MarkupKind::Markdown,
+				value: contents.join("\n\n---\n\n")
+			}),
+			range: Some(document.range(name))
+		})
+	}
+
+	fn document_symbols(&self, params: DocumentSymbolParams) -> Option<DocumentSymbolResponse> {
+		let document = self.documents.get(&params.text_document.uri)?;
+
+		let symbols = document
+			.analysis
+			.outline
+			.iter()
+			.map(|&id| document_symbol(document, id))
+			.collect();
+
+		Some(DocumentSymbolResponse::Nested(symbols))
+	}
+}
+
+fn kind_name(kind: DefKind) -> &'static str {
+	match kind {
+		DefKind::Variable => "variable",
+		DefKind::Function => "function",
+		DefKind::Parameter => "parameter",
+		DefKind::Class => "class",
+		DefKind::Field => "field",
+		DefKind::Method => "method"
+	}
+}
+
+fn document_symbol(document: &Document, id: DefId) -> DocumentSymbol {
+	let def = &document.analysis.defs[id];
+
+	let kind = match def.kind {
+		DefKind::Variable | DefKind::Parameter => SymbolKind::VARIABLE,
+		DefKind::Function => SymbolKind::FUNCTION,
+		DefKind::Class => SymbolKind::CLASS,
+		DefKind::Field => SymbolKind::FIELD,
+		DefKind::Method => SymbolKind::METHOD
+	};
+
+	let children = def
+		.children
+		.iter()
+		.map(|&child| document_symbol(document, child))
+		.collect::<Vec<_>>();
+
+	#[allow(deprecated)]
+	DocumentSymbol {
+		name: def.name.clone(),
+		detail: None,
+		kind,
+		tags: None,
+		deprecated: None,
+		range: document.range(def.pos),
+		selection_range: document.range(def.name_pos),
+		children: (!children.is_empty()).then_some(children)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use lsp_server::RequestId;
+	use serde_json::json;
+
+	use super::*;
+
+	#[test]
+	fn test_malformed_notifications() {
+		let (server, client) = Connection::memory();
+		let handle = std::thread::spawn(move || Server::new(server).run());
+
+		let notify = |method: &str, params| {
+			let notification = Notification::new(method.to_owned(), params);
+			client
+				.sender
+				.send(Message::Notification(notification))
+				.unwrap();
+		};
+
+		// The malformed notification is skipped, and the server keeps handling the next ones
+		notify(DidOpenTextDocument::METHOD, json!({ "textDocument": 5 }));
+		notify(
+			DidOpenTextDocument::METHOD,
+			json!({
+				"textDocument": {
+					"uri": "file:///main.prog",
+					"languageId": "prog",
+					"version": 1,
+					"text": "def a = )"
+				}
+			})
+		);
+
+		let Message::Notification(published) = client.receiver.recv().unwrap() else {
+			panic!("Expected the diagnostics to be published");
+		};
+		assert_eq!(published.method, PublishDiagnostics::METHOD);
+
+		let shutdown = Request::new(RequestId::from(1), String::from("shutdown"), json!(null));
+		client.sender.send(Message::Request(shutdown)).unwrap();
+		client.receiver.recv().unwrap();
+		notify("exit", json!(null));
+
+		assert!(handle.join().unwrap().is_ok());
+	}
+}
@@ -1,6 +1,6 @@
 use proc_macro2::{Ident, TokenStream};
 use quote::quote;
-use syn::{Generics, Result, Variant};
+use syn::{Fields, Generics, ItemStruct, Result, Variant};
 
 use super::utils::{expand_destructure_pattern, expand_fields_type};
 
@@ -37,3 +37,88 @@ pub(crate) fn expand_variant(
 		}
 	})
 }
+
+/// Expands to impl of `prog_interpreter::FromValue` for a struct with named fields
+pub(crate) fn expand_from_value(item: ItemStruct) -> Result<TokenStream> {
+	let struct_name = &item.ident;
+	let field_names = named_fields(&item)?;
+	let field_strs = field_names.iter().map(|f| f.to_string());
+
+	let generics = with_value_lifetime(&item.generics, quote!(::prog_interpreter::FromValue));
+	let (impl_generics, _, where_clause) = generics.split_for_impl();
+	let (_, type_generics, _) = item.generics.split_for_impl();
+
+	Ok(quote! {
+		impl #impl_generics ::prog_interpreter::FromValue<'__i> for #struct_name #type_generics #where_clause {
+			fn from_value(
+				value: ::prog_interpreter::Value<'__i>
+			) -> ::std::result::Result<Self, ::prog_interpreter::error::ConversionFailed> {
+				let fields = ::prog_interpreter::value::Fields::new(value)?;
+
+				::std::result::Result::Ok(Self {
+					#( #field_names: fields.get(#field_strs)? ),*
+				})
+			}
+		}
+	})
+}
+
+/// Expands to impl of `prog_interpreter::IntoValue` for a struct with named fields
+pub(crate) fn expand_into_value(item: ItemStruct) -> Result<TokenStream> {
+	let struct_name = &item.ident;
+	let field_names = named_fields(&item)?;
+	let field_strs = field_names.iter().map(|f| f.to_string());
+
+	let generics = with_value_lifetime(&item.generics, quote!(::prog_interpreter::IntoValue));
+	let (impl_generics, _, where_clause) = generics.split_for_impl();
+	let (_, type_generics, _) = item.generics.split_for_impl();
+
+	Ok(quote! {
+		impl #impl_generics ::prog_interpreter::IntoValue<'__i> for #struct_name #type_generics #where_clause {
+			fn into_value(self) -> ::prog_interpreter::Value<'__i> {
+				let obj = ::prog_interpreter::value::Obj::default();
+
+				#(
+					obj.insert(#field_strs, ::prog_interpreter::IntoValue::into_value(self.#field_names));
+				)*
+
+				::prog_interpreter::Value::Obj(obj)
+			}
+		}
+	})
+}
+
+fn named_fields(item: &ItemStruct) -> Result<Vec<&Ident>> {
+	let Fields::Named(ref fields) = item.fields else {
+		return Err(syn::Error::new_spanned(
+			&item.fields,
+			"Derive of this macro is only allowed for structs with named fields"
+		));
+	};
+
+	Ok(fields
+		.named
+		.iter()
+		.filter_map(|f| f.ident.as_ref())
+		.collect())
+}
+
+/// Adds the value lifetime `'__i` to `generics`, bounding every type parameter by `conversion_trait<'__i>`
+fn with_value_lifetime(generics: &Generics, conversion_trait: TokenStream) -> Generics {
+	let mut generics = generics.clone();
+
+	let type_params = generics
+		.type_params()
+		.map(|p| p.ident.clone())
+		.collect::<Vec<_>>();
+
+	let where_clause = generics.make_where_clause();
+	for param in type_params {
+		where_clause
+			.predicates
+			.push(syn::parse_quote!(#param: #conversion_trait<'__i>));
+	}
+
+	generics.params.insert(0, syn::parse_quote!('__i));
+	generics
+}
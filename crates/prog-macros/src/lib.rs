@@ -35,6 +35,31 @@ pub fn variant_unwrap(input: pm::TokenStream) -> pm::TokenStream {
 	.into()
 }
 
+/// Implements `prog_interpreter::FromValue` for the deriving struct, converting it from an `Obj` or a `ClassInstance`.
+/// **Only compatible with structs with named fields**
+#[proc_macro_derive(FromValue)]
+pub fn from_value(input: pm::TokenStream) -> pm::TokenStream {
+	let item = parse_macro_input!(input as syn::ItemStruct);
+
+	match conversion_inner::expand_from_value(item) {
+		Ok(ts) => ts.into(),
+		Err(e) => e.to_compile_error().into()
+	}
+}
+
+/// Implements `prog_interpreter::IntoValue` for the deriving struct, converting it into an `Obj`.
+/// The struct is never converted into a `ClassInstance`, even if it was converted from one.
+/// **Only compatible with structs with named fields**
+#[proc_macro_derive(IntoValue)]
+pub fn into_value(input: pm::TokenStream) -> pm::TokenStream {
+	let item = parse_macro_input!(input as syn::ItemStruct);
+
+	match conversion_inner::expand_into_value(item) {
+		Ok(ts) => ts.into(),
+		Err(e) => e.to_compile_error().into()
+	}
+}
+
 /// Expands to `enum <Enum>Kind` and implements `.kind()` for the deriving enum. **Only compatible with enums**
 #[proc_macro_derive(EnumKind)]
 pub fn enum_kind(input: pm::TokenStream) -> pm::TokenStream {
@@ -75,7 +100,14 @@ pub fn enum_kind(input: pm::TokenStream) -> pm::TokenStream {
 
 	// Expanding kind enum
 	let kind_enum = quote! {
-		#[derive(::core::fmt::Debug, ::core::clone::Clone, ::core::marker::Copy, ::core::cmp::PartialEq)]
+		#[derive(
+			::core::fmt::Debug,
+			::core::clone::Clone,
+			::core::marker::Copy,
+			::core::cmp::PartialEq,
+			::core::cmp::Eq,
+			::core::hash::Hash
+		)]
 		#[cfg_attr(feature = "serde", derive(::serde::Serialize))]
 		#enum_vis enum #enum_kind_name {
 			#( #kind_variants ),*
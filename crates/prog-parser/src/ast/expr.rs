@@ -39,16 +39,16 @@ impl<'src> ParsePrecedence<'src> for Expr<'src> {
 
 		while let Some(token) = input.peek() {
 			let infix_binding_power = match token.kind() {
-				T::Plus | T::Minus => (1, 2),
+				T::Or => (1, 2),
+				T::And => (3, 4),
 
-				T::Asterisk | T::Slash | T::Sign => (3, 4),
+				T::EqEq | T::Neq | T::Gt | T::Lt | T::Gte | T::Lte => (5, 6),
 
-				T::Dot | T::LeftBracket => (5, 6),
+				T::Plus | T::Minus => (7, 8),
 
-				T::EqEq | T::Neq | T::Gt | T::Lt | T::Gte | T::Lte => (1, 2),
+				T::Asterisk | T::Slash | T::Sign => (9, 10),
 
-				T::And => (3, 2),
-				T::Or => (1, 1),
+				T::Dot | T::LeftBracket => (11, 12),
 
 				_ => break
 			};
@@ -35,7 +35,7 @@ impl FuncArgs<'_> {
 		}
 	}
 
-	pub fn items(&self) -> Vec<&dyn ASTNode> {
+	pub fn items(&self) -> Vec<&dyn ASTNode<'_>> {
 		let mut items = vec![];
 
 		match self {
@@ -0,0 +1,71 @@
+use prog_lexer::TokenKind;
+
+use crate::ast::*;
+use crate::{token, ASTNode, Parse, ParseResult, ParseStream, Position, Span};
+
+/// String literal with embedded expressions, such as `"value = {x + 1}"`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct InterpStr<'src> {
+	/// Text surrounding the embedded expressions, there is always one more segment than there are expressions.
+	pub segments: Vec<StrSegment<'src>>,
+	pub exprs: Vec<Expr<'src>>
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct StrSegment<'src> {
+	/// Decoded text of the segment.
+	pub value: String,
+	pub span: Span<'src>
+}
+
+impl<'src> StrSegment<'src> {
+	fn new(token: &dyn ASTNode<'src>) -> Self {
+		Self {
+			value: prog_lexer::string_value(token.value()),
+			span: token.span()
+		}
+	}
+}
+
+impl<'src> ASTNode<'src> for InterpStr<'src> {
+	fn span<'a>(&'a self) -> Span<'src> {
+		let first = self.segments.first().unwrap().span;
+		let last = self.segments.last().unwrap().span;
+
+		let position = Position::new(first.position().start(), last.position().end());
+		Span::new(first.source(), first.file(), position)
+	}
+}
+
+impl<'src> ASTNode<'src> for StrSegment<'src> {
+	fn span<'a>(&'a self) -> Span<'src> { self.span }
+}
+
+impl<'src> Parse<'src> for InterpStr<'src> {
+	fn parse(input: &ParseStream<'src, '_>) -> ParseResult<'src, Self> {
+		let start = input.parse::<token::StringStart>()?;
+
+		let mut segments = vec![StrSegment::new(&start)];
+		let mut exprs = vec![];
+
+		loop {
+			exprs.push(input.parse::<Expr>()?);
+
+			if input.peek_matches(TokenKind::StringMiddle).is_some() {
+				let middle = input.parse::<token::StringMiddle>()?;
+				segments.push(StrSegment::new(&middle));
+
+				continue;
+			}
+
+			let end = input.parse::<token::StringEnd>()?;
+			segments.push(StrSegment::new(&end));
+
+			break;
+		}
+
+		Ok(Self { segments, exprs })
+	}
+}
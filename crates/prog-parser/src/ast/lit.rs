@@ -20,10 +20,6 @@ pub enum LitKind {
 	None
 }
 
-impl Lit<'_> {
-	pub fn strip_quotes(str: &str) -> &str { str.trim_start_matches('\"').trim_end_matches('\"') }
-}
-
 impl<'src> ASTNode<'src> for Lit<'src> {
 	fn span<'a>(&'a self) -> Span<'src> { self.span }
 }
@@ -58,7 +54,7 @@ impl<'src> Parse<'src> for Lit<'src> {
 			}
 
 			TokenKind::String => {
-				let str = Self::strip_quotes(token.value()).to_owned();
+				let str = prog_lexer::string_value(token.value());
 
 				Ok(Self {
 					kind: LitKind::Str(str),
@@ -6,6 +6,7 @@ mod field_acc;
 mod func;
 mod ident;
 mod index_acc;
+mod interp_str;
 mod list;
 mod lit;
 mod obj;
@@ -13,6 +14,7 @@ mod punctuated;
 mod self_kw;
 mod term;
 mod stmts;
+mod super_kw;
 mod unary_expr;
 
 pub use binary_expr::*;
@@ -23,18 +25,20 @@ pub use field_acc::*;
 pub use func::*;
 pub use ident::*;
 pub use index_acc::*;
+pub use interp_str::*;
 pub use list::*;
 pub use lit::*;
 pub use obj::*;
 pub use punctuated::*;
 pub use self_kw::SelfKw;
 pub use stmts::*;
+pub use super_kw::SuperKw;
 pub use term::*;
 pub use unary_expr::*;
 
 use std::rc::Rc;
 
-use crate::{ASTNode, Parse, ParseResult, ParseStream, Position, Span};
+use crate::{ASTNode, Parse, ParseError, ParseResult, ParseStream, Position, Span};
 
 macro_rules! op_to_token {
 	($op:ident : $kind:ident => $token:ident) => {
@@ -88,18 +92,36 @@ impl<'src> ASTNode<'src> for Program<'src> {
 	}
 }
 
-impl<'src> Parse<'src> for Program<'src> {
-	fn parse(input: &ParseStream<'src, '_>) -> ParseResult<'src, Self> {
+impl<'src> Program<'src> {
+	/// Parses the whole program, recovering from the errors at statement boundaries.
+	///
+	/// Returns the partial program, in which the invalid statements are replaced with [`Stmt::Error`],
+	/// along with all of the errors that have been encountered.
+	pub fn parse_recovering(input: &ParseStream<'src, '_>) -> (Self, Vec<ParseError<'src>>) {
+		let reported = input.errors().len();
 		let mut stmts = vec![];
 
 		while input.peek().is_some() {
-			let stmt = input.parse::<Stmt>()?;
-			stmts.push(stmt);
+			stmts.push(Stmt::parse_recovering(input));
 		}
 
-		Ok(Self {
+		let program = Self {
 			stmts: stmts.into()
-		})
+		};
+
+		(program, input.errors().split_off(reported))
+	}
+}
+
+impl<'src> Parse<'src> for Program<'src> {
+	/// Fails with the first error of the program, see [`Program::parse_recovering`] for getting all of them.
+	fn parse(input: &ParseStream<'src, '_>) -> ParseResult<'src, Self> {
+		let (program, errors) = Self::parse_recovering(input);
+
+		match errors.into_iter().next() {
+			Some(error) => Err(error),
+			None => Ok(program)
+		}
 	}
 }
 
@@ -1,3 +1,5 @@
+use prog_lexer::TokenKind;
+
 use crate::ast::*;
 use crate::{token, ASTNode, Parse, ParseResult, ParseStream, Position, Span};
 
@@ -6,6 +8,7 @@ use crate::{token, ASTNode, Parse, ParseResult, ParseStream, Position, Span};
 pub struct ClassDef<'src> {
 	pub _class: token::Class<'src>,
 	pub name: Ident<'src>,
+	pub parent: Option<(token::Extends<'src>, Expr<'src>)>,
 	pub fields: Vec<VarDefine<'src>>,
 	pub _end: token::End<'src>
 }
@@ -27,17 +30,19 @@ impl<'src> Parse<'src> for ClassDef<'src> {
 	fn parse(input: &ParseStream<'src, '_>) -> ParseResult<'src, Self> {
 		let _class = input.parse::<token::Class>()?;
 		let name = input.parse::<Ident>()?;
-		let mut fields = vec![];
 
-		while let Ok(f) = input.try_parse::<VarDefine>() {
-			fields.push(f);
-		}
+		let parent = match input.peek_matches(TokenKind::Extends) {
+			Some(_) => Some((input.parse::<token::Extends>()?, input.parse::<Expr>()?)),
+			None => None
+		};
 
+		let fields = super::parse_block_of::<VarDefine>(input);
 		let _end = input.parse::<token::End>()?;
 
 		Ok(Self {
 			_class,
 			name,
+			parent,
 			fields,
 			_end
 		})
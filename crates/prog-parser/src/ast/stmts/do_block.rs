@@ -27,10 +27,7 @@ impl<'src> Parse<'src> for DoBlock<'src> {
 	fn parse(input: &ParseStream<'src, '_>) -> ParseResult<'src, Self> {
 		let _do = input.parse::<token::Do>()?;
 
-		let mut stmts = vec![];
-		while let Ok(stmt) = input.try_parse::<Stmt>() {
-			stmts.push(stmt);
-		}
+		let stmts = Stmt::parse_block(input);
 
 		let _end = input.parse::<token::End>()?;
 
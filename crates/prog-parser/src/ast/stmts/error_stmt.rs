@@ -0,0 +1,21 @@
+use crate::{ASTNode, ParseError, Span};
+
+/// Statement which could not be parsed.
+///
+/// Spans all of the tokens which have been skipped while recovering from the `error`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ErrorStmt<'src> {
+	pub span: Span<'src>,
+	pub error: ParseError<'src>
+}
+
+impl<'src> ASTNode<'src> for ErrorStmt<'src> {
+	fn span<'a>(&'a self) -> Span<'src> { self.span }
+}
+
+impl PartialEq for ErrorStmt<'_> {
+	fn eq(&self, other: &Self) -> bool {
+		self.span == other.span && self.error.span == other.error.span
+	}
+}
@@ -0,0 +1,55 @@
+use prog_lexer::TokenKind;
+
+use crate::ast::*;
+use crate::{token, ASTNode, Parse, ParseResult, ParseStream, Position, Span};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ForLoop<'src> {
+	pub _for: token::For<'src>,
+	/// Item of a sequence or key of an object, or the index or key when `value` is present.
+	pub var: Ident<'src>,
+	/// Item of a sequence or value of an object in `for index, item in ...`.
+	pub value: Option<(token::Comma<'src>, Ident<'src>)>,
+	pub _in: token::In<'src>,
+	pub iter: Expr<'src>,
+	pub block: DoBlock<'src>
+}
+
+impl<'src> ASTNode<'src> for ForLoop<'src> {
+	fn span<'a>(&'a self) -> Span<'src> {
+		let start = self._for.start();
+		let end = self.block.end();
+
+		let source = self._for.source();
+		let file = self._for.file();
+		let position = Position::new(start, end);
+
+		Span::new(source, file, position)
+	}
+}
+
+impl<'src> Parse<'src> for ForLoop<'src> {
+	fn parse(input: &ParseStream<'src, '_>) -> ParseResult<'src, Self> {
+		let _for = input.parse::<token::For>()?;
+		let var = input.parse::<Ident>()?;
+
+		let value = match input.peek_matches(TokenKind::Comma) {
+			Some(_) => Some((input.parse::<token::Comma>()?, input.parse::<Ident>()?)),
+			None => None
+		};
+
+		let _in = input.parse::<token::In>()?;
+		let iter = input.parse::<Expr>()?;
+		let block = input.parse::<DoBlock>()?;
+
+		Ok(Self {
+			_for,
+			var,
+			value,
+			_in,
+			iter,
+			block
+		})
+	}
+}
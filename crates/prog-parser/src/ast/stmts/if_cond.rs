@@ -78,11 +78,7 @@ impl<'src> Parse<'src> for If<'src> {
 		let _if = input.parse::<token::If>()?;
 		let cond = input.parse::<Expr>()?;
 		let _then = input.parse::<token::Then>()?;
-		let mut stmts = vec![];
-
-		while let Ok(stmt) = input.try_parse::<Stmt>() {
-			stmts.push(stmt);
-		}
+		let stmts = Stmt::parse_block(input);
 
 		if let Ok(_end) = input.try_parse::<token::End>() {
 			return Ok(Self {
@@ -126,11 +122,7 @@ impl<'src> Parse<'src> for ElseIf<'src> {
 		let _elseif = input.parse::<token::ElseIf>()?;
 		let cond = input.parse::<Expr>()?;
 		let _then = input.parse::<token::Then>()?;
-		let mut stmts = vec![];
-
-		while let Ok(stmt) = input.try_parse::<Stmt>() {
-			stmts.push(stmt);
-		}
+		let stmts = Stmt::parse_block(input);
 
 		Ok(Self {
 			_elseif,
@@ -144,11 +136,7 @@ impl<'src> Parse<'src> for ElseIf<'src> {
 impl<'src> Parse<'src> for Else<'src> {
 	fn parse(input: &ParseStream<'src, '_>) -> ParseResult<'src, Self> {
 		let _else = input.parse::<token::Else>()?;
-		let mut stmts = vec![];
-
-		while let Ok(stmt) = input.try_parse::<Stmt>() {
-			stmts.push(stmt);
-		}
+		let stmts = Stmt::parse_block(input);
 
 		Ok(Self {
 			_else,
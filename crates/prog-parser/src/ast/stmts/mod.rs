@@ -3,17 +3,25 @@ mod var_assign;
 mod do_block;
 mod ret;
 mod while_loop;
+mod for_loop;
 mod control_flow;
 mod if_cond;
+mod throw;
+mod try_catch;
 mod expr_assign;
 mod class_def;
+mod error_stmt;
 
 pub use class_def::ClassDef;
 pub use control_flow::{Break, Continue};
 pub use do_block::DoBlock;
+pub use error_stmt::ErrorStmt;
 pub use expr_assign::{ExprAssign, FieldAssign, IndexAssign};
+pub use for_loop::ForLoop;
 pub use if_cond::{Else, ElseIf, If};
 pub use ret::Return;
+pub use throw::Throw;
+pub use try_catch::{Catch, Finally, Try};
 pub use var_assign::VarAssign;
 pub use var_def::VarDefine;
 pub use while_loop::WhileLoop;
@@ -21,7 +29,8 @@ pub use while_loop::WhileLoop;
 use prog_lexer::TokenKind;
 
 use crate::{
-	ast, error, ASTNode, Parse, ParseError, ParseErrorKind, ParseResult, ParseStream, Span
+	ast, error, ASTNode, Parse, ParseError, ParseErrorKind, ParseResult, ParseStream, Position,
+	Span
 };
 
 #[derive(Debug, Clone, PartialEq)]
@@ -33,11 +42,15 @@ pub enum Stmt<'src> {
 	Return(Return<'src>),
 	Call(ast::Call<'src>),
 	WhileLoop(WhileLoop<'src>),
+	ForLoop(ForLoop<'src>),
 	Break(Break<'src>),
 	Continue(Continue<'src>),
 	If(If<'src>),
+	Throw(Throw<'src>),
+	Try(Try<'src>),
 	ExprAssign(ExprAssign<'src>),
-	ClassDef(ClassDef<'src>)
+	ClassDef(ClassDef<'src>),
+	Error(ErrorStmt<'src>)
 }
 
 impl<'src> ASTNode<'src> for Stmt<'src> {
@@ -49,16 +62,148 @@ impl<'src> ASTNode<'src> for Stmt<'src> {
 			Self::Return(s) => s as &dyn ASTNode,
 			Self::Call(s) => s as &dyn ASTNode,
 			Self::WhileLoop(s) => s as &dyn ASTNode,
+			Self::ForLoop(s) => s as &dyn ASTNode,
 			Self::Break(s) => s as &dyn ASTNode,
 			Self::Continue(s) => s as &dyn ASTNode,
 			Self::If(s) => s as &dyn ASTNode,
+			Self::Throw(s) => s as &dyn ASTNode,
+			Self::Try(s) => s as &dyn ASTNode,
 			Self::ExprAssign(s) => s as &dyn ASTNode,
-			Self::ClassDef(s) => s as &dyn ASTNode
+			Self::ClassDef(s) => s as &dyn ASTNode,
+			Self::Error(s) => s as &dyn ASTNode
 		}
 		.span()
 	}
 }
 
+impl<'src> Stmt<'src> {
+	/// Parses the statements of a block up until the token which terminates it (`end`, `else`, `elseif`, `catch`, or `finally`),
+	/// recovering from the invalid ones.
+	pub(crate) fn parse_block(input: &ParseStream<'src, '_>) -> Vec<Self> {
+		let mut stmts = vec![];
+
+		while input.peek().is_some_and(|t| !is_block_end(t.kind())) {
+			stmts.push(Self::parse_recovering(input));
+		}
+
+		stmts
+	}
+
+	/// Parses a statement, or, if it is invalid, reports the error and skips the tokens
+	/// up until the next statement boundary past the error, producing a [`Stmt::Error`] in its place.
+	///
+	/// Statement boundaries are the keywords which start or terminate statements, as well as line breaks,
+	/// outside of the blocks opened by the skipped tokens.
+	///
+	/// # Panics
+	///
+	/// Panics if the stream has no tokens left.
+	pub(crate) fn parse_recovering(input: &ParseStream<'src, '_>) -> Self {
+		recover(input).unwrap_or_else(Self::Error)
+	}
+}
+
+/// Parses the nodes of a block up until the token which terminates it, like [`Stmt::parse_block`],
+/// reporting and leaving out the invalid ones.
+pub(crate) fn parse_block_of<'src, T>(input: &ParseStream<'src, '_>) -> Vec<T>
+where
+	T: Parse<'src>
+{
+	let mut nodes = vec![];
+
+	while input.peek().is_some_and(|t| !is_block_end(t.kind())) {
+		if let Ok(node) = recover(input) {
+			nodes.push(node);
+		}
+	}
+
+	nodes
+}
+
+/// Parses a node, or reports the error and skips the tokens of the invalid node, see [`Stmt::parse_recovering`].
+#[allow(clippy::result_large_err)]
+fn recover<'src, T>(input: &ParseStream<'src, '_>) -> Result<T, ErrorStmt<'src>>
+where
+	T: Parse<'src>
+{
+	let fork = input.fork();
+
+	// The errors recovered from inside of an invalid node are kept,
+	// since its tokens are skipped below instead of being parsed again
+	let error = match fork.parse::<T>() {
+		Ok(node) => {
+			input.set_cursor(fork.cursor());
+			return Ok(node);
+		}
+		Err(error) => error
+	};
+
+	input.report(error.clone());
+
+	// Skipping at least one token so that the parser does not get stuck on it
+	let first = input.next().expect("no tokens left to recover from");
+	let mut last = first;
+	let mut depth = block_depth(0, first.kind());
+
+	while let Some(token) = input.peek() {
+		let past_error = token.position().start() >= error.span.position().start();
+		let gap = &token.span().source()[last.position().end()..token.position().start()];
+		let starts_line = gap.contains('\n');
+
+		if past_error && depth == 0 && (starts_line || is_stmt_boundary(token.kind())) {
+			break;
+		}
+
+		last = token;
+		input.next();
+		depth = block_depth(depth, token.kind());
+	}
+
+	let position = Position::new(first.position().start(), last.position().end());
+	let span = Span::new(first.span().source(), first.span().file(), position);
+
+	Err(ErrorStmt { span, error })
+}
+
+/// Depth of the blocks opened by the skipped tokens, after skipping the token of the given kind.
+///
+/// Resuming inside of such a block would report its `end` as a statement on its own.
+fn block_depth(depth: usize, kind: TokenKind) -> usize {
+	match kind {
+		TokenKind::Do | TokenKind::If | TokenKind::Class | TokenKind::Try => depth + 1,
+		TokenKind::End => depth.saturating_sub(1),
+		_ => depth
+	}
+}
+
+/// Whether the token terminates the statements of a block.
+fn is_block_end(kind: TokenKind) -> bool {
+	matches!(
+		kind,
+		TokenKind::End
+			| TokenKind::Else
+			| TokenKind::ElseIf
+			| TokenKind::Catch
+			| TokenKind::Finally
+	)
+}
+
+/// Whether the parser can resume parsing at the token after encountering an error.
+fn is_stmt_boundary(kind: TokenKind) -> bool {
+	use TokenKind as T;
+
+	is_block_end(kind)
+		|| matches!(
+			kind,
+			T::Def
+				| T::Do | T::Return
+				| T::Break | T::Continue
+				| T::While | T::For
+				| T::If | T::Class
+				| T::Throw | T::Try
+		)
+}
+
 impl<'src> Parse<'src> for Stmt<'src> {
 	fn parse(input: &ParseStream<'src, '_>) -> ParseResult<'src, Self> {
 		let token = input.expect_peek()?;
@@ -94,11 +239,26 @@ impl<'src> Parse<'src> for Stmt<'src> {
 			return input.parse::<WhileLoop>().map(Self::WhileLoop);
 		}
 
+		// `for ...`
+		if input.peek_matches(TokenKind::For).is_some() {
+			return input.parse::<ForLoop>().map(Self::ForLoop);
+		}
+
 		// `if ...`
 		if input.peek_matches(TokenKind::If).is_some() {
 			return input.parse::<If>().map(Self::If);
 		}
 
+		// `throw ...`
+		if input.peek_matches(TokenKind::Throw).is_some() {
+			return input.parse::<Throw>().map(Self::Throw);
+		}
+
+		// `try ...`
+		if input.peek_matches(TokenKind::Try).is_some() {
+			return input.parse::<Try>().map(Self::Try);
+		}
+
 		// `class ...`
 		if input.peek_matches(TokenKind::Class).is_some() {
 			return input.parse::<ClassDef>().map(Self::ClassDef);
@@ -126,7 +286,10 @@ impl<'src> Parse<'src> for Stmt<'src> {
 
 		Err(ParseError::new(
 			span,
-			ParseErrorKind::Internal(error::Internal(String::from("no statement matched")))
+			ParseErrorKind::UnexpectedToken(error::UnexpectedToken {
+				found: token.kind(),
+				expected: None
+			})
 		))
 	}
 }
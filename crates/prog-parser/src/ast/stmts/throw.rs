@@ -0,0 +1,31 @@
+use crate::ast::*;
+use crate::{token, ASTNode, Parse, ParseResult, ParseStream, Position, Span};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Throw<'src> {
+	pub _throw: token::Throw<'src>,
+	pub value: Expr<'src>
+}
+
+impl<'src> ASTNode<'src> for Throw<'src> {
+	fn span<'a>(&'a self) -> Span<'src> {
+		let start = self._throw.start();
+		let end = self.value.end();
+
+		let source = self._throw.source();
+		let file = self._throw.file();
+		let position = Position::new(start, end);
+
+		Span::new(source, file, position)
+	}
+}
+
+impl<'src> Parse<'src> for Throw<'src> {
+	fn parse(input: &ParseStream<'src, '_>) -> ParseResult<'src, Self> {
+		let _throw = input.parse::<token::Throw>()?;
+		let value = input.parse::<Expr>()?;
+
+		Ok(Self { _throw, value })
+	}
+}
@@ -0,0 +1,181 @@
+use prog_lexer::TokenKind;
+
+use crate::ast::*;
+use crate::{token, ASTNode, Parse, ParseResult, ParseStream, Position, Span};
+
+use std::rc::Rc;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Try<'src> {
+	pub _try: token::Try<'src>,
+	pub stmts: Rc<[Stmt<'src>]>,
+	pub b_catch: Option<Catch<'src>>,
+	pub b_finally: Option<Finally<'src>>,
+	pub _end: token::End<'src>
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Catch<'src> {
+	pub _catch: token::Catch<'src>,
+	/// Variable which the thrown value, or the error object, is bound to.
+	pub name: Ident<'src>,
+	pub stmts: Rc<[Stmt<'src>]>
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finally<'src> {
+	pub _finally: token::Finally<'src>,
+	pub stmts: Rc<[Stmt<'src>]>
+}
+
+impl<'src> ASTNode<'src> for Try<'src> {
+	fn span<'a>(&'a self) -> Span<'src> {
+		let start = self._try.start();
+		let end = self._end.end();
+
+		let source = self._try.source();
+		let file = self._try.file();
+		let position = Position::new(start, end);
+
+		Span::new(source, file, position)
+	}
+}
+
+impl<'src> ASTNode<'src> for Catch<'src> {
+	fn span<'a>(&'a self) -> Span<'src> {
+		let start = self._catch.start();
+		let end = match self.stmts.last() {
+			Some(stmt) => stmt.end(),
+			None => self.name.end()
+		};
+
+		let source = self._catch.source();
+		let file = self._catch.file();
+		let position = Position::new(start, end);
+
+		Span::new(source, file, position)
+	}
+}
+
+impl<'src> ASTNode<'src> for Finally<'src> {
+	fn span<'a>(&'a self) -> Span<'src> {
+		let start = self._finally.start();
+		let end = match self.stmts.last() {
+			Some(stmt) => stmt.end(),
+			None => self._finally.end()
+		};
+
+		let source = self._finally.source();
+		let file = self._finally.file();
+		let position = Position::new(start, end);
+
+		Span::new(source, file, position)
+	}
+}
+
+impl<'src> Parse<'src> for Try<'src> {
+	fn parse(input: &ParseStream<'src, '_>) -> ParseResult<'src, Self> {
+		let _try = input.parse::<token::Try>()?;
+		let stmts = Stmt::parse_block(input);
+
+		let b_catch = match input.peek_matches(TokenKind::Catch) {
+			Some(_) => Some(input.parse::<Catch>()?),
+			None => None
+		};
+
+		let b_finally = match input.peek_matches(TokenKind::Finally) {
+			Some(_) => Some(input.parse::<Finally>()?),
+			None => None
+		};
+
+		// A `try` without any handler is rejected, reporting the missing `catch`
+		if b_catch.is_none() && b_finally.is_none() {
+			input.parse::<token::Catch>()?;
+		}
+
+		let _end = input.parse::<token::End>()?;
+
+		Ok(Self {
+			_try,
+			stmts: stmts.into(),
+			b_catch,
+			b_finally,
+			_end
+		})
+	}
+}
+
+impl<'src> Parse<'src> for Catch<'src> {
+	fn parse(input: &ParseStream<'src, '_>) -> ParseResult<'src, Self> {
+		let _catch = input.parse::<token::Catch>()?;
+		let name = input.parse::<Ident>()?;
+		let stmts = Stmt::parse_block(input);
+
+		Ok(Self {
+			_catch,
+			name,
+			stmts: stmts.into()
+		})
+	}
+}
+
+impl<'src> Parse<'src> for Finally<'src> {
+	fn parse(input: &ParseStream<'src, '_>) -> ParseResult<'src, Self> {
+		let _finally = input.parse::<token::Finally>()?;
+		let stmts = Stmt::parse_block(input);
+
+		Ok(Self {
+			_finally,
+			stmts: stmts.into()
+		})
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Try<'_> {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: serde::Serializer
+	{
+		use serde::ser::SerializeStruct;
+
+		let mut s = serializer.serialize_struct("Try", 5)?;
+		s.serialize_field("_try", &self._try)?;
+		s.serialize_field("stmts", &*self.stmts)?;
+		s.serialize_field("b_catch", &self.b_catch)?;
+		s.serialize_field("b_finally", &self.b_finally)?;
+		s.serialize_field("_end", &self._end)?;
+		s.end()
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Catch<'_> {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: serde::Serializer
+	{
+		use serde::ser::SerializeStruct;
+
+		let mut s = serializer.serialize_struct("Catch", 3)?;
+		s.serialize_field("_catch", &self._catch)?;
+		s.serialize_field("name", &self.name)?;
+		s.serialize_field("stmts", &*self.stmts)?;
+		s.end()
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Finally<'_> {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: serde::Serializer
+	{
+		use serde::ser::SerializeStruct;
+
+		let mut s = serializer.serialize_struct("Finally", 2)?;
+		s.serialize_field("_finally", &self._finally)?;
+		s.serialize_field("stmts", &*self.stmts)?;
+		s.end()
+	}
+}
@@ -3,6 +3,7 @@ use crate::{token, ASTNode, Parse, ParseResult, ParseStream, Position, Span};
 
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[allow(clippy::large_enum_variant)]
 pub enum VarDefine<'src> {
 	WithValue {
 		_def: token::Def<'src>,
@@ -0,0 +1,9 @@
+/// Keyword which refers to the parent class inside of a class and its methods.
+///
+/// Unlike [`SelfKw`](crate::ast::SelfKw), it never appears in the arguments of a function,
+/// so it is parsed as a regular identifier and only its name is needed.
+pub struct SuperKw;
+
+impl SuperKw {
+	pub const KEYWORD: &'static str = "super";
+}
@@ -12,6 +12,7 @@ pub enum Term<'src> {
 
 	// Regular terms
 	Lit(Lit<'src>),
+	InterpStr(InterpStr<'src>),
 	Ident(Ident<'src>),
 	Func(Box<Func<'src>>),
 	List(List<'src>),
@@ -50,6 +51,7 @@ impl<'src> ASTNode<'src> for Term<'src> {
 			Self::Expr(t) => t.as_ref() as &dyn ASTNode,
 			Self::ParenExpr(t) => t as &dyn ASTNode,
 			Self::Lit(t) => t as &dyn ASTNode,
+			Self::InterpStr(t) => t as &dyn ASTNode,
 			Self::Ident(t) => t as &dyn ASTNode,
 			Self::Func(t) => t.as_ref() as &dyn ASTNode,
 			Self::List(t) => t as &dyn ASTNode,
@@ -76,6 +78,8 @@ impl<'src> Parse<'src> for Term<'src> {
 				input.parse::<Lit>().map(Self::Lit)?
 			}
 
+			T::StringStart => input.parse::<InterpStr>().map(Self::InterpStr)?,
+
 			T::Ident => input.parse::<Ident>().map(Self::Ident)?,
 
 			T::Func => input.parse::<Func>().map(|f| Self::Func(Box::new(f)))?,
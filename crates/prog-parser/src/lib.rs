@@ -33,3 +33,86 @@ pub trait Parse<'src>: Sized + ASTNode<'src> {
 pub trait ParsePrecedence<'src>: Parse<'src> {
 	fn parse_precedence(input: &ParseStream<'src, '_>, precedence: u8) -> ParseResult<'src, Self>;
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn parse_recovering(source: &str) -> (ast::Program<'_>, Vec<ParseError<'_>>) {
+		let ts = prog_lexer::lex(source, "<stdin>").unwrap();
+		ast::Program::parse_recovering(&ParseStream::new(&ts))
+	}
+
+	fn is_error(stmt: &ast::Stmt) -> bool { matches!(stmt, ast::Stmt::Error(_)) }
+
+	#[test]
+	fn test_no_errors() {
+		let (program, errors) = parse_recovering("def a = 1\nprint(a)");
+
+		assert!(errors.is_empty());
+		assert_eq!(program.stmts.len(), 2);
+	}
+
+	#[test]
+	fn test_top_level_recovery() {
+		let (program, errors) = parse_recovering("def a = 1 +\ndef b = 2\nprint(a b)\nprint(b)");
+		let stmts = program.stmts.iter().map(is_error).collect::<Vec<_>>();
+
+		assert_eq!(errors.len(), 2);
+		assert_eq!(stmts, [true, false, true, false]);
+	}
+
+	#[test]
+	fn test_block_recovery() {
+		let (program, errors) = parse_recovering(
+			"def f = func() do\n\tdef x = )\n\treturn 1\nend\nif f() then\n\tprint(\nelse\n\tprint(2)\nend"
+		);
+
+		assert_eq!(errors.len(), 2);
+		assert!(!program.stmts.iter().any(is_error));
+	}
+
+	#[test]
+	fn test_stray_end() {
+		let (program, errors) = parse_recovering("end end\nprint(1)");
+
+		assert_eq!(errors.len(), 2);
+		assert_eq!(program.stmts.len(), 3);
+	}
+
+	#[test]
+	fn test_errors_in_class() {
+		let (program, errors) = parse_recovering(
+			"class A\n\tdef x = )\n\tprint(1)\n\tdef y = 2\nend\nif a the\n\tprint(\nend\nprint(a b)"
+		);
+
+		let positions = errors
+			.iter()
+			.map(|e| e.span.position().start())
+			.collect::<Vec<_>>();
+
+		// The `end` of a block which has been skipped is not reported on its own
+		assert_eq!(positions, [17, 20, 49, 65]);
+
+		let ast::Stmt::ClassDef(ref class) = program.stmts[0] else {
+			panic!("expected a class definition");
+		};
+		assert_eq!(class.fields.len(), 1);
+		assert_eq!(program.stmts.len(), 3);
+	}
+
+	#[test]
+	fn test_errors_in_failed_stmt() {
+		// The error inside of the block is kept when the whole statement fails on the missing `end`
+		let (_, errors) = parse_recovering("do\n\tdef x = )\n\tprint(1)");
+		assert_eq!(errors.len(), 2);
+	}
+
+	#[test]
+	fn test_first_error() {
+		let ts = prog_lexer::lex("print(\ndef x = )", "<stdin>").unwrap();
+		let error = ParseStream::new(&ts).parse::<ast::Program>().unwrap_err();
+
+		assert_eq!(error.span.position().start(), 0);
+	}
+}
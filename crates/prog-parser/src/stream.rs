@@ -1,4 +1,5 @@
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
 
 use prog_lexer::{Token, TokenKind};
 
@@ -8,7 +9,9 @@ use crate::{error, ParseError, ParseErrorKind, ParseResult};
 pub struct ParseStream<'src, 'buf> {
 	buffer: &'buf [Token<'src>],
 	/// Current buffer index position.
-	cursor: Cell<usize>
+	cursor: Cell<usize>,
+	/// Errors which have been recovered from, shared between the stream and its forks.
+	errors: Rc<RefCell<Vec<ParseError<'src>>>>
 }
 
 impl<'src, 'buf> ParseStream<'src, 'buf> {
@@ -21,7 +24,8 @@ impl<'src, 'buf> ParseStream<'src, 'buf> {
 	pub fn from_buffer(buffer: &'buf [Token<'src>]) -> Self {
 		Self {
 			buffer,
-			cursor: Cell::new(0)
+			cursor: Cell::new(0),
+			errors: Rc::new(RefCell::new(vec![]))
 		}
 	}
 }
@@ -79,22 +83,31 @@ where
 	///
 	/// The stream is forked before calling the `parse` function, and only if the
 	/// parse is successful, the main stream cursor will be advanced, thus *consuming*
-	/// the parsed tokens.
+	/// the parsed tokens. Otherwise, the errors reported by the fork are discarded as well.
 	pub fn try_parse_with<T, F>(&'_ self, parse: F) -> ParseResult<'src, T>
 	where
 		T: crate::Parse<'src>,
 		F: FnOnce(&Self) -> ParseResult<'src, T>
 	{
 		let fork = self.fork();
+		let reported = self.errors.borrow().len();
 		let result = parse(&fork);
 
 		if result.is_ok() {
 			self.set_cursor(fork.cursor());
+		} else {
+			self.errors.borrow_mut().truncate(reported);
 		}
 
 		result
 	}
 
+	/// Records an error which the parser has recovered from.
+	pub(crate) fn report(&self, error: ParseError<'src>) { self.errors.borrow_mut().push(error); }
+
+	/// Returns all of the errors which the parser has recovered from so far.
+	pub fn errors(&self) -> Vec<ParseError<'src>> { self.errors.borrow().clone() }
+
 	/// Peeks at the current token in the stream without advancing the cursor.
 	///
 	/// Returns `Some(Token)` if a token is available, or `None` if the
@@ -224,7 +237,8 @@ where
 	pub fn fork(&'_ self) -> Self {
 		Self {
 			buffer: self.buffer,
-			cursor: self.cursor.clone()
+			cursor: self.cursor.clone(),
+			errors: Rc::clone(&self.errors)
 		}
 	}
 }
@@ -100,6 +100,8 @@ def_token!(pub Return);
 def_token!(pub While);
 def_token!(pub Break);
 def_token!(pub Continue);
+def_token!(pub For);
+def_token!(pub In);
 def_token!(pub If);
 def_token!(pub Then);
 def_token!(pub ElseIf);
@@ -109,13 +111,21 @@ def_token!(pub And);
 def_token!(pub Or);
 def_token!(pub Not);
 def_token!(pub Class);
+def_token!(pub Extends);
 def_token!(pub Extern);
+def_token!(pub Throw);
+def_token!(pub Try);
+def_token!(pub Catch);
+def_token!(pub Finally);
 
 def_token!(pub Ident);
 // Comments are ignored
 // def_token!(pub Comment);
 def_token!(pub Number);
 def_token!(pub String);
+def_token!(pub StringStart);
+def_token!(pub StringMiddle);
+def_token!(pub StringEnd);
 
 def_token!(pub Plus);
 def_token!(pub Minus);
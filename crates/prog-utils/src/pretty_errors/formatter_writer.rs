@@ -23,20 +23,17 @@ impl io::Write for FormatterWriter<'_, '_> {
 	fn flush(&mut self) -> io::Result<()> {
 		use std::str::from_utf8;
 
-		use io::{Error, ErrorKind};
+		use io::Error;
 
 		match from_utf8(&self.buffer) {
 			Ok(s) => {
 				self.formatter
 					.write_str(s)
-					.map_err(|e| Error::new(ErrorKind::Other, e))
+					.map_err(Error::other)
 			}
 
 			Err(_) => {
-				Err(Error::new(
-					ErrorKind::Other,
-					"Failed to convert buffer to string"
-				))
+				Err(Error::other("Failed to convert buffer to string"))
 			}
 		}?;
 
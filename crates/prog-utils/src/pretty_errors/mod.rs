@@ -1,12 +1,16 @@
 mod formatter_writer;
+mod source_cache;
 mod span;
+mod trace;
 
 use formatter_writer::FormatterWriter;
+use source_cache::SourceCache;
 pub use span::{Position, Span};
+pub use trace::TraceFrame;
 
 use std::{fmt, io};
 
-use ariadne::{ColorGenerator, Label, Report, ReportKind, Source};
+use ariadne::{ColorGenerator, Fmt, Label, Report, ReportBuilder, ReportKind};
 
 /// Initializes a color generator with a specific initial state.
 pub fn color_generator() -> ColorGenerator {
@@ -31,7 +35,7 @@ pub trait AriadneCompatible<'s> {
 
 	fn labels(&self, span: Span<'s>) -> Vec<Label<Span<'s>>>;
 
-	fn create_report(&self, span: Span<'s>) -> Report<Span<'s>> {
+	fn build_report(&self, span: Span<'s>) -> ReportBuilder<'_, Span<'s>> {
 		let mut report = Report::build(ReportKind::Error, span);
 		report.set_message(self.message());
 		report.add_labels(self.labels(span));
@@ -40,18 +44,30 @@ pub trait AriadneCompatible<'s> {
 			report.set_note(note);
 		}
 
-		report.finish()
+		report
+	}
+
+	fn create_report(&self, span: Span<'s>) -> Report<'_, Span<'s>> {
+		self.build_report(span).finish()
 	}
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct PrettyError<'s, Kind: PrettyErrorKind<'s>> {
 	pub span: Span<'s>,
-	pub kind: Kind
+	pub kind: Kind,
+	/// Calls which were in progress when the error was raised, the outermost one first.
+	pub trace: Vec<TraceFrame<'s>>
 }
 
 impl<'s, Kind: PrettyErrorKind<'s>> PrettyError<'s, Kind> {
-	pub fn new(span: Span<'s>, kind: Kind) -> Self { Self { span, kind } }
+	pub fn new(span: Span<'s>, kind: Kind) -> Self {
+		Self {
+			span,
+			kind,
+			trace: vec![]
+		}
+	}
 
 	pub fn new_unspanned(kind: Kind) -> Self {
 		let position = Position::new(0, 0);
@@ -65,10 +81,59 @@ impl<'s, Kind: PrettyErrorKind<'s>> PrettyError<'s, Kind> {
 		Self::new(span, kind)
 	}
 
-	fn create_report(&self) -> Report<Span<'s>> { self.kind.create_report(self.span) }
+	pub fn with_trace(mut self, trace: Vec<TraceFrame<'s>>) -> Self {
+		self.trace = trace;
+		self
+	}
+
+	fn create_report(&self) -> Report<'_, Span<'s>> {
+		let mut report = self.kind.build_report(self.span);
+		let mut colors = color_generator();
+
+		// Recursive calls repeat the same frames, which are labelled once along with their count
+		let mut frames: Vec<(usize, &TraceFrame<'s>, usize)> = vec![];
+
+		// The innermost call is listed first, right after the labels of the error itself
+		for (n, frame) in self.trace.iter().rev().enumerate() {
+			let seen = frames
+				.iter_mut()
+				.find(|(_, seen, _)| seen.span == frame.span);
+
+			match seen {
+				Some((_, _, count)) => *count += 1,
+				None => frames.push((n, frame, 1))
+			}
+		}
+
+		for (order, (n, frame, count)) in frames.into_iter().enumerate() {
+			let color = colors.next();
+
+			let mut message = format!("#{n} in call to `{}`", (&frame.name).fg(color));
+			if count > 1 {
+				message.push_str(&format!(", repeated {count} times"));
+			}
+
+			report.add_label(
+				Label::new(frame.span)
+					.with_message(message)
+					.with_color(color)
+					.with_order(order as i32 + 1)
+			);
+		}
+
+		report.finish()
+	}
+
+	/// Sources of all the files which the error and its trace point into.
+	fn get_cache(&self) -> SourceCache<'s> {
+		let mut cache = SourceCache::new();
+		cache.insert(self.span.file(), self.span.source());
+
+		for frame in &self.trace {
+			cache.insert(frame.span.file(), frame.span.source());
+		}
 
-	fn get_cache(&self) -> (&str, Source<&str>) {
-		(self.span.file(), Source::from(self.span.source()))
+		cache
 	}
 
 	pub fn eprint(&self) {
@@ -101,10 +166,11 @@ impl<'s, Kind: PrettyErrorKind<'s>> serde::Serialize for PrettyError<'s, Kind> {
 	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
 		use serde::ser::SerializeStruct;
 
-		let mut s = serializer.serialize_struct("PrettyError", 3)?;
+		let mut s = serializer.serialize_struct("PrettyError", 4)?;
 		s.serialize_field("message", &self.kind.message())?;
 		s.serialize_field("span", &self.span)?;
 		s.serialize_field("kind", &self.kind)?;
+		s.serialize_field("trace", &self.trace)?;
 
 		s.end()
 	}
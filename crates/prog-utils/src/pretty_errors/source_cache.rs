@@ -0,0 +1,34 @@
+use std::fmt;
+
+use ariadne::{Cache, Source};
+
+/// Sources of the files which a report points into, by their names.
+pub(crate) struct SourceCache<'s> {
+	sources: Vec<(&'s str, Source<&'s str>)>
+}
+
+impl<'s> SourceCache<'s> {
+	pub fn new() -> Self { Self { sources: vec![] } }
+
+	pub fn insert(&mut self, file: &'s str, source: &'s str) {
+		if !self.sources.iter().any(|(name, _)| *name == file) {
+			self.sources.push((file, Source::from(source)));
+		}
+	}
+}
+
+impl<'s> Cache<&'s str> for SourceCache<'s> {
+	type Storage = &'s str;
+
+	fn fetch(&mut self, id: &&'s str) -> Result<&Source<&'s str>, Box<dyn fmt::Debug + '_>> {
+		self.sources
+			.iter()
+			.find(|(name, _)| name == id)
+			.map(|(_, source)| source)
+			.ok_or_else(|| Box::new(format!("Failed to fetch source `{id}`")) as _)
+	}
+
+	fn display<'a>(&self, id: &'a &'s str) -> Option<Box<dyn fmt::Display + 'a>> {
+		Some(Box::new(id))
+	}
+}
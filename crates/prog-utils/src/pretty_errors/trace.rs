@@ -0,0 +1,15 @@
+use super::Span;
+
+/// Call which was in progress when an error was raised.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TraceFrame<'s> {
+	/// Source text of the called expression, `object.method` in `object.method(...)`.
+	pub name: String,
+	/// Span of the whole call.
+	pub span: Span<'s>
+}
+
+impl<'s> TraceFrame<'s> {
+	pub fn new(name: String, span: Span<'s>) -> Self { Self { name, span } }
+}
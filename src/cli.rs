@@ -1,4 +1,4 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
 /// Default input file path
 #[cfg(debug_assertions)]
@@ -7,11 +7,59 @@ pub const DEFAULT_INPUT_FP: &str = "input.prog";
 #[derive(Debug, Parser)]
 #[clap(author, version, about)]
 pub struct Cli {
-	/// Target file path
-	#[cfg_attr(debug_assertions, arg(default_value = DEFAULT_INPUT_FP))]
-	pub file_path: String,
+	#[command(subcommand)]
+	pub command: Command
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+	/// Evaluate a file or an inline script
+	Run {
+		/// Target file path
+		#[cfg_attr(debug_assertions, arg(default_value = DEFAULT_INPUT_FP))]
+		#[cfg_attr(not(debug_assertions), arg(required_unless_present = "eval"))]
+		file_path: Option<String>,
+
+		/// Evaluate the provided code instead of a file
+		#[arg(long, short, value_name = "CODE", conflicts_with = "file_path")]
+		eval: Option<String>,
+
+		/// Compile the program into bytecode and run it on the virtual machine
+		#[arg(long)]
+		vm: bool
+	},
+
+	/// Lex, parse and resolve the variables of a file without evaluating it, reporting the errors
+	Check {
+		/// Target file path
+		file_path: String
+	},
+
+	/// Format files in place, preserving their comments
+	Fmt {
+		/// Target file paths
+		#[arg(required = true)]
+		file_paths: Vec<String>,
+
+		/// Only report the files which are not formatted, without modifying them
+		#[arg(long)]
+		check: bool
+	},
+
+	/// Print the tokens of a file as JSON
+	#[cfg(feature = "serde")]
+	Tokens {
+		/// Target file path
+		file_path: String
+	},
+
+	/// Print the syntax tree of a file as JSON
+	#[cfg(feature = "serde")]
+	Ast {
+		/// Target file path
+		file_path: String
+	},
 
-	/// Enable debug information
-	#[arg(long, short, default_value_t = false)]
-	pub debug: bool
+	/// Start an interactive session
+	Repl
 }
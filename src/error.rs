@@ -1,8 +1,12 @@
 use std::fmt::{self, Debug, Display};
 
+#[allow(clippy::large_enum_variant)]
 pub enum ProgError<'s> {
 	Lex(prog_lexer::LexError<'s>),
-	Parse(prog_parser::ParseError<'s>),
+	/// All of the errors found in a file, in the order of appearance.
+	Parse(Vec<prog_parser::ParseError<'s>>),
+	/// All of the variables of a file which fail to resolve, in the order of appearance.
+	Resolve(Vec<prog_interpreter::InterpretError<'s>>),
 	Interpret(prog_interpreter::InterpretError<'s>)
 }
 
@@ -11,18 +15,31 @@ impl<'s> From<prog_lexer::LexError<'s>> for ProgError<'s> {
 }
 
 impl<'s> From<prog_parser::ParseError<'s>> for ProgError<'s> {
-	fn from(err: prog_parser::ParseError<'s>) -> Self { Self::Parse(err) }
+	fn from(err: prog_parser::ParseError<'s>) -> Self { Self::Parse(vec![err]) }
+}
+
+impl<'s> From<Vec<prog_parser::ParseError<'s>>> for ProgError<'s> {
+	fn from(errors: Vec<prog_parser::ParseError<'s>>) -> Self { Self::Parse(errors) }
 }
 
 impl<'s> From<prog_interpreter::InterpretError<'s>> for ProgError<'s> {
 	fn from(err: prog_interpreter::InterpretError<'s>) -> Self { Self::Interpret(err) }
 }
 
+impl<'s> From<Vec<prog_interpreter::InterpretError<'s>>> for ProgError<'s> {
+	fn from(errors: Vec<prog_interpreter::InterpretError<'s>>) -> Self { Self::Resolve(errors) }
+}
+
 impl Display for ProgError<'_> {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		match self {
 			Self::Lex(err) => err as &dyn Display,
-			Self::Parse(err) => err as &dyn Display,
+			Self::Parse(errors) => {
+				return errors.iter().try_for_each(|err| write!(f, "{err}"));
+			}
+			Self::Resolve(errors) => {
+				return errors.iter().try_for_each(|err| write!(f, "{err}"));
+			}
 			Self::Interpret(err) => err as &dyn Display
 		}
 		.fmt(f)
@@ -33,7 +50,8 @@ impl Debug for ProgError<'_> {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		match self {
 			Self::Lex(err) => err as &dyn Debug,
-			Self::Parse(err) => err as &dyn Debug,
+			Self::Parse(errors) => errors as &dyn Debug,
+			Self::Resolve(errors) => errors as &dyn Debug,
 			Self::Interpret(err) => err as &dyn Debug
 		}
 		.fmt(f)
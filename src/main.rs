@@ -1,45 +1,205 @@
+#![allow(clippy::result_large_err)]
+
 pub mod cli;
 mod error;
+mod repl;
+
+use std::process::ExitCode;
 
-use cli::Cli;
+use cli::{Cli, Command};
 use error::ProgError;
 
 use clap::Parser;
-use prog_interpreter::{Interpreter, ValueKind};
-use prog_utils::read_file;
+use prog_interpreter::{Backend, Interpreter, ValueKind};
+use prog_parser::{ast, ParseStream};
+
+/// File name which is displayed in the reports of errors occurring in the code passed with `run -e`.
+const EVAL_FILE_NAME: &str = "<eval>";
+
+/// Size of the native stack which the commands are executed on.
+///
+/// Every call in a program is evaluated recursively, so the stack has to fit
+/// [`prog_interpreter::InterpreterBuilder::DEFAULT_MAX_CALL_DEPTH`] nested calls even in unoptimized builds.
+const STACK_SIZE: usize = 256 * 1024 * 1024;
+
+/// Parses the whole program, failing with all of the errors found in it.
+pub(crate) fn parse<'s>(
+	ts: &prog_lexer::TokenStream<'s>
+) -> Result<ast::Program<'s>, ProgError<'s>> {
+	let (program, errors) = ast::Program::parse_recovering(&ParseStream::new(ts));
+
+	if !errors.is_empty() {
+		return Err(errors.into());
+	}
+
+	Ok(program)
+}
+
+fn run<'s>(source: &'s str, file: &'s str, backend: Backend) -> Result<(), ProgError<'s>> {
+	let ts = prog_lexer::lex(source, file)?;
+	let ast = parse(&ts)?;
+
+	let mut interpreter = Interpreter::builder().backend(backend).build();
+	let value = interpreter.evaluate(ast)?;
+
+	if value.kind() != ValueKind::None {
+		println!("{value}");
+	}
+
+	Ok(())
+}
+
+fn check<'s>(source: &'s str, file: &'s str) -> Result<(), ProgError<'s>> {
+	let ts = prog_lexer::lex(source, file)?;
+	let ast = parse(&ts)?;
+
+	let errors = Interpreter::new().check(&ast);
+	if !errors.is_empty() {
+		return Err(errors.into());
+	}
+
+	Ok(())
+}
+
+fn format<'s>(source: &'s str, file: &'s str) -> Result<String, ProgError<'s>> {
+	let ts = prog_lexer::lex(source, file)?;
+	let ast = parse(&ts)?;
+
+	let ts = prog_lexer::lex_with_comments(source, file)?;
+	Ok(prog_fmt::format(&ast, &ts))
+}
+
+/// Formats the files in place, or, if `check` is set, reports the ones which are not formatted.
+fn format_files(file_paths: &[String], check: bool) -> ExitCode {
+	let mut exit_code = ExitCode::SUCCESS;
+
+	for file_path in file_paths {
+		let source = match std::fs::read_to_string(file_path) {
+			Ok(source) => source.replace("\r\n", "\n"),
+			Err(err) => {
+				eprintln!("Failed to read file `{file_path}`: {err}");
+				exit_code = ExitCode::FAILURE;
+				continue;
+			}
+		};
+
+		let formatted = match format(&source, file_path) {
+			Ok(formatted) => formatted,
+			Err(err) => {
+				eprintln!("{err}");
+				exit_code = ExitCode::FAILURE;
+				continue;
+			}
+		};
 
-fn evaluate_file(file_path: String, _debug: bool) {
-	use prog_parser::ast;
+		if formatted == source {
+			continue;
+		}
+
+		if check {
+			println!("File `{file_path}` is not formatted");
+			exit_code = ExitCode::FAILURE;
+		} else if let Err(err) = std::fs::write(file_path, formatted) {
+			eprintln!("Failed to write file `{file_path}`: {err}");
+			exit_code = ExitCode::FAILURE;
+		}
+	}
+
+	exit_code
+}
+
+#[cfg(feature = "serde")]
+fn dump_tokens<'s>(source: &'s str, file: &'s str) -> Result<(), ProgError<'s>> {
+	let ts = prog_lexer::lex(source, file)?;
+
+	let json = serde_json::to_string_pretty(ts.buffer()).expect("failed to serialize tokens");
+	println!("{json}");
+
+	Ok(())
+}
+
+#[cfg(feature = "serde")]
+fn dump_ast<'s>(source: &'s str, file: &'s str) -> Result<(), ProgError<'s>> {
+	let ts = prog_lexer::lex(source, file)?;
+	let (ast, errors) = ast::Program::parse_recovering(&ParseStream::new(&ts));
+
+	// The partial syntax tree is printed even if it has errors
+	let json = serde_json::to_string_pretty(&ast).expect("failed to serialize the syntax tree");
+	println!("{json}");
+
+	if !errors.is_empty() {
+		return Err(errors.into());
+	}
 
-	let contents = read_file(&file_path);
-	let ts = match prog_lexer::lex(&contents, &file_path).map_err(ProgError::Lex) {
-		Ok(ts) => ts,
+	Ok(())
+}
+
+/// Runs the `command` over the `source`, reporting its error and mapping the outcome to an exit code.
+fn execute<F>(source: &str, file: &str, command: F) -> ExitCode
+where
+	F: for<'s> FnOnce(&'s str, &'s str) -> Result<(), ProgError<'s>>
+{
+	match command(source, file) {
+		Ok(()) => ExitCode::SUCCESS,
 		Err(err) => {
 			eprintln!("{err}");
-			return;
+			ExitCode::FAILURE
 		}
-	};
+	}
+}
 
-	let ps = prog_parser::ParseStream::new(&ts);
-	let parse_result = ps.parse::<ast::Program>().map_err(ProgError::Parse);
-	let ast = match parse_result {
-		Ok(ast) => ast,
+/// Reads the file at `file_path` and runs the `command` over its contents.
+fn execute_file<F>(file_path: &str, command: F) -> ExitCode
+where
+	F: for<'s> FnOnce(&'s str, &'s str) -> Result<(), ProgError<'s>>
+{
+	match std::fs::read_to_string(file_path) {
+		Ok(source) => execute(&source.replace("\r\n", "\n"), file_path, command),
 		Err(err) => {
-			eprintln!("{err}");
-			return;
+			eprintln!("Failed to read file `{file_path}`: {err}");
+			ExitCode::FAILURE
 		}
-	};
+	}
+}
 
-	let mut interpreter = Interpreter::new();
-	match interpreter.evaluate(ast).map_err(ProgError::Interpret) {
-		Ok(val) if val.kind() != ValueKind::None => println!("{val}"),
-		Err(err) => eprintln!("{err}"),
+fn main() -> ExitCode {
+	let Cli { command } = Cli::parse();
 
-		_ => ()
-	};
+	std::thread::Builder::new()
+		.stack_size(STACK_SIZE)
+		.spawn(move || execute_command(command))
+		.expect("Failed to spawn the main thread")
+		.join()
+		.unwrap_or_else(|panic| std::panic::resume_unwind(panic))
 }
 
-fn main() {
-	let Cli { file_path, debug } = Cli::parse();
-	evaluate_file(file_path, debug);
+fn execute_command(command: Command) -> ExitCode {
+	match command {
+		Command::Run {
+			file_path,
+			eval,
+			vm
+		} => {
+			let backend = if vm { Backend::Vm } else { Backend::TreeWalker };
+
+			match (eval, file_path) {
+				(Some(code), _) => execute(&code, EVAL_FILE_NAME, |s, f| run(s, f, backend)),
+				(None, Some(file_path)) => execute_file(&file_path, |s, f| run(s, f, backend)),
+				(None, None) => unreachable!("either a file path or the code is required by clap")
+			}
+		}
+
+		Command::Check { file_path } => execute_file(&file_path, check),
+		Command::Fmt { file_paths, check } => format_files(&file_paths, check),
+
+		#[cfg(feature = "serde")]
+		Command::Tokens { file_path } => execute_file(&file_path, dump_tokens),
+		#[cfg(feature = "serde")]
+		Command::Ast { file_path } => execute_file(&file_path, dump_ast),
+
+		Command::Repl => {
+			repl::start();
+			ExitCode::SUCCESS
+		}
+	}
 }
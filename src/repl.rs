@@ -0,0 +1,239 @@
+use std::io::{self, BufRead, Write};
+
+use prog_interpreter::{Interpreter, Value, ValueKind};
+use prog_lexer::{LexErrorKind, TokenKind};
+use prog_parser::{ast, ParseStream};
+use prog_utils::fmt::Printable;
+
+use crate::error::ProgError;
+
+/// File name which is displayed in the reports of errors occurring in the entered code.
+const FILE_NAME: &str = "<repl>";
+
+const PROMPT: &str = ">> ";
+const PROMPT_CONTINUE: &str = ".. ";
+
+const HELP: &str = "\
+:ctx          print the variables of the current context
+:load <file>  evaluate a file in the current context
+:reset        discard all of the definitions
+:help         print this message
+:quit         exit the REPL
+
+Blocks are submitted once they are closed, an empty line submits the input as is.";
+
+pub fn start() {
+	let mut repl = Repl::new();
+	let mut stdin = io::stdin().lock();
+
+	println!("Type `:help` for the list of commands");
+
+	loop {
+		let prompt = if repl.buffer.is_empty() {
+			PROMPT
+		} else {
+			PROMPT_CONTINUE
+		};
+
+		print!("{prompt}");
+		io::stdout().flush().unwrap();
+
+		let mut line = String::new();
+		match stdin.read_line(&mut line) {
+			Ok(0) | Err(_) => break,
+			Ok(_) => ()
+		}
+
+		let line = line.trim_end_matches(['\r', '\n']);
+
+		if repl.buffer.is_empty() && line.trim_start().starts_with(':') {
+			match repl.command(line.trim()) {
+				Flow::Continue => continue,
+				Flow::Exit => break
+			}
+		}
+
+		repl.push_line(line);
+	}
+
+	println!();
+}
+
+enum Flow {
+	Continue,
+	Exit
+}
+
+struct Repl {
+	interpreter: Interpreter<'static>,
+	/// Lines of the input which has not been submitted yet.
+	buffer: String
+}
+
+impl Repl {
+	fn new() -> Self {
+		Self {
+			interpreter: Interpreter::new(),
+			buffer: String::new()
+		}
+	}
+
+	fn command(&mut self, command: &str) -> Flow {
+		let (name, arg) = command
+			.split_once(char::is_whitespace)
+			.map_or((command, ""), |(name, arg)| (name, arg.trim()));
+
+		match name {
+			":ctx" => print!("{}", self.interpreter.context.str_pretty()),
+			":load" if arg.is_empty() => eprintln!("Usage: :load <file>"),
+			":load" => self.load(arg),
+			":reset" => {
+				self.interpreter = Interpreter::new();
+				println!("Context has been reset");
+			}
+			":help" => println!("{HELP}"),
+			":quit" | ":exit" | ":q" => return Flow::Exit,
+
+			_ => eprintln!("Unknown command `{name}`, type `:help` for the list of commands")
+		}
+
+		Flow::Continue
+	}
+
+	fn push_line(&mut self, line: &str) {
+		if !self.buffer.is_empty() {
+			self.buffer.push('\n');
+		}
+		self.buffer.push_str(line);
+
+		if self.buffer.trim().is_empty() {
+			self.buffer.clear();
+			return;
+		}
+
+		// An empty line submits the input as it is, unless it belongs to a multi-line string
+		let submit_anyway = line.trim().is_empty() && !is_in_string(&self.buffer);
+
+		if !submit_anyway && is_incomplete(&self.buffer) {
+			return;
+		}
+
+		let source = std::mem::take(&mut self.buffer);
+		self.evaluate(source, FILE_NAME);
+	}
+
+	fn load(&mut self, path: &str) {
+		match std::fs::read_to_string(path) {
+			Ok(source) => self.evaluate(source.replace("\r\n", "\n"), path),
+			Err(err) => eprintln!("Failed to read file `{path}`: {err}")
+		}
+	}
+
+	fn evaluate(&mut self, source: String, file: &str) {
+		// Values defined in the REPL keep references to the source for as long as the session lasts
+		let source: &'static str = Box::leak(source.into_boxed_str());
+		let file: &'static str = Box::leak(file.to_owned().into_boxed_str());
+
+		match evaluate_source(&mut self.interpreter, source, file) {
+			Ok(value) if value.kind() != ValueKind::None => println!("{value}"),
+			Err(err) => eprintln!("{err}"),
+
+			_ => ()
+		}
+	}
+}
+
+/// Evaluates a single expression if the whole `source` is one, otherwise evaluates it as a program.
+fn evaluate_source(
+	interpreter: &mut Interpreter<'static>,
+	source: &'static str,
+	file: &'static str
+) -> Result<Value<'static>, ProgError<'static>> {
+	let ts = prog_lexer::lex(source, file)?;
+
+	let ps = ParseStream::new(&ts);
+	if let Ok(expr) = ps.try_parse::<ast::Expr>() {
+		if ps.peek().is_none() && ps.errors().is_empty() {
+			return Ok(interpreter.evaluate(expr)?);
+		}
+	}
+
+	let ast = crate::parse(&ts)?;
+
+	Ok(interpreter.evaluate(ast)?)
+}
+
+/// Checks whether the `source` has unclosed blocks or brackets, meaning that more input is expected.
+///
+/// Lexing errors are left to be reported by the evaluation.
+fn is_incomplete(source: &str) -> bool {
+	let Ok(ts) = prog_lexer::lex(source, FILE_NAME) else {
+		return is_in_string(source);
+	};
+
+	let mut blocks = 0isize;
+	let mut brackets = 0isize;
+
+	for token in ts.buffer() {
+		match token.kind() {
+			TokenKind::Do | TokenKind::If | TokenKind::Class | TokenKind::Try => blocks += 1,
+			TokenKind::End => blocks -= 1,
+
+			TokenKind::LeftParen | TokenKind::LeftBracket | TokenKind::LeftBrace => brackets += 1,
+			TokenKind::RightParen | TokenKind::RightBracket | TokenKind::RightBrace => {
+				brackets -= 1
+			}
+
+			_ => ()
+		}
+	}
+
+	(blocks > 0) || (brackets > 0)
+}
+
+/// Whether the source ends inside of a string, which continues on the next lines.
+///
+/// Strings which are not triple-quoted end with their line, so they are only continued
+/// while the line is being entered.
+fn is_in_string(source: &str) -> bool {
+	match prog_lexer::lex(source, FILE_NAME) {
+		Err(err) => {
+			matches!(err.kind, LexErrorKind::UnterminatedString(_))
+				&& err.span.position().end() == source.len()
+		}
+
+		Ok(_) => false
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::is_incomplete;
+
+	#[test]
+	fn test_complete() {
+		assert!(!is_incomplete("def x = 5"));
+		assert!(!is_incomplete("def f = func() do return 1 end"));
+		assert!(!is_incomplete("class A\n\tdef x = 1\nend"));
+		assert!(!is_incomplete("print(1))"));
+		assert!(!is_incomplete("try\n\tthrow 1\ncatch err\nend"));
+		assert!(!is_incomplete("def s = \"\"\"\nfirst\n\nlast\"\"\""));
+
+		// Strings which are not triple-quoted cannot continue on the next line
+		assert!(!is_incomplete("def s = \"first\nlast\""));
+	}
+
+	#[test]
+	fn test_incomplete() {
+		assert!(is_incomplete("def f = func() do"));
+		assert!(is_incomplete("if x then\n\tprint(x)\nelse"));
+		assert!(is_incomplete("while true do\n\tif x then break end"));
+		assert!(is_incomplete("class A"));
+		assert!(is_incomplete("try"));
+		assert!(is_incomplete("try\n\tthrow 1\ncatch err"));
+		assert!(is_incomplete("def s = \"\"\""));
+		assert!(is_incomplete("def s = \"\"\"\nfirst\n"));
+		assert!(is_incomplete("def s = \"{1 + "));
+		assert!(is_incomplete("def list = [1,"));
+	}
+}
@@ -0,0 +1,31 @@
+const vscode = require("vscode");
+const { LanguageClient } = require("vscode-languageclient/node");
+
+/** @type {LanguageClient | undefined} */
+let client;
+
+/** @param {vscode.ExtensionContext} context */
+function activate(context) {
+	const command = vscode.workspace
+		.getConfiguration("prog-lang")
+		.get("server.path", "prog-lsp");
+
+	client = new LanguageClient(
+		"prog-lang",
+		"Prog Lang Language Server",
+		{ command },
+		{ documentSelector: [{ language: "prog-lang" }] }
+	);
+
+	client.start().catch((err) => {
+		vscode.window.showErrorMessage(`Failed to start \`${command}\`: ${err.message}`);
+	});
+
+	context.subscriptions.push(client);
+}
+
+function deactivate() {
+	return client?.stop();
+}
+
+module.exports = { activate, deactivate };
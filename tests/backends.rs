@@ -0,0 +1,204 @@
+#![allow(clippy::result_large_err)]
+
+use prog_interpreter::{Backend, CaptureIo, InterpretResult, Interpreter, Value};
+
+fn evaluate<'src>(
+	interpreter: &mut Interpreter<'src>,
+	source: &'src str
+) -> InterpretResult<'src, Value<'src>> {
+	let ts = prog_lexer::lex(source, "<test>").unwrap();
+	let ast = prog_parser::ParseStream::new(&ts)
+		.parse::<prog_parser::ast::Program>()
+		.unwrap();
+
+	interpreter.evaluate(ast)
+}
+
+fn interpreter<'i>(backend: Backend) -> Interpreter<'i> {
+	Interpreter::builder()
+		.io(CaptureIo::new())
+		.backend(backend)
+		.fuel(1_000_000)
+		.build()
+}
+
+/// Evaluates the source with both backends, expecting the same outcome and the same fuel spent.
+fn assert_same(source: &str) {
+	let mut walker = interpreter(Backend::TreeWalker);
+	let mut vm = interpreter(Backend::Vm);
+
+	let expected = evaluate(&mut walker, source);
+	let found = evaluate(&mut vm, source);
+
+	match (expected, found) {
+		(Ok(expected), Ok(found)) => assert_eq!(expected, found, "Results differ for `{source}`"),
+
+		(Err(expected), Err(found)) => {
+			assert_eq!(expected.kind.name(), found.kind.name());
+			assert_eq!(expected.span, found.span, "Errors differ for `{source}`");
+			assert_eq!(expected.trace, found.trace, "Traces differ for `{source}`");
+		}
+
+		(expected, found) => panic!("Outcomes differ for `{source}`: {expected:?} and {found:?}")
+	}
+
+	assert_eq!(
+		walker.fuel(),
+		vm.fuel(),
+		"Fuel spent differs for `{source}`"
+	);
+}
+
+#[test]
+fn values() {
+	assert_same("return 1 + 2 * 3");
+	assert_same("return false or \"a\" and none");
+	assert_same("def n = 3 return \"n = {n}, list = {[n, n]}\"");
+	assert_same("def o = { a = 1, b = [1, 2] } o.b[1] = o.a return o");
+	assert_same("def f = func(a, b) do return a - b end return f(5, 2)");
+	assert_same("def f = func() do end return f()");
+}
+
+#[test]
+fn loops() {
+	assert_same(
+		"def total = 0
+		def i = 0
+		while i < 10 do
+			i = i + 1
+			if i == 3 then continue end
+			if i == 8 then break end
+			total = total + i
+		end
+		return total"
+	);
+
+	assert_same(
+		"def keys = []
+		for key, value in { a = 1, b = 2 } do
+			if value == 2 then continue end
+			keys.push(key)
+		end
+		for c in \"abc\" do
+			try
+				if c == \"b\" then break end
+			finally
+				keys.push(c)
+			end
+		end
+		return keys.len()"
+	);
+}
+
+#[test]
+fn classes() {
+	assert_same(
+		"class Point
+			def x
+			def y
+			def __add = func(self, other) do
+				return Point({ x = self.x + other.x, y = self.y + other.y })
+			end
+		end
+		class Named extends Point
+			def name = \"p\"
+		end
+		def p = Point({ x = 1, y = 2 }) + Point({ x = 3, y = 4 })
+		return [p.x, p.y, Named({ x = 0, y = 0 }).name]"
+	);
+}
+
+#[test]
+fn recursion() {
+	let source = "def fib = func(n) do
+			if n <= 1 then return n end
+			def a = fib(n - 1)
+			return a + fib(n - 2)
+		end
+		return [fib(2), fib(5), fib(10)]";
+
+	assert_same(source);
+
+	// Every call has its own arguments and variables, which the nested calls cannot overwrite
+	for backend in [Backend::TreeWalker, Backend::Vm] {
+		let value = evaluate(&mut interpreter(backend), source).unwrap();
+		assert_eq!(value.to_string(), "[1, 5, 55]", "{backend:?}");
+	}
+
+	// Closures created by a call keep seeing the variables of that call
+	assert_same(
+		"def make = func(n) do
+			return func() do return n end
+		end
+		def a = make(1)
+		def b = make(2)
+		return [a(), b()]"
+	);
+}
+
+#[test]
+fn errors() {
+	assert_same("return missing");
+	assert_same("missing = 1");
+	assert_same("def o = { a = 1, a = 2 }");
+	assert_same("class A def a def a end");
+	assert_same("def l = [] l[\"x\"] = 1");
+	assert_same("def f = func(a) do return a end f()");
+	assert_same("def f = func() do return 1() end f()");
+	assert_same("def f = func() do throw 1 end try f() catch e return e end");
+	assert_same("def f = func() do break end f()");
+	assert_same("continue");
+	assert_same("def a = 1 def a = 2");
+	assert_same("def b = a def a = 1");
+	assert_same("def f = func() do return later end f() def later = 1");
+}
+
+#[test]
+fn errors_in_class_bodies() {
+	let sources = [
+		"def f = func() do throw 1 end class A def a = f() end",
+		"class B def b = 1 def b = 2 end"
+	];
+
+	for backend in [Backend::TreeWalker, Backend::Vm] {
+		for source in sources {
+			let mut interpreter = interpreter(backend);
+			evaluate(&mut interpreter, source).unwrap_err();
+
+			// The evaluations which follow are back in the scope the class was defined in
+			evaluate(&mut interpreter, "def after = 1").unwrap();
+			assert!(interpreter.context.get("after").is_some(), "{backend:?}");
+			assert!(interpreter.context.get("self").is_none(), "{backend:?}");
+		}
+	}
+}
+
+#[test]
+fn values_cross_backends() {
+	// Functions created by one backend can be called by the other
+	let mut vm = interpreter(Backend::Vm);
+	let func = evaluate(&mut vm, "return func(a) do return a * 2 end").unwrap();
+
+	let mut walker = interpreter(Backend::TreeWalker);
+	walker.context.insert("double", func);
+
+	let value = evaluate(&mut walker, "return double(21)").unwrap();
+	assert_eq!(value.to_string(), "42");
+}
+
+#[test]
+fn size_limit() {
+	let source = "def l = [] while true do l.push(1) end";
+	let errors = [Backend::TreeWalker, Backend::Vm].map(|backend| {
+		let mut interpreter = Interpreter::builder()
+			.io(CaptureIo::new())
+			.backend(backend)
+			.max_size(100)
+			.build();
+
+		evaluate(&mut interpreter, source).unwrap_err()
+	});
+
+	assert_eq!(errors[0].kind.name(), "SizeLimitExceeded");
+	assert_eq!(errors[0].span, errors[1].span);
+}
@@ -0,0 +1,130 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn prog(args: &[&str]) -> Output {
+	Command::new(env!("CARGO_BIN_EXE_prog-lang"))
+		.args(args)
+		.output()
+		.unwrap()
+}
+
+#[test]
+fn repl_multiline_string() {
+	let mut child = Command::new(env!("CARGO_BIN_EXE_prog-lang"))
+		.arg("repl")
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped())
+		.spawn()
+		.unwrap();
+
+	// The empty line belongs to the string, rather than submitting the input entered so far
+	let input = "def s = \"\"\"\nfirst\n\nlast\"\"\"\nprint(s)\n";
+	child
+		.stdin
+		.take()
+		.unwrap()
+		.write_all(input.as_bytes())
+		.unwrap();
+
+	let output = child.wait_with_output().unwrap();
+	let stdout = String::from_utf8_lossy(&output.stdout);
+	let stderr = String::from_utf8_lossy(&output.stderr);
+
+	assert!(stderr.is_empty(), "{stderr}");
+	assert!(stdout.contains("first\n\nlast\n"));
+}
+
+#[test]
+fn run_inline_script() {
+	let output = prog(&["run", "-e", "print(1 + 2)"]);
+
+	assert!(output.status.success());
+	assert_eq!(String::from_utf8_lossy(&output.stdout), "3\n");
+}
+
+#[test]
+fn run_fails_with_exit_code() {
+	let output = prog(&["run", "-e", "print(undefined_variable)"]);
+
+	assert!(!output.status.success());
+	assert!(!output.stderr.is_empty());
+}
+
+#[test]
+fn run_missing_file() {
+	let output = prog(&["run", "tests/cases/missing.prog"]);
+	assert!(!output.status.success());
+}
+
+#[test]
+fn run_entry_import_cycle() {
+	let output = prog(&["run", "tests/modules/entry.prog"]);
+
+	assert!(!output.status.success());
+	assert_eq!(String::from_utf8_lossy(&output.stdout), "entry\n");
+	assert!(String::from_utf8_lossy(&output.stderr).contains("entry.prog"));
+}
+
+#[test]
+fn check_does_not_evaluate() {
+	let output = prog(&["check", "tests/cases/modules.prog"]);
+
+	assert!(output.status.success());
+	assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn check_reports_unresolved_variables() {
+	let path = std::env::temp_dir().join("prog_lang_check_test.prog");
+	std::fs::write(&path, "def a = 1\ndef a = b\n").unwrap();
+
+	let output = prog(&["check", path.to_str().unwrap()]);
+	let stderr = String::from_utf8_lossy(&output.stderr);
+
+	assert!(!output.status.success());
+	assert!(stderr.contains("cannot redefine variables in the same scope"));
+	assert!(stderr.contains("variable doesn't exist"));
+
+	std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn fmt_check_and_rewrite() {
+	let path = std::env::temp_dir().join("prog_lang_fmt_test.prog");
+	let path_str = path.to_str().unwrap();
+	std::fs::write(&path, "def  a=1+2 // comment\nprint( a )").unwrap();
+
+	let output = prog(&["fmt", "--check", path_str]);
+	assert!(!output.status.success());
+
+	let output = prog(&["fmt", path_str]);
+	assert!(output.status.success());
+	assert_eq!(
+		std::fs::read_to_string(&path).unwrap(),
+		"def a = 1 + 2 // comment\nprint(a)\n"
+	);
+
+	let output = prog(&["fmt", "--check", path_str]);
+	assert!(output.status.success());
+
+	std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn runaway_recursion_fails_gracefully() {
+	let source = "def recurse = func(n) do\n\treturn recurse(n + 1)\nend\nrecurse(0)";
+	let output = prog(&["run", "-e", source]);
+
+	assert_eq!(output.status.code(), Some(1));
+	assert!(String::from_utf8_lossy(&output.stderr).contains("maximum call depth exceeded"));
+}
+
+#[test]
+fn deep_recursion_within_limit() {
+	let source = "def sum = func(n) do\n\tif n == 0 then return 0 end\n\treturn n + sum(n - 1)\nend\nprint(sum(990))";
+	let output = prog(&["run", "-e", source]);
+
+	assert!(output.status.success());
+	assert_eq!(String::from_utf8_lossy(&output.stdout), "490545\n");
+}
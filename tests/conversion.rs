@@ -0,0 +1,147 @@
+#![allow(clippy::result_large_err)]
+
+use std::collections::HashMap;
+
+use prog_interpreter::{
+	Arg, ArgList, CaptureIo, FromValue, Interpreter, IntoValue, Value, ValueKind
+};
+
+#[derive(Debug, PartialEq, FromValue, IntoValue)]
+struct Point {
+	x: f64,
+	y: f64
+}
+
+#[derive(Debug, PartialEq, FromValue, IntoValue)]
+struct Shape {
+	name: String,
+	points: Vec<Point>,
+	closed: Option<bool>
+}
+
+fn evaluate(source: &'static str) -> Value<'static> {
+	let ts = prog_lexer::lex(source, "<test>").unwrap();
+	let ast = prog_parser::ParseStream::new(&ts)
+		.parse::<prog_parser::ast::Program>()
+		.unwrap();
+
+	let mut interpreter = Interpreter::builder().io(CaptureIo::new()).build();
+	interpreter.evaluate(ast).unwrap()
+}
+
+#[test]
+fn primitives() {
+	assert_eq!(f64::from_value(5.5.into_value()).unwrap(), 5.5);
+	assert_eq!(i64::from_value((-3i64).into_value()).unwrap(), -3);
+	assert!(bool::from_value(true.into_value()).unwrap());
+	assert_eq!(String::from_value("hi".into_value()).unwrap(), "hi");
+	assert_eq!(Option::<f64>::from_value(Value::None).unwrap(), None);
+	assert_eq!(None::<f64>.into_value(), Value::None);
+
+	assert!(i64::from_value(1.5.into_value()).is_err());
+	assert!(i64::from_value(9223372036854775808.0.into_value()).is_err());
+	assert_eq!(
+		i64::from_value((i64::MIN as f64).into_value()).unwrap(),
+		i64::MIN
+	);
+	assert!(f64::from_value("5".into_value()).is_err());
+}
+
+#[test]
+fn collections() {
+	let list = evaluate("return [1, 2, 3]");
+	assert_eq!(Vec::<i64>::from_value(list).unwrap(), [1, 2, 3]);
+
+	let obj = evaluate("return { a = 1, b = 2 }");
+	let map = HashMap::<String, f64>::from_value(obj).unwrap();
+	assert_eq!(map["a"], 1.0);
+	assert_eq!(map["b"], 2.0);
+
+	let roundtrip = vec![String::from("a"), String::from("b")].into_value();
+	assert_eq!(Vec::<String>::from_value(roundtrip).unwrap(), ["a", "b"]);
+}
+
+#[test]
+fn derived_from_obj_and_class_instance() {
+	let obj = evaluate(
+		r#"
+		return {
+			name = "triangle",
+			points = [{ x = 0, y = 0 }, { x = 1, y = 0 }, { x = 0, y = 1 }]
+		}
+	"#
+	);
+
+	let shape = Shape::from_value(obj).unwrap();
+	assert_eq!(shape.name, "triangle");
+	assert_eq!(shape.points.len(), 3);
+	assert_eq!(shape.points[1], Point { x: 1.0, y: 0.0 });
+	assert_eq!(shape.closed, None);
+
+	let instance = evaluate(
+		r#"
+		class Point
+			def x
+			def y = 2
+		end
+
+		return Point({ x = 1 })
+	"#
+	);
+
+	assert_eq!(
+		Point::from_value(instance).unwrap(),
+		Point { x: 1.0, y: 2.0 }
+	);
+}
+
+#[test]
+fn derived_into_obj() {
+	let point = Point { x: 3.0, y: 4.0 }.into_value();
+
+	let Value::Obj(ref obj) = point else {
+		panic!("expected an object, found {point}");
+	};
+
+	assert_eq!(obj.get("x"), Some(3.0.into_value()));
+	assert_eq!(Point::from_value(point).unwrap(), Point { x: 3.0, y: 4.0 });
+}
+
+#[test]
+fn failure_reports_path() {
+	let obj =
+		evaluate(r#"return { name = "line", points = [{ x = 0, y = 0 }, { x = "1", y = 0 }] }"#);
+
+	let error = Shape::from_value(obj).unwrap_err();
+	let message = format!("{error:?}");
+
+	assert!(message.contains("points[1].x"), "{message}");
+}
+
+#[test]
+fn arguments_are_converted() {
+	let mut interpreter = Interpreter::builder().io(CaptureIo::new()).build();
+
+	interpreter.register_fn(
+		"length",
+		ArgList::new(vec![Arg::Required("point".into(), ValueKind::Obj)]),
+		|mut data| {
+			let Point { x, y } = data.arg("point")?;
+			Ok((x * x + y * y).sqrt())
+		}
+	);
+
+	let source = r#"
+		def should_panic = extern "should_panic"
+
+		assert_eq(length({ x = 3, y = 4 }), 5)
+		should_panic(func() do length({ x = 3 }) end)
+	"#;
+
+	let ts = prog_lexer::lex(source, "<test>").unwrap();
+	let ast = prog_parser::ParseStream::new(&ts)
+		.parse::<prog_parser::ast::Program>()
+		.unwrap();
+
+	interpreter.evaluate(ast).unwrap();
+}
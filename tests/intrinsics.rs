@@ -0,0 +1,132 @@
+#![allow(clippy::result_large_err)]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use prog_interpreter::{CallbackIo, CaptureIo, ContextFlags, Interpreter, Value};
+
+fn evaluate<'src>(interpreter: &mut Interpreter<'src>, source: &'src str) -> Value<'src> {
+	let ts = prog_lexer::lex(source, "<test>").unwrap();
+	let ast = prog_parser::ParseStream::new(&ts)
+		.parse::<prog_parser::ast::Program>()
+		.unwrap();
+
+	interpreter.evaluate(ast).unwrap()
+}
+
+#[test]
+fn input_reads_scripted_stdin() {
+	let io = CaptureIo::new();
+	io.stdin_mut().extend(b"first\r\nsecond\nthird");
+
+	let mut interpreter = Interpreter::builder().io(io.clone()).build();
+
+	let source = r#"
+		def input = extern "input"
+
+		assert_eq(input("> "), "first")
+		assert_eq(input(), "second")
+		assert_eq(input(), "third")
+		assert_eq(input(), none)
+	"#;
+
+	evaluate(&mut interpreter, source);
+	assert_eq!(*io.stdout(), b"> ");
+}
+
+#[test]
+fn input_disallowed() {
+	let flags = ContextFlags {
+		inputs_allowed: false,
+		..Default::default()
+	};
+
+	let mut interpreter = Interpreter::builder()
+		.io(CaptureIo::new())
+		.flags(flags)
+		.build();
+
+	let source = r#"
+		def input = extern "input"
+		def should_panic = extern "should_panic"
+
+		should_panic(func() do input() end)
+	"#;
+
+	evaluate(&mut interpreter, source);
+}
+
+#[test]
+fn dump_ctx_prints_chain() {
+	let io = CaptureIo::new();
+	let mut interpreter = Interpreter::builder().io(io.clone()).build();
+
+	let source = r#"
+		def dump_ctx = extern "dump_ctx"
+		def x = 5
+
+		def f = func() do
+			def y = "hi"
+			dump_ctx()
+		end
+
+		f()
+	"#;
+
+	evaluate(&mut interpreter, source);
+
+	let stdout = String::from_utf8(io.stdout().clone()).unwrap();
+	assert!(stdout.starts_with("scope #0 {\n\ty = hi\n}\n"));
+	assert!(stdout.contains("\tx = 5\n"));
+}
+
+#[test]
+fn debug_writes_to_stdout() {
+	let io = CaptureIo::new();
+	let mut interpreter = Interpreter::builder().io(io.clone()).build();
+
+	evaluate(&mut interpreter, "print(1)\ndebug(1 + 1)");
+
+	let stdout = String::from_utf8(io.stdout().clone()).unwrap();
+	assert!(stdout.starts_with("1\n[<test>:2:"));
+	assert!(stdout.ends_with("] 1 + 1 = 2\n"));
+	assert!(io.stderr().is_empty());
+}
+
+#[test]
+fn callback_io_is_line_buffered() {
+	let lines = Rc::new(RefCell::new(vec![]));
+	let errors = Rc::new(RefCell::new(vec![]));
+
+	let io = CallbackIo::new(
+		&b""[..],
+		{
+			let lines = Rc::clone(&lines);
+			move |line: &str| lines.borrow_mut().push(line.to_owned())
+		},
+		{
+			let errors = Rc::clone(&errors);
+			move |line: &str| errors.borrow_mut().push(line.to_owned())
+		}
+	);
+
+	let mut interpreter = Interpreter::builder().io(io).build();
+
+	let source = r#"
+		def raw_print = extern "raw_print"
+
+		raw_print("a")
+		raw_print("b")
+		print("c")
+		print("d")
+		debug(5)
+		raw_print("tail")
+	"#;
+
+	evaluate(&mut interpreter, source);
+
+	assert_eq!(lines.borrow()[..2], ["abc", "d"]);
+	assert!(lines.borrow()[2].ends_with("] 5 = 5"));
+	assert_eq!(lines.borrow()[3], "tail");
+	assert!(errors.borrow().is_empty());
+}
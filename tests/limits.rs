@@ -0,0 +1,225 @@
+#![allow(clippy::result_large_err)]
+
+use std::time::{Duration, Instant};
+
+use prog_interpreter::{
+	ArgList, CaptureIo, InterpretErrorKind, InterpretResult, Interpreter, Value
+};
+
+fn evaluate<'src>(
+	interpreter: &mut Interpreter<'src>,
+	source: &'src str
+) -> InterpretResult<'src, Value<'src>> {
+	let ts = prog_lexer::lex(source, "<test>").unwrap();
+	let ast = prog_parser::ParseStream::new(&ts)
+		.parse::<prog_parser::ast::Program>()
+		.unwrap();
+
+	interpreter.evaluate(ast)
+}
+
+const INFINITE_LOOP: &str = "while true do end";
+
+#[test]
+fn fuel() {
+	let mut interpreter = Interpreter::builder()
+		.io(CaptureIo::new())
+		.fuel(1000)
+		.build();
+
+	evaluate(&mut interpreter, "def a = 1 + 2").unwrap();
+	assert!(interpreter.fuel().unwrap() < 1000);
+
+	let error = evaluate(&mut interpreter, INFINITE_LOOP).unwrap_err();
+	assert!(matches!(error.kind, InterpretErrorKind::FuelExhausted(_)));
+	assert_eq!(interpreter.fuel(), Some(0));
+
+	// Refilling the fuel makes the interpreter usable again
+	interpreter.set_fuel(Some(1000));
+	evaluate(&mut interpreter, "def b = 3").unwrap();
+}
+
+#[test]
+fn deadline() {
+	let mut interpreter = Interpreter::builder()
+		.io(CaptureIo::new())
+		.timeout(Duration::from_millis(50))
+		.build();
+
+	let start = Instant::now();
+	let error = evaluate(&mut interpreter, INFINITE_LOOP).unwrap_err();
+
+	assert!(matches!(
+		error.kind,
+		InterpretErrorKind::DeadlineExceeded(_)
+	));
+	assert!(start.elapsed() >= Duration::from_millis(50));
+
+	// Every evaluation gets its own deadline
+	evaluate(&mut interpreter, "def a = 1").unwrap();
+}
+
+#[test]
+fn max_size() {
+	let mut interpreter = Interpreter::builder()
+		.io(CaptureIo::new())
+		.max_size(64)
+		.build();
+
+	let source = r#"
+		def s = "ab"
+		while true do
+			s = s + s
+		end
+	"#;
+
+	let error = evaluate(&mut interpreter, source).unwrap_err();
+	assert!(matches!(
+		error.kind,
+		InterpretErrorKind::SizeLimitExceeded(_)
+	));
+
+	let source = r#"
+		def list = []
+		while true do
+			list.push(1)
+		end
+	"#;
+
+	let error = evaluate(&mut interpreter, source).unwrap_err();
+	assert!(matches!(
+		error.kind,
+		InterpretErrorKind::SizeLimitExceeded(_)
+	));
+
+	evaluate(&mut interpreter, "def list = [1, 2, 3]").unwrap();
+}
+
+#[test]
+fn cancellation() {
+	let mut interpreter = Interpreter::builder().io(CaptureIo::new()).build();
+	let handle = interpreter.cancel_handle();
+
+	let canceller = std::thread::spawn(move || {
+		std::thread::sleep(Duration::from_millis(50));
+		handle.cancel();
+	});
+
+	let error = evaluate(&mut interpreter, INFINITE_LOOP).unwrap_err();
+	assert!(matches!(error.kind, InterpretErrorKind::Cancelled(_)));
+	canceller.join().unwrap();
+
+	// The cancellation only applies to the evaluation in progress
+	assert!(!interpreter.cancel_handle().is_cancelled());
+	evaluate(&mut interpreter, "def a = 1").unwrap();
+}
+
+#[test]
+fn limits_inside_intrinsics() {
+	let mut interpreter = Interpreter::builder()
+		.io(CaptureIo::new())
+		.fuel(100)
+		.build();
+
+	// Every item of a range costs fuel, even though only a single expression is evaluated
+	let error = evaluate(&mut interpreter, "def r = range(1000)").unwrap_err();
+	assert!(matches!(error.kind, InterpretErrorKind::FuelExhausted(_)));
+	assert_eq!(error.span.value(), "range(1000)");
+
+	// The deadline passes while the arguments are evaluated, and is noticed by the range itself
+	let mut interpreter = Interpreter::builder()
+		.io(CaptureIo::new())
+		.timeout(Duration::from_millis(10))
+		.build();
+
+	interpreter.register_fn("wait", ArgList::new(vec![]), |_| {
+		std::thread::sleep(Duration::from_millis(20));
+		Ok(1000)
+	});
+
+	let error = evaluate(&mut interpreter, "def r = range(wait())").unwrap_err();
+	assert!(matches!(
+		error.kind,
+		InterpretErrorKind::DeadlineExceeded(_)
+	));
+	assert_eq!(error.span.value(), "range(wait())");
+
+	let mut interpreter = Interpreter::builder().io(CaptureIo::new()).build();
+	let handle = interpreter.cancel_handle();
+
+	interpreter.register_fn("cancel", ArgList::new(vec![]), move |_| {
+		handle.cancel();
+		Ok(1000)
+	});
+
+	let error = evaluate(&mut interpreter, "def r = range(cancel())").unwrap_err();
+	assert!(matches!(error.kind, InterpretErrorKind::Cancelled(_)));
+	assert_eq!(error.span.value(), "range(cancel())");
+}
+
+#[test]
+fn max_size_inside_intrinsics() {
+	let mut interpreter = Interpreter::builder()
+		.io(CaptureIo::new())
+		.max_size(64)
+		.build();
+
+	let sources = [
+		"def r = range(100)",
+		r#"def s = "".join(range(40))"#,
+		r#"def l = ",,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,,".split(",")"#,
+		r#"def s = "abcdefgh abcdefgh abcdefgh".replace("h", "hhhhhhhhhhhhhhhhhhhhhhhh")"#,
+		r#"def s = "abcdefghabcdefghabcdefghabcdefgh" + "abcdefghabcdefghabcdefghabcdefgh!""#
+	];
+
+	for source in sources {
+		let error = evaluate(&mut interpreter, source).unwrap_err();
+
+		assert_eq!(error.kind.name(), "SizeLimitExceeded", "{source}");
+	}
+
+	evaluate(&mut interpreter, r#"def s = ", ".join(range(10))"#).unwrap();
+}
+
+#[test]
+fn not_catchable_by_scripts() {
+	let mut interpreter = Interpreter::builder()
+		.io(CaptureIo::new())
+		.fuel(1000)
+		.build();
+
+	let source = r#"
+		while true do
+			try
+				while true do end
+			catch err
+			end
+		end
+	"#;
+
+	let error = evaluate(&mut interpreter, source).unwrap_err();
+	assert!(matches!(error.kind, InterpretErrorKind::FuelExhausted(_)));
+
+	// Neither by `should_panic`, which returns the other errors as values
+	let mut interpreter = Interpreter::builder()
+		.io(CaptureIo::new())
+		.max_size(64)
+		.build();
+
+	let source = r#"
+		def should_panic = extern "should_panic"
+		def error = should_panic(func() do
+			def list = []
+			while true do
+				list.push(1)
+			end
+		end)
+		return error.kind
+	"#;
+
+	let error = evaluate(&mut interpreter, source).unwrap_err();
+	assert!(matches!(
+		error.kind,
+		InterpretErrorKind::SizeLimitExceeded(_)
+	));
+}
@@ -0,0 +1,131 @@
+#![allow(clippy::result_large_err)]
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use prog_interpreter::{Arg, ArgList, CaptureIo, InterpretResult, Interpreter, Value, ValueKind};
+
+fn evaluate<'src>(
+	interpreter: &mut Interpreter<'src>,
+	source: &'src str
+) -> InterpretResult<'src, Value<'src>> {
+	let ts = prog_lexer::lex(source, "<test>").unwrap();
+	let ast = prog_parser::ParseStream::new(&ts)
+		.parse::<prog_parser::ast::Program>()
+		.unwrap();
+
+	interpreter.evaluate(ast)
+}
+
+fn interpreter<'i>() -> Interpreter<'i> { Interpreter::builder().io(CaptureIo::new()).build() }
+
+#[test]
+fn typed_arguments() {
+	let mut interpreter = interpreter();
+
+	interpreter.register_fn(
+		"add",
+		ArgList::new(vec![
+			Arg::Required("a".into(), ValueKind::Num),
+			Arg::Required("b".into(), ValueKind::Num),
+		]),
+		|mut data| {
+			let a = data.arg::<f64>("a")?;
+			let b = data.arg::<f64>("b")?;
+
+			Ok(a + b)
+		}
+	);
+
+	interpreter.register_fn(
+		"greet",
+		ArgList::new(vec![
+			Arg::RequiredUntyped("who".into()),
+			Arg::Optional("greeting".into(), ValueKind::Str),
+		]),
+		|mut data| {
+			let who = data.arg::<Value>("who")?;
+			let greeting = data.opt_arg::<String>("greeting")?;
+			let greeting = greeting.as_deref().unwrap_or("hello");
+
+			Ok(format!("{greeting}, {who}"))
+		}
+	);
+
+	interpreter.register_fn(
+		"count",
+		ArgList::new(vec![Arg::Variadic("values".into())]),
+		|mut data| Ok(data.variadic("values").len() as i64)
+	);
+
+	let source = r#"
+		assert_eq(add(2, 3), 5)
+		assert_eq(greet(5), "hello, 5")
+		assert_eq(greet("world", "hi"), "hi, world")
+		assert_eq(count(), 0)
+		assert_eq(count(1, "2", none), 3)
+	"#;
+
+	evaluate(&mut interpreter, source).unwrap();
+}
+
+#[test]
+fn reachable_through_extern() {
+	let mut interpreter = interpreter();
+
+	interpreter.register_fn("answer", ArgList::new_empty(), |_| Ok(42.0));
+
+	let source = r#"
+		def answer_ext = extern "answer"
+
+		assert_eq(answer_ext, answer)
+		assert_eq(answer_ext(), 42)
+	"#;
+
+	evaluate(&mut interpreter, source).unwrap();
+}
+
+#[test]
+fn captures_host_state() {
+	let calls = Rc::new(Cell::new(0));
+	let mut interpreter = interpreter();
+
+	interpreter.register_fn("tick", ArgList::new_empty(), {
+		let calls = Rc::clone(&calls);
+
+		move |_| {
+			calls.set(calls.get() + 1);
+			Ok(())
+		}
+	});
+
+	evaluate(&mut interpreter, "tick()\ntick()\ntick()").unwrap();
+	assert_eq!(calls.get(), 3);
+}
+
+#[test]
+fn errors() {
+	let mut interpreter = interpreter();
+
+	interpreter.register_fn(
+		"fail",
+		ArgList::new(vec![Arg::Required("message".into(), ValueKind::Str)]),
+		|mut data| {
+			let message = data.arg::<String>("message")?;
+			Err::<Value, _>(data.error(message))
+		}
+	);
+
+	let source = r#"
+		def should_panic = extern "should_panic"
+
+		should_panic(func() do fail("oops") end)
+		should_panic(func() do fail(5) end)
+		should_panic(func() do fail() end)
+	"#;
+
+	evaluate(&mut interpreter, source).unwrap();
+
+	let error = evaluate(&mut interpreter, r#"fail("oops")"#).unwrap_err();
+	assert!(format!("{error}").contains("oops"));
+}
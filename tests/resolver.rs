@@ -0,0 +1,168 @@
+#![allow(clippy::result_large_err)]
+
+use prog_interpreter::{Backend, CaptureIo, InterpretResult, Interpreter, Value};
+
+fn parse(source: &str) -> prog_parser::ast::Program<'_> {
+	let ts = prog_lexer::lex(source, "<test>").unwrap();
+	prog_parser::ParseStream::new(&ts)
+		.parse::<prog_parser::ast::Program>()
+		.unwrap()
+}
+
+fn evaluate<'src>(
+	interpreter: &mut Interpreter<'src>,
+	source: &'src str
+) -> InterpretResult<'src, Value<'src>> {
+	interpreter.evaluate(parse(source))
+}
+
+/// Names of the errors found by resolving the source, with the source of their spans.
+fn check(source: &str) -> Vec<(&'static str, &str)> {
+	Interpreter::builder()
+		.io(CaptureIo::new())
+		.build()
+		.check(&parse(source))
+		.iter()
+		.map(|err| (err.kind.name(), err.span.value()))
+		.collect()
+}
+
+#[test]
+fn undefined_variables() {
+	assert_eq!(check("return missing"), [("VarDoesntExist", "missing")]);
+	assert_eq!(check("missing = 1"), [("VarDoesntExist", "missing")]);
+	assert_eq!(
+		check("def f = func() do return a + b end"),
+		[("VarDoesntExist", "a"), ("VarDoesntExist", "b")]
+	);
+
+	// Variables are scoped to their blocks
+	assert_eq!(
+		check("do def a = 1 end return a"),
+		[("VarDoesntExist", "a")]
+	);
+	assert_eq!(
+		check("try throw 1 catch err end return err"),
+		[("VarDoesntExist", "err")]
+	);
+	assert_eq!(
+		check("for i in [1] do end return i"),
+		[("VarDoesntExist", "i")]
+	);
+
+	// Intrinsics are resolved like any other variable
+	assert!(check("print(1)").is_empty());
+}
+
+#[test]
+fn undefined_variables_in_nested_blocks() {
+	// Reported before evaluation, instead of being thrown when the block is reached
+	let source = "try\n\tdef x = missing_variable\ncatch err\nend";
+	assert_eq!(check(source), [("VarDoesntExist", "missing_variable")]);
+
+	let source = "def inner = func() do\n\tdef x = missing_variable\nend\n\ndef outer = func() do\n\tinner()\nend";
+	assert_eq!(check(source), [("VarDoesntExist", "missing_variable")]);
+}
+
+#[test]
+fn catch_binding_scope() {
+	assert!(check("try\n\tthrow 1\ncatch scoped\n\treturn scoped\nend").is_empty());
+
+	// The binding is not visible after the `end` of its branch, not even inside of functions
+	let source = "try\n\tthrow 1\ncatch scoped\nend\n\ndef f = func() do\n\tdef x = scoped\nend";
+	assert_eq!(check(source), [("VarDoesntExist", "scoped")]);
+
+	let source = "try\n\tthrow 1\ncatch scoped\nfinally\n\tdef x = scoped\nend";
+	assert_eq!(check(source), [("VarDoesntExist", "scoped")]);
+}
+
+/// Cases which the scripts of tests/cases and examples used to check while they were evaluated.
+#[test]
+fn script_cases() {
+	// classes.prog and iterators.prog defined the same variable and class twice at the top level
+	let source = "class A end\ndef instance = A()\ndef instance = A()";
+	assert_eq!(check(source), [("VarRedef", "instance")]);
+
+	let source = "class A\n\tdef state = 1\nend\nclass A\n\tdef state = 2\nend";
+	assert_eq!(check(source), [("VarRedef", "A")]);
+
+	// The recursion examples called functions through `self`, which only refers to instances and classes
+	let source = "def sum = func(num) do\n\treturn num + self(num - 1)\nend";
+	assert_eq!(check(source), [("VarDoesntExist", "self")]);
+}
+
+#[test]
+fn redefinitions() {
+	assert_eq!(check("def a = 1 def a = 2"), [("VarRedef", "a")]);
+	assert_eq!(check("def f = func(a, a) do end"), [("VarRedef", "a")]);
+
+	// Classes are variables like any other
+	assert_eq!(check("class A end class A end"), [("VarRedef", "A")]);
+	assert_eq!(check("def A = 1 class A end"), [("VarRedef", "A")]);
+	assert_eq!(check("class A end def A = 1"), [("VarRedef", "A")]);
+
+	// Shadowing in nested scopes is allowed
+	assert!(check("def a = 1 do def a = 2 end").is_empty());
+	assert!(check("def f = func(a) do def g = func(a) do end end").is_empty());
+	assert!(check("class A end do class A end end").is_empty());
+}
+
+#[test]
+fn used_before_definition() {
+	assert_eq!(check("def b = a def a = 1"), [("VarUsedBeforeDef", "a")]);
+	assert_eq!(
+		check("def a = 1 do def b = a def a = 2 end"),
+		[("VarUsedBeforeDef", "a")]
+	);
+
+	// Functions may refer to variables which are defined after them
+	assert!(check("def f = func() do return g() end def g = func() do end").is_empty());
+}
+
+#[test]
+fn errors_prevent_evaluation() {
+	let io = CaptureIo::new();
+	let mut interpreter = Interpreter::builder().io(io.clone()).build();
+
+	let error = evaluate(&mut interpreter, "print(1) def a = missing").unwrap_err();
+	assert_eq!(error.kind.name(), "VarDoesntExist");
+	assert!(io.stdout().is_empty());
+}
+
+#[test]
+fn deferred_references_fail_at_runtime() {
+	for backend in [Backend::TreeWalker, Backend::Vm] {
+		let mut interpreter = Interpreter::builder()
+			.io(CaptureIo::new())
+			.backend(backend)
+			.build();
+
+		let error = evaluate(
+			&mut interpreter,
+			"def f = func() do return later end f() def later = 1"
+		)
+		.unwrap_err();
+
+		assert_eq!(error.kind.name(), "VarDoesntExist");
+		assert_eq!(error.span.value(), "later");
+	}
+}
+
+#[test]
+fn definitions_persist_across_evaluations() {
+	for backend in [Backend::TreeWalker, Backend::Vm] {
+		let mut interpreter = Interpreter::builder()
+			.io(CaptureIo::new())
+			.backend(backend)
+			.build();
+
+		evaluate(&mut interpreter, "def a = 1 def f = func() do return a end").unwrap();
+
+		// Programs evaluated later see the variables of the earlier ones, and may redefine them
+		let value = evaluate(&mut interpreter, "def a = f() + 1 return a").unwrap();
+		assert_eq!(value.to_string(), "2");
+
+		let value = evaluate(&mut interpreter, "a = a * 10 return f()").unwrap();
+		assert_eq!(value.to_string(), "20");
+	}
+}
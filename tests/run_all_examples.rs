@@ -1,24 +1,44 @@
+#![allow(clippy::result_large_err)]
+
 use std::ffi::OsStr;
 use std::fs::ReadDir;
 use std::path::Path;
 
+use prog_interpreter::Backend;
 use prog_lang::ProgResult;
 
 #[allow(unused_variables)]
-fn execute_string<'src>(source: &'src str, file: &'src str) -> ProgResult<'src, ()> {
+fn execute_string<'src>(
+	source: &'src str,
+	file: &'src str,
+	backend: Backend
+) -> ProgResult<'src, ()> {
 	let ts = prog_lexer::lex(source, file)?;
 
 	let ps = prog_parser::ParseStream::new(&ts);
 	let ast = ps.parse::<prog_parser::ast::Program>()?;
 
-	let mut interpreter = prog_interpreter::Interpreter::new();
-	interpreter.context.inner_mut().flags.con_stdout_allowed = false;
+	let mut interpreter = prog_interpreter::Interpreter::builder()
+		.io(prog_interpreter::CaptureIo::new())
+		.backend(backend)
+		.build();
 	interpreter.evaluate(ast)?;
 
 	Ok(())
 }
 
-fn iterate_dir(paths: ReadDir, exclusions: &[&str]) {
+/// Formats the source the same way as the `fmt` subcommand.
+fn format_source(source: &str, file: &str) -> String {
+	let ts = prog_lexer::lex(source, file).expect("Failed to lex file");
+	let ast = prog_parser::ParseStream::new(&ts)
+		.parse::<prog_parser::ast::Program>()
+		.expect("Failed to parse file");
+
+	let ts = prog_lexer::lex_with_comments(source, file).expect("Failed to lex file");
+	prog_fmt::format(&ast, &ts)
+}
+
+fn iterate_dir(paths: ReadDir, exclusions: &[&str], backend: Backend, formatted: bool) {
 	let file_extension = OsStr::new("prog");
 
 	for path in paths {
@@ -27,7 +47,7 @@ fn iterate_dir(paths: ReadDir, exclusions: &[&str]) {
 		if path.is_dir() {
 			let paths = path.read_dir().expect("Failed to read directory");
 
-			iterate_dir(paths, exclusions);
+			iterate_dir(paths, exclusions, backend, formatted);
 			continue;
 		}
 
@@ -43,12 +63,17 @@ fn iterate_dir(paths: ReadDir, exclusions: &[&str]) {
 			continue;
 		}
 
-		println!("Running file {}", path.display());
+		println!("Running file {} with {backend:?}", path.display());
 
 		let path_str = path.to_str().unwrap();
 
-		let source = prog_utils::read_file(path_str);
-		let execution_result = execute_string(&source, path_str);
+		let mut source = prog_utils::read_file(path_str);
+
+		if formatted {
+			source = format_source(&source, path_str);
+		}
+
+		let execution_result = execute_string(&source, path_str, backend);
 
 		assert!(
 			execution_result.is_ok(),
@@ -63,11 +88,24 @@ fn iterate_dir(paths: ReadDir, exclusions: &[&str]) {
 // 	let paths = std::fs::read_dir("./examples").expect("Failed to read directory");
 // 	let exclusions = vec!["mandelbrot_set.prog"];
 //
-// 	iterate_dir(paths, exclusions.as_slice());
+// 	iterate_dir(paths, exclusions.as_slice(), Backend::TreeWalker, false);
 // }
 
 #[test]
 fn run_all_cases() {
 	let paths = std::fs::read_dir("./tests/cases").expect("Failed to read directory");
-	iterate_dir(paths, &[]);
+	iterate_dir(paths, &[], Backend::TreeWalker, false);
+}
+
+#[test]
+fn run_all_cases_on_vm() {
+	let paths = std::fs::read_dir("./tests/cases").expect("Failed to read directory");
+	iterate_dir(paths, &[], Backend::Vm, false);
+}
+
+/// Formatting must not change the outcome of the cases, including the positions which they assert
+#[test]
+fn run_all_formatted_cases() {
+	let paths = std::fs::read_dir("./tests/cases").expect("Failed to read directory");
+	iterate_dir(paths, &[], Backend::TreeWalker, true);
 }
@@ -0,0 +1,122 @@
+#![allow(clippy::result_large_err)]
+
+use prog_interpreter::{CaptureIo, InterpretErrorKind, InterpretResult, Interpreter, Value};
+
+fn evaluate<'src>(
+	interpreter: &mut Interpreter<'src>,
+	source: &'src str
+) -> InterpretResult<'src, Value<'src>> {
+	let ts = prog_lexer::lex(source, "<test>").unwrap();
+	let ast = prog_parser::ParseStream::new(&ts)
+		.parse::<prog_parser::ast::Program>()
+		.unwrap();
+
+	interpreter.evaluate(ast)
+}
+
+fn interpreter<'i>() -> Interpreter<'i> { Interpreter::builder().io(CaptureIo::new()).build() }
+
+const SOURCE: &str = r#"
+class Checker
+	def check = func(self, value) do
+		assert_eq(value, 2)
+	end
+end
+
+def run = func(checker) do
+	checker.check(1)
+end
+
+run(Checker())
+"#;
+
+#[test]
+fn nested_calls() {
+	let mut interpreter = interpreter();
+	let error = evaluate(&mut interpreter, SOURCE).unwrap_err();
+
+	let names = error
+		.trace
+		.iter()
+		.map(|frame| frame.name.as_str())
+		.collect::<Vec<_>>();
+	assert_eq!(names, ["run", "checker.check"]);
+	assert_eq!(error.trace[0].span.value(), "run(Checker())");
+	assert_eq!(error.trace[1].span.value(), "checker.check(1)");
+
+	// Frames are popped even when the calls fail
+	assert!(interpreter.call_stack().is_empty());
+}
+
+#[test]
+fn rendered_frames() {
+	let mut interpreter = interpreter();
+	let error = evaluate(&mut interpreter, SOURCE).unwrap_err();
+
+	let rendered = format!("{error}");
+	assert!(rendered.contains("#0 in call to `"));
+	assert!(rendered.contains("#1 in call to `"));
+}
+
+#[test]
+fn top_level_error() {
+	let mut interpreter = interpreter();
+	let error = evaluate(&mut interpreter, "assert_eq(1, 2)").unwrap_err();
+
+	assert!(error.trace.is_empty());
+}
+
+const RECURSION: &str = r#"
+def recurse = func(n) do
+	return recurse(n + 1)
+end
+
+recurse(0)
+"#;
+
+#[test]
+fn call_depth_limit() {
+	let mut interpreter = Interpreter::builder()
+		.io(CaptureIo::new())
+		.max_call_depth(20)
+		.build();
+
+	let error = evaluate(&mut interpreter, RECURSION).unwrap_err();
+
+	assert!(matches!(
+		error.kind,
+		InterpretErrorKind::CallDepthExceeded(_)
+	));
+	assert_eq!(error.span.value(), "recurse(n + 1)");
+	assert_eq!(error.trace.len(), 20);
+	assert!(interpreter.call_stack().is_empty());
+
+	// The interpreter remains usable after the limit was reached
+	let source = "def f = func() do return 1 end\nassert_eq(f(), 1)";
+	evaluate(&mut interpreter, source).unwrap();
+}
+
+#[test]
+fn call_depth_limit_is_catchable() {
+	let mut interpreter = Interpreter::builder()
+		.io(CaptureIo::new())
+		.max_call_depth(20)
+		.build();
+
+	let source = r#"
+		def recurse = func(n) do
+			return recurse(n + 1)
+		end
+
+		def kind = none
+		try
+			recurse(0)
+		catch err
+			kind = err.kind
+		end
+
+		assert_eq(kind, "CallDepthExceeded")
+	"#;
+
+	evaluate(&mut interpreter, source).unwrap();
+}